@@ -11,6 +11,7 @@ const BUILD_VARIANT: &str = "Linux-Clang-Release";
 
 struct BindgenSpecs {
     header: String,
+    fabrics_header: String,
     includes_dirs: Vec<String>,
 }
 
@@ -29,6 +30,12 @@ fn get_bindgen_specs() -> BindgenSpecs {
             .join("include")
             .to_string_lossy()
             .to_string(),
+        repo_root
+            .join("lib")
+            .join("fabrics")
+            .join("include")
+            .to_string_lossy()
+            .to_string(),
     ];
     if cfg!(not(feature = "mxl-not-built")) {
         let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
@@ -57,6 +64,7 @@ fn get_bindgen_specs() -> BindgenSpecs {
 
     BindgenSpecs {
         header,
+        fabrics_header: "wrapper-fabrics.h".to_string(),
         includes_dirs,
     }
 }
@@ -85,4 +93,27 @@ fn main() {
     bindings
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Could not write bindings");
+
+    // The fabrics header is C++ only, so it is processed separately. Only the items declared in
+    // it are generated, everything it depends on comes from the main bindings above.
+    let fabrics_bindings = bindgen::builder()
+        .clang_args(["-x", "c++", "-std=c++17"])
+        .clang_args(
+            bindgen_specs
+                .includes_dirs
+                .iter()
+                .map(|dir| format!("-I{dir}")),
+        )
+        .header(bindgen_specs.fabrics_header)
+        .allowlist_file(".*/mxl/fabrics\\.h")
+        .allowlist_recursively(false)
+        .derive_default(true)
+        .derive_debug(true)
+        .prepend_enum_name(false)
+        .generate()
+        .unwrap();
+
+    fabrics_bindings
+        .write_to_file(out_path.join("fabrics_bindings.rs"))
+        .expect("Could not write fabrics bindings");
 }
@@ -11,3 +11,10 @@
 // See https://github.com/rust-lang/rust-bindgen/issues/1651.
 #![allow(deref_nullptr)]
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+/// Bindings of `mxl/fabrics.h`. The types shared with the core API live in the crate root.
+pub mod fabrics {
+    use super::*;
+
+    include!(concat!(env!("OUT_DIR"), "/fabrics_bindings.rs"));
+}
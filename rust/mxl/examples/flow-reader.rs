@@ -35,53 +35,48 @@ fn main() -> Result<(), mxl::Error> {
 
     let mxl_api = mxl::load_api(get_mxl_so_path())?;
     let mxl_instance = mxl::MxlInstance::new(mxl_api, &opts.mxl_domain, "")?;
-    let reader = mxl_instance.create_flow_reader(&opts.flow_id)?;
-    let flow_info = reader.get_info()?;
-    if flow_info.config.is_discrete_flow() {
-        if opts.sample_batch_size.is_some() {
-            return Err(mxl::Error::Other(
-                "Sample batch size is only relevant for \"continuous\" flows.".to_owned(),
-            ));
+    match mxl_instance.open_flow(&opts.flow_id)? {
+        mxl::OpenedFlow::Discrete(reader) => {
+            if opts.sample_batch_size.is_some() {
+                return Err(mxl::Error::Other(
+                    "Sample batch size is only relevant for \"continuous\" flows.".to_owned(),
+                ));
+            }
+            read_grains(mxl_instance, reader)
         }
-        read_grains(mxl_instance, reader.to_grain_reader()?, flow_info)
-    } else {
-        read_samples(
-            mxl_instance,
-            reader.to_samples_reader()?,
-            flow_info,
-            opts.sample_batch_size,
-        )
+        mxl::OpenedFlow::Continuous(reader) => read_samples(reader, opts.sample_batch_size),
     }
 }
 
-fn read_grains(
-    mxl_instance: mxl::MxlInstance,
-    reader: mxl::GrainReader,
-    flow_info: mxl::FlowInfo,
-) -> Result<(), mxl::Error> {
-    let rate = flow_info.config.common().grain_rate()?;
-    let current_index = mxl_instance.get_current_index(&rate);
+fn read_grains(mxl_instance: mxl::MxlInstance, reader: mxl::GrainReader) -> Result<(), mxl::Error> {
+    let rate = reader.config_info().common().grain_rate()?;
+    let index = current_index(&mxl_instance, &rate)?;
 
-    info!("Grain rate: {}/{}", rate.numerator, rate.denominator);
+    info!("Grain rate: {rate}");
 
-    for index in current_index.. {
-        let grain_data = reader.get_complete_grain(index, READ_TIMEOUT)?;
-        info!(
-            "Index: {index} Grain data len: {:?}",
-            grain_data.payload.len()
-        );
+    let grains = reader
+        .iter(mxl::StreamStart::Index(index), READ_TIMEOUT)
+        .catch_up(mxl::CatchUp::SkipToHead);
+    let mut dropped_grains = 0;
+    for event in grains {
+        match event? {
+            mxl::GrainEvent::Grain { index, grain } => {
+                info!("Index: {index} Grain data len: {:?}", grain.payload.len());
+            }
+            event @ mxl::GrainEvent::CaughtUp { missed, resumed } => {
+                dropped_grains += event.dropped_grains();
+                warn!(
+                    "Grain {missed} overwritten, skipping to {resumed} ({dropped_grains} grains \
+                     dropped so far)."
+                );
+            }
+        }
     }
-
     Ok(())
 }
 
-fn read_samples(
-    mxl_instance: mxl::MxlInstance,
-    reader: mxl::SamplesReader,
-    flow_info: mxl::FlowInfo,
-    batch_size: Option<u64>,
-) -> Result<(), mxl::Error> {
-    let common_flow_info = flow_info.config.common();
+fn read_samples(reader: mxl::SamplesReader, batch_size: Option<u64>) -> Result<(), mxl::Error> {
+    let common_flow_info = reader.config_info().common();
     let flow_id = common_flow_info.id().to_string();
     let sample_rate = common_flow_info.sample_rate()?;
     let batch_size = if let Some(batch_size) = batch_size {
@@ -96,7 +91,7 @@ fn read_samples(
         }
         batch_size as usize
     } else if common_flow_info.max_commit_batch_size_hint() == 0 {
-        let batch_size = (sample_rate.numerator / (100 * sample_rate.denominator)) as usize;
+        let batch_size = (sample_rate.numerator() / (100 * sample_rate.denominator())) as usize;
         warn!(
             "Writer batch size not available, using fallback value of {}.",
             batch_size
@@ -105,54 +100,46 @@ fn read_samples(
     } else {
         common_flow_info.max_commit_batch_size_hint() as usize
     };
-    let mut read_head = reader.get_runtime_info()?.headIndex;
-    let mut read_head_valid_at = mxl_instance.get_time();
+    let start = reader.head_index()?;
+    let mut cursor = reader
+        .cursor(start, READ_TIMEOUT)?
+        .with_chunk_size(batch_size)?;
     info!(
-        "Will read from flow \"{flow_id}\" with sample rate {}/{}, using batches of size \
-        {batch_size} samples, first batch ending at index {read_head}.",
-        sample_rate.numerator, sample_rate.denominator
+        "Will read from flow \"{flow_id}\" with sample rate {sample_rate}, using batches of size \
+        {batch_size} samples, first batch starting at index {start}."
     );
     loop {
-        let samples_data = reader.get_samples_non_blocking(read_head, batch_size)?;
-        info!(
-            "Read samples for {} channel(s) at index {}.",
-            samples_data.num_of_channels(),
-            read_head
-        );
-        if samples_data.num_of_channels() > 0 {
-            let channel_data = samples_data.channel_data(0)?;
-            info!(
-                "Buffer size for channel 0 is ({}, {}).",
-                channel_data.0.len(),
-                channel_data.1.len()
-            );
-        }
-        // TODO:
-        //      Switch to the recently introduced reading mechanism that waits for data to be
-        //      available.
-        // We will just blindly assume that more data will be available when we need them.
-        // This, of course, does not have to be true, because the write batch size may be larger
-        // than our reading batch size.
-        let next_head = read_head + batch_size as u64;
-        let next_head_timestamp = mxl_instance.index_to_timestamp(next_head, &sample_rate)?;
-        let read_head_timestamp = mxl_instance.index_to_timestamp(read_head, &sample_rate)?;
-        let read_batch_duration = next_head_timestamp - read_head_timestamp;
-        let deadline = std::time::Instant::now() + READ_TIMEOUT;
-        loop {
-            read_head_valid_at += read_batch_duration;
-            let sleep_duration =
-                Duration::from_nanos(read_head_valid_at.saturating_sub(mxl_instance.get_time()));
-            info!("Will sleep for {:?}.", sleep_duration);
-            mxl_instance.sleep_for(sleep_duration);
-            if std::time::Instant::now() >= deadline {
-                warn!("Timeout while waiting for samples at index {}.", next_head);
-                return Err(mxl::Error::Timeout);
-            }
-            let available_head = reader.get_runtime_info()?.headIndex;
-            if available_head >= next_head {
-                break;
+        match cursor.next_chunk(batch_size)? {
+            mxl::CursorEvent::Samples { start, data } => {
+                info!(
+                    "Read samples for {} channel(s) at index {}.",
+                    data.num_of_channels(),
+                    start
+                );
+                if data.num_of_channels() > 0 {
+                    let channel_data = data.channel_data(0)?;
+                    info!(
+                        "Buffer size for channel 0 is ({}, {}).",
+                        channel_data.0.len(),
+                        channel_data.1.len()
+                    );
+                }
             }
+            event @ mxl::CursorEvent::Overrun { resumed, .. } => warn!(
+                "Lost {} samples, resuming at index {}.",
+                event.lost_samples(),
+                resumed
+            ),
         }
-        read_head = next_head;
     }
 }
+
+/// The current index at `rate`, which is only unavailable without a clock.
+fn current_index<I: mxl::MediaIndex>(
+    mxl_instance: &mxl::MxlInstance,
+    rate: &mxl::EditRate<I>,
+) -> Result<I, mxl::Error> {
+    mxl_instance
+        .get_current_index(rate)
+        .ok_or_else(|| mxl::Error::Other("The TAI clock is unavailable.".to_owned()))
+}
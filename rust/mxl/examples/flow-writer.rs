@@ -3,6 +3,8 @@
 
 mod common;
 
+use std::ops::ControlFlow;
+
 use clap::Parser;
 use tracing::info;
 
@@ -27,6 +29,11 @@ pub struct Opts {
     /// flows. If not specified, will more or less fit 10 ms.
     #[arg(long)]
     pub sample_batch_size: Option<u64>,
+
+    /// Offset in grains or samples. Positive values delay the writing (commit in the past),
+    /// negative ones write in the future.
+    #[arg(long, default_value_t = 0, allow_hyphen_values = true)]
+    pub offset: i64,
 }
 
 fn main() -> Result<(), mxl::Error> {
@@ -49,13 +56,19 @@ fn main() -> Result<(), mxl::Error> {
                 "Sample batch size is only relevant for \"continuous\" flows.".to_owned(),
             ));
         }
-        write_grains(mxl_instance, flow_config_info, opts.grain_or_sample_count)
+        write_grains(
+            mxl_instance,
+            flow_config_info,
+            opts.grain_or_sample_count,
+            opts.offset,
+        )
     } else {
         write_samples(
             mxl_instance,
             flow_config_info,
             opts.grain_or_sample_count,
             opts.sample_batch_size,
+            opts.offset,
         )
     }
 }
@@ -64,49 +77,45 @@ pub fn write_grains(
     mxl_instance: mxl::MxlInstance,
     flow_config_info: mxl::FlowConfigInfo,
     grain_count: Option<u64>,
+    offset: i64,
 ) -> Result<(), mxl::Error> {
     let flow_id = flow_config_info.common().id().to_string();
     let grain_rate = flow_config_info.common().grain_rate()?;
-    let mut grain_index = mxl_instance.get_current_index(&grain_rate);
-    info!(
-        "Will write to flow \"{flow_id}\" with grain rate {}/{} starting from index {grain_index}.",
-        grain_rate.numerator, grain_rate.denominator
-    );
-    let writer = mxl_instance
-        .create_flow_writer(flow_id.as_str())?
-        .to_grain_writer()?;
+    info!("Will write to flow \"{flow_id}\" with grain rate {grain_rate} and offset {offset}.");
+    let mut writer = mxl_instance
+        .create_flow_writer_with_config(&flow_config_info)?
+        .to_grain_writer()?
+        .into_paced()?
+        .offset(offset);
 
     let mut remaining_grains = grain_count;
-    loop {
+    writer.run(|grain_index, grain_writer_access| {
         if let Some(count) = remaining_grains {
             if count == 0 {
-                break;
+                return Ok(ControlFlow::Break(()));
             }
             remaining_grains = Some(count - 1);
         }
 
-        let mut grain_writer_access = writer.open_grain(grain_index)?;
         let total_slices = grain_writer_access.total_slices();
         let payload = grain_writer_access.payload_mut();
         let payload_len = payload.len();
         for (i, byte) in payload.iter_mut().enumerate() {
-            *byte = ((i as u64 + grain_index) % 256) as u8;
+            *byte = ((i as u64 + grain_index.value()) % 256) as u8;
         }
-        grain_writer_access.commit(total_slices)?;
-
-        let timestamp = mxl_instance.index_to_timestamp(grain_index + 1, &grain_rate)?;
-        let sleep_duration = mxl_instance.get_duration_until_index(grain_index + 1, &grain_rate)?;
-        info!(
-            "Finished writing {payload_len} bytes ({total_slices} slices) into grain {grain_index}, will sleep \
-             for {:?} until timestamp {timestamp}.",
-            sleep_duration
-        );
-        grain_index += 1;
-        mxl_instance.sleep_for(sleep_duration);
-    }
+        info!("Writing {payload_len} bytes ({total_slices} slices) into grain {grain_index}.");
+        Ok(ControlFlow::Continue(()))
+    })?;
 
-    info!("Finished writing requested number of grains, deleting the flow.");
-    writer.destroy()?;
+    let stats = writer.stats();
+    info!(
+        "Finished writing requested number of grains, {} grains filled as invalid, {} dropped, \
+         mean lateness {:?}, deleting the flow.",
+        stats.filled,
+        stats.dropped,
+        stats.mean_lateness()
+    );
+    writer.into_writer().destroy()?;
     mxl_instance.destroy_flow(flow_id.as_str())?;
     Ok(())
 }
@@ -116,34 +125,34 @@ pub fn write_samples(
     flow_config_info: mxl::FlowConfigInfo,
     sample_count: Option<u64>,
     batch_size: Option<u64>,
+    offset: i64,
 ) -> Result<(), mxl::Error> {
     let flow_id = flow_config_info.common().id().to_string();
     let sample_rate = flow_config_info.common().sample_rate()?;
     let batch_size =
-        batch_size.unwrap_or((sample_rate.numerator / (100 * sample_rate.denominator)) as u64);
-    let mut samples_index = mxl_instance.get_current_index(&sample_rate);
+        batch_size.unwrap_or((sample_rate.numerator() / (100 * sample_rate.denominator())) as u64);
     info!(
-        "Will write to flow \"{flow_id}\" with sample rate {}/{}, using batches of size {batch_size} samples, first batch ending at index {samples_index}.",
-        sample_rate.numerator, sample_rate.denominator
+        "Will write to flow \"{flow_id}\" with sample rate {sample_rate} and offset {offset}, using batches of size {batch_size} samples."
     );
-    let writer = mxl_instance
-        .create_flow_writer(flow_id.as_str())?
-        .to_samples_writer()?;
+    let mut writer = mxl_instance
+        .create_flow_writer_with_config(&flow_config_info)?
+        .to_samples_writer()?
+        .into_paced(batch_size)?
+        .offset(offset);
 
     let mut remaining_samples = sample_count;
-    loop {
+    writer.run(|samples_index, samples_write_access| {
         if let Some(count) = remaining_samples
             && count == 0
         {
-            break;
+            return Ok(ControlFlow::Break(()));
         }
         let samples_to_write = u64::min(batch_size, remaining_samples.unwrap_or(u64::MAX));
         if let Some(count) = remaining_samples {
             remaining_samples = Some(count.saturating_sub(batch_size));
         }
 
-        let mut samples_write_access = writer.open_samples(samples_index, batch_size as usize)?;
-        let mut writing_sample_index = samples_index - batch_size + 1;
+        let mut writing_sample_index = (samples_index - batch_size + 1).value();
         for channel in 0..samples_write_access.channels() {
             let (data_1, data_2) = samples_write_access.channel_data_mut(channel)?;
             for sample in data_1.iter_mut() {
@@ -155,22 +164,19 @@ pub fn write_samples(
                 writing_sample_index += 1;
             }
         }
-        samples_write_access.commit()?;
-
-        let timestamp =
-            mxl_instance.index_to_timestamp(samples_index + batch_size, &sample_rate)?;
-        let sleep_duration =
-            mxl_instance.get_duration_until_index(samples_index + batch_size, &sample_rate)?;
-        info!(
-            "Finished writing {samples_to_write} samples into batch ending with index {samples_index}, will sleep for {:?} until timestamp {timestamp}.",
-            sleep_duration
-        );
-        samples_index += batch_size;
-        mxl_instance.sleep_for(sleep_duration);
-    }
+        info!("Writing {samples_to_write} samples into batch ending with index {samples_index}.");
+        Ok(ControlFlow::Continue(()))
+    })?;
 
-    info!("Finished writing requested number of samples, deleting the flow.");
-    writer.destroy()?;
+    let stats = writer.stats();
+    info!(
+        "Finished writing requested number of samples, {} batches filled with silence, {} \
+         dropped, mean lateness {:?}, deleting the flow.",
+        stats.filled,
+        stats.dropped,
+        stats.mean_lateness()
+    );
+    writer.into_writer().destroy()?;
     mxl_instance.destroy_flow(flow_id.as_str())?;
     Ok(())
 }
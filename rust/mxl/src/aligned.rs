@@ -0,0 +1,250 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Error, GrainData, GrainIndex, GrainRate, GrainReader, Result, SampleIndex, SamplesData,
+    SamplesReader, Timestamp,
+    timing::{self, RateMapping, SampleRing},
+};
+
+/// The reader of a flow, with the mapping from the ticks to its grains or samples.
+enum MemberReader {
+    Grains(GrainReader, RateMapping<GrainIndex, GrainIndex>),
+    Samples(SamplesReader, RateMapping<GrainIndex, SampleIndex>),
+}
+
+struct Member {
+    reader: MemberReader,
+    flow_id: uuid::Uuid,
+}
+
+impl Member {
+    /// The tick the first grain or sample that is not available yet begins in.
+    fn first_incomplete_tick(&self) -> Result<GrainIndex> {
+        match &self.reader {
+            // The head is the last grain committed, but the last sample committed is the one
+            // before the head.
+            MemberReader::Grains(reader, mapping) => {
+                mapping.inverse().containing(reader.head_index()? + 1)
+            }
+            MemberReader::Samples(reader, mapping) => {
+                mapping.inverse().containing(reader.head_index()?)
+            }
+        }
+    }
+}
+
+/// What an `AlignedReader` read from one of its flows for a tick.
+pub enum AlignedData<'a> {
+    /// The grains beginning during the tick, usually a single one when the flow runs at the tick
+    /// rate.
+    Grains(Vec<(GrainIndex, GrainData<'a>)>),
+    /// The samples beginning during the tick, from `start` on.
+    Samples {
+        start: SampleIndex,
+        data: SamplesData<'a>,
+    },
+    /// The grains or samples of the tick were not available before the timeout.
+    Missing,
+    /// The grains or samples of the tick had already been overwritten.
+    Late,
+}
+
+/// The grains or samples of a flow for a tick of an `AlignedReader`.
+pub struct AlignedMember<'a> {
+    pub flow_id: uuid::Uuid,
+    pub data: AlignedData<'a>,
+}
+
+/// The grains and samples of all the flows of an `AlignedReader` covering the same TAI interval.
+pub struct AlignedBundle<'a> {
+    /// Index of the tick, at the rate of the `AlignedReader`.
+    pub index: GrainIndex,
+    /// Beginning of the tick.
+    pub start: Timestamp,
+    /// Beginning of the next tick.
+    pub end: Timestamp,
+    /// The flows in the order they have been added to the reader.
+    pub members: Vec<AlignedMember<'a>>,
+}
+
+impl AlignedBundle<'_> {
+    /// Whether all the flows have their grains or samples for the tick.
+    pub fn is_complete(&self) -> bool {
+        self.members
+            .iter()
+            .all(|member| !matches!(member.data, AlignedData::Missing | AlignedData::Late))
+    }
+}
+
+/// Reads several flows, discrete and continuous, aligned on a common clock, following the
+/// alignment strategy of `docs/timing.md`.
+///
+/// Time is split into ticks at the rate of the reader, usually the grain rate of the video flows.
+/// Every tick yields the grains and samples of all the flows that begin during the tick, so that
+/// the bundles cover the same TAI interval. The sample counts follow the exact rates, e.g. the
+/// 1601 and 1602 sample cadence of 48 kHz audio at 30000/1001 ticks. Reading starts at the last
+/// tick available on all the flows, i.e. `min(F1_head ... FN_head)`.
+///
+/// A tick is yielded once all of its grains and samples are available or the timeout has elapsed,
+/// the flows that were not ready in time being reported as `AlignedData::Missing`, and the ones
+/// that had already been overwritten as `AlignedData::Late`. The next call moves to the next tick
+/// either way.
+pub struct AlignedReader {
+    members: Vec<Member>,
+    rate: GrainRate,
+    timeout: Duration,
+    /// `None` until resolved from the heads of the flows.
+    next: Option<GrainIndex>,
+}
+
+impl AlignedReader {
+    /// An empty reader ticking at `rate`, waiting up to 1 second for every tick.
+    pub fn new(rate: GrainRate) -> Self {
+        Self {
+            members: Vec::new(),
+            rate,
+            timeout: Duration::from_secs(1),
+            next: None,
+        }
+    }
+
+    /// Sets how long to wait for the grains and samples of a tick.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_grains(mut self, reader: GrainReader) -> Result<Self> {
+        let rate = reader.config_info().common().grain_rate()?;
+        let mapping = RateMapping::new(self.rate, rate);
+        self.members.push(Member {
+            flow_id: reader.flow_id(),
+            reader: MemberReader::Grains(reader, mapping),
+        });
+        Ok(self)
+    }
+
+    /// Fails with `Error::InvalidOptions` if the channel buffers cannot hold the samples of a
+    /// tick.
+    pub fn with_samples(mut self, reader: SamplesReader) -> Result<Self> {
+        let config = reader.config_info();
+        let rate = config.common().sample_rate()?;
+        let ring = SampleRing::new(u64::from(config.continuous()?.bufferLength))?;
+        let mapping = RateMapping::new(self.rate, rate);
+        // No tick holds more samples than the first one.
+        let samples_per_tick = mapping.count(GrainIndex::new(0))?;
+        if samples_per_tick > ring.buffer_length() / 2 {
+            return Err(Error::InvalidOptions(format!(
+                "the channel buffers of flow {} cannot hold the {samples_per_tick} samples of a \
+                 tick",
+                reader.flow_id()
+            )));
+        }
+        self.members.push(Member {
+            flow_id: reader.flow_id(),
+            reader: MemberReader::Samples(reader, mapping),
+        });
+        Ok(self)
+    }
+
+    pub fn rate(&self) -> GrainRate {
+        self.rate
+    }
+
+    /// Index of the next tick to read, `None` until it has been resolved from the heads of the
+    /// flows.
+    pub fn next_index(&self) -> Option<GrainIndex> {
+        self.next
+    }
+
+    pub fn seek(&mut self, index: GrainIndex) {
+        self.next = Some(index);
+    }
+
+    /// Resolves the next tick from the heads of the flows again, e.g. after falling behind.
+    pub fn realign(&mut self) {
+        self.next = None;
+    }
+
+    /// The last tick available on all the flows, `None` without flows.
+    pub fn aligned_head(&self) -> Result<Option<GrainIndex>> {
+        let mut head = None;
+        for member in &self.members {
+            // The ticks before the one the first missing grain or sample begins in are complete.
+            let complete = member.first_incomplete_tick()? - 1;
+            head = Some(head.map_or(complete, |head: GrainIndex| head.min(complete)));
+        }
+        Ok(head)
+    }
+
+    /// Reads the grains and samples of the next tick, see `AlignedReader`. Fails with
+    /// `Error::InvalidArg` without flows.
+    pub fn next_bundle(&mut self) -> Result<AlignedBundle<'_>> {
+        let index = match self.next {
+            Some(index) => index,
+            None => self.aligned_head()?.ok_or(Error::InvalidArg)?,
+        };
+        self.next = Some(index + 1);
+        let deadline = Instant::now() + self.timeout;
+        let members = self
+            .members
+            .iter()
+            .map(|member| {
+                Ok(AlignedMember {
+                    flow_id: member.flow_id,
+                    data: self.read_member(member, index, deadline)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(AlignedBundle {
+            index,
+            start: timing::index_to_timestamp(index, &self.rate)?,
+            end: timing::index_to_timestamp(index + 1, &self.rate)?,
+            members,
+        })
+    }
+
+    fn read_member<'a>(
+        &self,
+        member: &'a Member,
+        index: GrainIndex,
+        deadline: Instant,
+    ) -> Result<AlignedData<'a>> {
+        let remaining = || deadline.saturating_duration_since(Instant::now());
+        let result = match &member.reader {
+            MemberReader::Grains(reader, mapping) => {
+                let Range { start, end } = mapping.map(index)?;
+                (start.value()..end.value())
+                    .map(|index| {
+                        let index = GrainIndex::new(index);
+                        Ok((index, reader.get_complete_grain(index, remaining())?))
+                    })
+                    .collect::<Result<_>>()
+                    .map(AlignedData::Grains)
+            }
+            MemberReader::Samples(reader, mapping) => {
+                let Range { start, end } = mapping.map(index)?;
+                let count = usize::try_from(end.saturating_distance_since(start))
+                    .map_err(|_| Error::InvalidArg)?;
+                reader
+                    .get_samples(end, count, remaining())
+                    .map(|data| AlignedData::Samples { start, data })
+            }
+        };
+        match result {
+            Ok(data) => Ok(data),
+            // `mxlFlowReaderGetSamples` reports missing samples as too early, even after waiting.
+            Err(error) if matches!(error.root(), Error::Timeout | Error::OutOfRangeTooEarly) => {
+                Ok(AlignedData::Missing)
+            }
+            Err(error) if matches!(error.root(), Error::OutOfRangeTooLate) => Ok(AlignedData::Late),
+            Err(error) => Err(error),
+        }
+    }
+}
@@ -1,12 +1,16 @@
 // SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{path::Path, sync::Arc};
+use std::{fmt::Display, ops::Deref, path::Path, sync::Arc};
 
 use dlopen2::wrapper::{Container, WrapperApi};
 
-use crate::Result;
+use crate::{Error, Result};
 
+/// Entry points of `libmxl.so`.
+///
+/// Entry points added after the first SDK releases are optional, so that older libraries can
+/// still be loaded. Calling a missing one fails with `Error::Unsupported`.
 #[derive(WrapperApi)]
 pub struct MxlApi {
     #[dlopen2_name = "mxlGetVersion"]
@@ -40,6 +44,15 @@ pub struct MxlApi {
         flow_id: *const std::os::raw::c_char,
     ) -> mxl_sys::mxlStatus,
 
+    #[dlopen2_name = "mxlIsFlowActive"]
+    is_flow_active: Option<
+        unsafe extern "C" fn(
+            instance: mxl_sys::mxlInstance,
+            flow_id: *const std::os::raw::c_char,
+            is_active: *mut bool,
+        ) -> mxl_sys::mxlStatus,
+    >,
+
     #[dlopen2_name = "mxlGetFlowDef"]
     get_flow_def: unsafe extern "C" fn(
         instance: mxl_sys::mxlInstance,
@@ -111,6 +124,38 @@ pub struct MxlApi {
         payload: *mut *mut u8,
     ) -> mxl_sys::mxlStatus,
 
+    #[dlopen2_name = "mxlFlowReaderGetGrainSlice"]
+    flow_reader_get_grain_slice: Option<
+        unsafe extern "C" fn(
+            reader: mxl_sys::mxlFlowReader,
+            index: u64,
+            min_valid_slices: u16,
+            timeout_ns: u64,
+            grain: *mut mxl_sys::mxlGrainInfo,
+            payload: *mut *mut u8,
+        ) -> mxl_sys::mxlStatus,
+    >,
+
+    #[dlopen2_name = "mxlFlowReaderGetGrainSliceNonBlocking"]
+    flow_reader_get_grain_slice_non_blocking: Option<
+        unsafe extern "C" fn(
+            reader: mxl_sys::mxlFlowReader,
+            index: u64,
+            min_valid_slices: u16,
+            grain: *mut mxl_sys::mxlGrainInfo,
+            payload: *mut *mut u8,
+        ) -> mxl_sys::mxlStatus,
+    >,
+
+    #[dlopen2_name = "mxlFlowWriterGetGrainInfo"]
+    flow_writer_get_grain_info: Option<
+        unsafe extern "C" fn(
+            writer: mxl_sys::mxlFlowWriter,
+            index: u64,
+            grain_info: *mut mxl_sys::mxlGrainInfo,
+        ) -> mxl_sys::mxlStatus,
+    >,
+
     #[dlopen2_name = "mxlFlowWriterOpenGrain"]
     flow_writer_open_grain: unsafe extern "C" fn(
         writer: mxl_sys::mxlFlowWriter,
@@ -184,9 +229,237 @@ pub struct MxlApi {
     get_time: unsafe extern "C" fn() -> u64,
 }
 
-pub type MxlApiHandle = Arc<Container<MxlApi>>;
+/// Version of the loaded MXL SDK, as reported by `mxlGetVersion`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MxlVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub bugfix: u16,
+    pub build: u16,
+}
+
+impl From<mxl_sys::mxlVersionType> for MxlVersion {
+    fn from(value: mxl_sys::mxlVersionType) -> Self {
+        Self {
+            major: value.major,
+            minor: value.minor,
+            bugfix: value.bugfix,
+            build: value.build,
+        }
+    }
+}
+
+impl Display for MxlVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.bugfix)
+    }
+}
+
+/// The loaded `libmxl.so` together with its version.
+pub struct MxlLibrary {
+    api: Container<MxlApi>,
+    version: MxlVersion,
+}
 
+impl MxlLibrary {
+    pub fn version(&self) -> MxlVersion {
+        self.version
+    }
+
+    /// Hides the optional entry points, as if the library predated them. Meant for testing how
+    /// callers cope with older libraries.
+    #[doc(hidden)]
+    pub fn without_optional_entry_points(mut self) -> Self {
+        self.api.is_flow_active = None;
+        self.api.flow_reader_get_grain_slice = None;
+        self.api.flow_reader_get_grain_slice_non_blocking = None;
+        self.api.flow_writer_get_grain_info = None;
+        self
+    }
+
+    /// Error returned when calling an optional entry point missing from the loaded library.
+    pub(crate) fn unsupported(&self, function: &'static str) -> Error {
+        Error::Unsupported {
+            function,
+            version: self.version,
+        }
+    }
+}
+
+impl Deref for MxlLibrary {
+    type Target = Container<MxlApi>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.api
+    }
+}
+
+pub type MxlApiHandle = Arc<MxlLibrary>;
+
+/// Loads `libmxl.so` and queries its version, available through `MxlLibrary::version`.
 pub fn load_api(path_to_so_file: impl AsRef<Path>) -> Result<MxlApiHandle> {
+    let api: Container<MxlApi> = unsafe { Container::load(path_to_so_file.as_ref().as_os_str()) }?;
+    let mut version: mxl_sys::mxlVersionType = unsafe { std::mem::zeroed() };
+    unsafe {
+        Error::from_status(api.get_version(&mut version))?;
+    }
+    Ok(Arc::new(MxlLibrary {
+        api,
+        version: version.into(),
+    }))
+}
+
+/// The fabrics API lives in its own shared library (`libmxl-fabrics.so`), which is only built
+/// when MXL is configured with `MXL_ENABLE_FABRICS_OFI`, so it is loaded separately.
+#[derive(WrapperApi)]
+pub struct MxlFabricsApi {
+    #[dlopen2_name = "mxlFabricsRegionsForFlowReader"]
+    regions_for_flow_reader: unsafe extern "C" fn(
+        in_reader: mxl_sys::mxlFlowReader,
+        out_regions: *mut mxl_sys::fabrics::mxlRegions,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsRegionsForFlowWriter"]
+    regions_for_flow_writer: unsafe extern "C" fn(
+        in_writer: mxl_sys::mxlFlowWriter,
+        out_regions: *mut mxl_sys::fabrics::mxlRegions,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsRegionsFromUserBuffers"]
+    regions_from_user_buffers: unsafe extern "C" fn(
+        in_regions: *const mxl_sys::fabrics::mxlFabricsMemoryRegion,
+        in_count: usize,
+        out_regions: *mut mxl_sys::fabrics::mxlRegions,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsRegionsFree"]
+    regions_free:
+        unsafe extern "C" fn(in_regions: mxl_sys::fabrics::mxlRegions) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsCreateInstance"]
+    create_instance: unsafe extern "C" fn(
+        in_instance: mxl_sys::mxlInstance,
+        out_fabrics_instance: *mut mxl_sys::fabrics::mxlFabricsInstance,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsDestroyInstance"]
+    destroy_instance: unsafe extern "C" fn(
+        in_instance: mxl_sys::fabrics::mxlFabricsInstance,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsCreateTarget"]
+    create_target: unsafe extern "C" fn(
+        in_fabrics_instance: mxl_sys::fabrics::mxlFabricsInstance,
+        out_target: *mut mxl_sys::fabrics::mxlFabricsTarget,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsDestroyTarget"]
+    destroy_target: unsafe extern "C" fn(
+        in_fabrics_instance: mxl_sys::fabrics::mxlFabricsInstance,
+        in_target: mxl_sys::fabrics::mxlFabricsTarget,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsTargetSetup"]
+    target_setup: unsafe extern "C" fn(
+        in_target: mxl_sys::fabrics::mxlFabricsTarget,
+        in_config: *mut mxl_sys::fabrics::mxlTargetConfig,
+        out_info: *mut mxl_sys::fabrics::mxlTargetInfo,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsTargetTryNewGrain"]
+    target_try_new_grain: unsafe extern "C" fn(
+        in_target: mxl_sys::fabrics::mxlFabricsTarget,
+        out_index: *mut u64,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsTargetWaitForNewGrain"]
+    target_wait_for_new_grain: unsafe extern "C" fn(
+        in_target: mxl_sys::fabrics::mxlFabricsTarget,
+        out_index: *mut u64,
+        in_timeout_ms: u16,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsCreateInitiator"]
+    create_initiator: unsafe extern "C" fn(
+        in_fabrics_instance: mxl_sys::fabrics::mxlFabricsInstance,
+        out_initiator: *mut mxl_sys::fabrics::mxlFabricsInitiator,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsDestroyInitiator"]
+    destroy_initiator: unsafe extern "C" fn(
+        in_fabrics_instance: mxl_sys::fabrics::mxlFabricsInstance,
+        in_initiator: mxl_sys::fabrics::mxlFabricsInitiator,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsInitiatorSetup"]
+    initiator_setup: unsafe extern "C" fn(
+        in_initiator: mxl_sys::fabrics::mxlFabricsInitiator,
+        in_config: *const mxl_sys::fabrics::mxlInitiatorConfig,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsInitiatorAddTarget"]
+    initiator_add_target: unsafe extern "C" fn(
+        in_initiator: mxl_sys::fabrics::mxlFabricsInitiator,
+        in_target_info: mxl_sys::fabrics::mxlTargetInfo,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsInitiatorRemoveTarget"]
+    initiator_remove_target: unsafe extern "C" fn(
+        in_initiator: mxl_sys::fabrics::mxlFabricsInitiator,
+        in_target_info: mxl_sys::fabrics::mxlTargetInfo,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsInitiatorTransferGrain"]
+    initiator_transfer_grain: unsafe extern "C" fn(
+        in_initiator: mxl_sys::fabrics::mxlFabricsInitiator,
+        in_grain_index: u64,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsInitiatorMakeProgressNonBlocking"]
+    initiator_make_progress_non_blocking: unsafe extern "C" fn(
+        in_initiator: mxl_sys::fabrics::mxlFabricsInitiator,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsInitiatorMakeProgressBlocking"]
+    initiator_make_progress_blocking: unsafe extern "C" fn(
+        in_initiator: mxl_sys::fabrics::mxlFabricsInitiator,
+        in_timeout_ms: u16,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsProviderFromString"]
+    provider_from_string: unsafe extern "C" fn(
+        in_string: *const std::os::raw::c_char,
+        out_provider: *mut mxl_sys::fabrics::mxlFabricsProvider,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsProviderToString"]
+    provider_to_string: unsafe extern "C" fn(
+        in_provider: mxl_sys::fabrics::mxlFabricsProvider,
+        out_string: *mut std::os::raw::c_char,
+        in_string_size: *mut usize,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsTargetInfoToString"]
+    target_info_to_string: unsafe extern "C" fn(
+        in_target_info: mxl_sys::fabrics::mxlTargetInfo,
+        out_string: *mut std::os::raw::c_char,
+        in_string_size: *mut usize,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsTargetInfoFromString"]
+    target_info_from_string: unsafe extern "C" fn(
+        in_string: *const std::os::raw::c_char,
+        out_target_info: *mut mxl_sys::fabrics::mxlTargetInfo,
+    ) -> mxl_sys::mxlStatus,
+
+    #[dlopen2_name = "mxlFabricsFreeTargetInfo"]
+    free_target_info:
+        unsafe extern "C" fn(in_info: mxl_sys::fabrics::mxlTargetInfo) -> mxl_sys::mxlStatus,
+}
+
+pub type MxlFabricsApiHandle = Arc<Container<MxlFabricsApi>>;
+
+pub fn load_fabrics_api(path_to_so_file: impl AsRef<Path>) -> Result<MxlFabricsApiHandle> {
     Ok(Arc::new(unsafe {
         Container::load(path_to_so_file.as_ref().as_os_str())
     }?))
@@ -20,6 +20,21 @@ pub fn get_mxl_so_path() -> std::path::PathBuf {
         .join("libmxl.so")
 }
 
+#[cfg(not(feature = "mxl-not-built"))]
+pub fn get_mxl_fabrics_so_path() -> std::path::PathBuf {
+    "libmxl-fabrics.so".into()
+}
+
+#[cfg(feature = "mxl-not-built")]
+pub fn get_mxl_fabrics_so_path() -> std::path::PathBuf {
+    std::path::PathBuf::from_str(MXL_BUILD_DIR)
+        .expect("build error: 'MXL_BUILD_DIR' is invalid")
+        .join("lib")
+        .join("fabrics")
+        .join("ofi")
+        .join("libmxl-fabrics.so")
+}
+
 pub fn get_mxl_repo_root() -> std::path::PathBuf {
     std::path::PathBuf::from_str(MXL_REPO_ROOT).expect("build error: 'MXL_REPO_ROOT' is invalid")
 }
@@ -1,6 +1,10 @@
 // SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::fmt::Display;
+
+use crate::DataFormat;
+
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Debug, thiserror::Error)]
@@ -23,6 +27,87 @@ pub enum Error {
     InvalidArg,
     #[error("Conflict")]
     Conflict,
+    #[error("Permission denied")]
+    PermissionDenied,
+    /// The flow data has been replaced, e.g. because the writer restarted and recreated the flow.
+    /// The reader has to be recreated.
+    #[error("Flow invalid")]
+    FlowInvalid,
+
+    // Errors of `mxl/fabrics.h`.
+    #[error("String too long")]
+    StringTooLong,
+    #[error("Interrupted")]
+    Interrupted,
+    #[error("No fabric available")]
+    NoFabric,
+    #[error("Invalid state")]
+    InvalidState,
+    #[error("Internal error")]
+    Internal,
+    #[error("Not ready")]
+    NotReady,
+    #[error("Not found")]
+    NotFound,
+    #[error("Already exists")]
+    Exists,
+
+    /// An error that happened while performing an operation on a flow.
+    #[error("{context}: {source}")]
+    Context {
+        context: ErrorContext,
+        source: Box<Error>,
+    },
+
+    /// The flow is not of the kind the operation requires.
+    #[error("Flow format is {actual:?}, {expected} required.")]
+    FlowFormatMismatch {
+        expected: &'static str,
+        actual: DataFormat,
+    },
+
+    #[error("Invalid flow definition: {0}.")]
+    InvalidFlowDef(String),
+
+    #[error("Invalid options: {0}.")]
+    InvalidOptions(String),
+
+    #[error("Invalid flow ID \"{0}\".")]
+    InvalidFlowId(String),
+
+    #[error("Invalid rational \"{0}\".")]
+    InvalidRational(String),
+
+    #[error("Invalid rate {numerator}/{denominator}.")]
+    InvalidRate { numerator: i64, denominator: i64 },
+
+    /// The time cannot be represented as an MXL timestamp.
+    #[error("Time out of the range of MXL timestamps.")]
+    TimestampOutOfRange,
+
+    #[error("{slices} slices requested, but the grain only has {total}.")]
+    SlicesOutOfRange { slices: u16, total: u16 },
+
+    /// The valid slices of a grain cannot go backwards once committed.
+    #[error("{slices} valid slices requested, but {committed} slices are already committed.")]
+    SlicesBelowCommitted { slices: u16, committed: u16 },
+
+    /// The writer reused the ring buffer slot of a grain while it was being read, so the data read
+    /// may mix two grains.
+    #[error("Grain overwritten while being read.")]
+    Overwritten,
+
+    /// A buffer of samples does not have the length the channels require.
+    #[error("{actual} samples provided, {expected} expected.")]
+    SampleCountMismatch { expected: usize, actual: usize },
+
+    /// The MXL library returned a null handle or pointer without reporting an error.
+    #[error("MXL returned a null {0}.")]
+    NullPointer(&'static str),
+
+    #[error("Instance is still in use.")]
+    InstanceInUse,
+
     /// The error is not defined in the MXL API, but it is used to wrap other errors.
     #[error("Other error: {0}")]
     Other(String),
@@ -32,6 +117,19 @@ pub enum Error {
 
     #[error("Null string: {0}")]
     NulString(#[from] std::ffi::NulError),
+
+    #[error("Invalid UTF-8: {0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// An optional entry point is missing from the loaded MXL library.
+    #[error("{function} is unsupported by loaded libmxl {version}")]
+    Unsupported {
+        function: &'static str,
+        version: crate::MxlVersion,
+    },
 }
 
 impl Error {
@@ -47,7 +145,118 @@ impl Error {
             mxl_sys::MXL_ERR_TIMEOUT => Err(Error::Timeout),
             mxl_sys::MXL_ERR_INVALID_ARG => Err(Error::InvalidArg),
             mxl_sys::MXL_ERR_CONFLICT => Err(Error::Conflict),
+            mxl_sys::MXL_ERR_PERMISSION_DENIED => Err(Error::PermissionDenied),
+            mxl_sys::MXL_ERR_FLOW_INVALID => Err(Error::FlowInvalid),
+            mxl_sys::MXL_ERR_STRLEN => Err(Error::StringTooLong),
+            mxl_sys::MXL_ERR_INTERRUPTED => Err(Error::Interrupted),
+            mxl_sys::MXL_ERR_NO_FABRIC => Err(Error::NoFabric),
+            mxl_sys::MXL_ERR_INVALID_STATE => Err(Error::InvalidState),
+            mxl_sys::MXL_ERR_INTERNAL => Err(Error::Internal),
+            mxl_sys::MXL_ERR_NOT_READY => Err(Error::NotReady),
+            mxl_sys::MXL_ERR_NOT_FOUND => Err(Error::NotFound),
+            mxl_sys::MXL_ERR_EXISTS => Err(Error::Exists),
             other => Err(Error::Unknown(other)),
         }
     }
+
+    /// Attaches the context of the operation that failed.
+    pub fn with_context(self, context: ErrorContext) -> Error {
+        Error::Context {
+            context,
+            source: Box::new(self),
+        }
+    }
+
+    /// The error without any context attached, the one to match on.
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// The context of the outermost operation that failed, if any.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Error::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Whether the very same call may succeed if repeated a bit later, because the data or the
+    /// peer was just not ready yet.
+    pub fn is_retryable(&self) -> bool {
+        match self.root() {
+            Error::OutOfRangeTooEarly | Error::Timeout | Error::Interrupted | Error::NotReady => {
+                true
+            }
+            Error::Io(error) => matches!(
+                error.kind(),
+                std::io::ErrorKind::WouldBlock
+                    | std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::TimedOut
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether the error comes from the timing between readers and writers rather than from a
+    /// broken setup. This includes the retryable errors, but also readers falling behind the
+    /// writer, who should skip ahead to a newer index instead of retrying.
+    pub fn is_transient(&self) -> bool {
+        self.is_retryable() || matches!(self.root(), Error::OutOfRangeTooLate)
+    }
+}
+
+/// Where an error happened: the operation, and when relevant, the flow and the grain or sample
+/// index it was performed on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    pub operation: &'static str,
+    pub flow_id: Option<uuid::Uuid>,
+    pub index: Option<u64>,
+}
+
+impl ErrorContext {
+    pub fn new(operation: &'static str) -> Self {
+        Self {
+            operation,
+            flow_id: None,
+            index: None,
+        }
+    }
+
+    pub fn flow_id(mut self, flow_id: uuid::Uuid) -> Self {
+        self.flow_id = Some(flow_id);
+        self
+    }
+
+    pub fn index(mut self, index: impl Into<u64>) -> Self {
+        self.index = Some(index.into());
+        self
+    }
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.operation)?;
+        if let Some(flow_id) = self.flow_id {
+            write!(f, " on flow {flow_id}")?;
+        }
+        if let Some(index) = self.index {
+            write!(f, " at index {index}")?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) trait ResultExt<T> {
+    /// Attaches the context built by `context` to the error, if any.
+    fn context(self, context: impl FnOnce() -> ErrorContext) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn context(self, context: impl FnOnce() -> ErrorContext) -> Result<T> {
+        self.map_err(|error| error.with_context(context()))
+    }
 }
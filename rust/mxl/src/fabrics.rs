@@ -0,0 +1,249 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Grain transfers between flows over a network, mirroring the model of `mxl/fabrics.h`.
+//!
+//! A *target* owns the writing side of a flow and receives grains, an *initiator* owns the reading
+//! side of a flow and pushes grains to all the targets it has been given. The target describes
+//! itself with a [`TargetInfo`] that is serialized and handed to the initiator out of band.
+//!
+//! Two implementations are provided:
+//! - [`ofi`] wraps the C fabrics library (`libmxl-fabrics.so`).
+//! - [`local`] is a pure Rust transport over TCP or Unix sockets, meant for development and
+//!   testing on a single machine.
+//!
+//! Code written against the [`Fabrics`], [`Target`] and [`Initiator`] traits works with both.
+
+pub mod local;
+pub mod ofi;
+
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use crate::{Error, GrainIndex, GrainReader, GrainWriter, Result};
+
+/// The provider used to move the data, mirroring `mxlFabricsProvider`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Provider {
+    /// Let the implementation pick the best provider.
+    #[default]
+    Auto,
+    Tcp,
+    Verbs,
+    Efa,
+    Shm,
+}
+
+impl Provider {
+    pub(crate) fn to_raw(self) -> mxl_sys::fabrics::mxlFabricsProvider {
+        match self {
+            Provider::Auto => mxl_sys::fabrics::MXL_SHARING_PROVIDER_AUTO,
+            Provider::Tcp => mxl_sys::fabrics::MXL_SHARING_PROVIDER_TCP,
+            Provider::Verbs => mxl_sys::fabrics::MXL_SHARING_PROVIDER_VERBS,
+            Provider::Efa => mxl_sys::fabrics::MXL_SHARING_PROVIDER_EFA,
+            Provider::Shm => mxl_sys::fabrics::MXL_SHARING_PROVIDER_SHM,
+        }
+    }
+}
+
+impl FromStr for Provider {
+    type Err = Error;
+
+    /// Accepts the same names as `mxlFabricsProviderFromString`.
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "auto" => Ok(Provider::Auto),
+            "tcp" => Ok(Provider::Tcp),
+            "verbs" => Ok(Provider::Verbs),
+            "efa" => Ok(Provider::Efa),
+            "shm" => Ok(Provider::Shm),
+            other => Err(Error::Other(format!(
+                "Unknown fabrics provider \"{other}\"."
+            ))),
+        }
+    }
+}
+
+impl Display for Provider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Provider::Auto => "auto",
+            Provider::Tcp => "tcp",
+            Provider::Verbs => "verbs",
+            Provider::Efa => "efa",
+            Provider::Shm => "shm",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Address of a logical network endpoint, mirroring `mxlEndpointAddress`. For IP based providers,
+/// `node` is usually an IP address and `service` a port number.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointAddress {
+    pub node: Option<String>,
+    pub service: Option<String>,
+}
+
+impl EndpointAddress {
+    pub fn new(node: impl Into<String>, service: impl Into<String>) -> Self {
+        Self {
+            node: Some(node.into()),
+            service: Some(service.into()),
+        }
+    }
+}
+
+/// Memory regions of a flow that take part in grain transfers, mirroring `mxlRegions`.
+///
+/// A target needs the regions of a flow writer (the grains are written into them), an initiator
+/// needs the regions of a flow reader (the grains are read from them). The reader or writer is
+/// kept alive by the target or initiator it is given to.
+pub enum Regions {
+    FlowReader(GrainReader),
+    FlowWriter(GrainWriter),
+}
+
+impl Regions {
+    pub(crate) fn into_writer(self) -> Result<GrainWriter> {
+        match self {
+            Regions::FlowWriter(writer) => Ok(writer),
+            Regions::FlowReader(_) => Err(Error::Other(
+                "A fabrics target requires the regions of a flow writer.".to_string(),
+            )),
+        }
+    }
+
+    pub(crate) fn into_reader(self) -> Result<GrainReader> {
+        match self {
+            Regions::FlowReader(reader) => Ok(reader),
+            Regions::FlowWriter(_) => Err(Error::Other(
+                "A fabrics initiator requires the regions of a flow reader.".to_string(),
+            )),
+        }
+    }
+}
+
+impl From<GrainReader> for Regions {
+    fn from(value: GrainReader) -> Self {
+        Regions::FlowReader(value)
+    }
+}
+
+impl From<GrainWriter> for Regions {
+    fn from(value: GrainWriter) -> Self {
+        Regions::FlowWriter(value)
+    }
+}
+
+/// Configuration of a target, mirroring `mxlTargetConfig`.
+pub struct TargetConfig {
+    /// Bind address of the local endpoint.
+    pub endpoint_address: EndpointAddress,
+    pub provider: Provider,
+    /// Regions of the flow the received grains are written to.
+    pub regions: Regions,
+    /// Require support of transfers involving device memory.
+    pub device_support: bool,
+}
+
+impl TargetConfig {
+    pub fn new(
+        endpoint_address: EndpointAddress,
+        provider: Provider,
+        regions: impl Into<Regions>,
+    ) -> Self {
+        Self {
+            endpoint_address,
+            provider,
+            regions: regions.into(),
+            device_support: false,
+        }
+    }
+}
+
+/// Configuration of an initiator, mirroring `mxlInitiatorConfig`.
+pub struct InitiatorConfig {
+    /// Bind address of the local endpoint.
+    pub endpoint_address: EndpointAddress,
+    pub provider: Provider,
+    /// Regions of the flow the transferred grains are read from.
+    pub regions: Regions,
+    /// Require support of transfers involving device memory.
+    pub device_support: bool,
+}
+
+impl InitiatorConfig {
+    pub fn new(
+        endpoint_address: EndpointAddress,
+        provider: Provider,
+        regions: impl Into<Regions>,
+    ) -> Self {
+        Self {
+            endpoint_address,
+            provider,
+            regions: regions.into(),
+            device_support: false,
+        }
+    }
+}
+
+/// Outcome of a call driving the initiator forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// All queued transfers, connections and disconnections have completed.
+    Done,
+    /// There is still work in flight, keep calling.
+    Pending,
+}
+
+/// Information about a target that an initiator needs to reach it.
+pub trait TargetInfo: Sized {
+    /// Serializes the info so that it can be shared with a remote initiator.
+    fn serialize(&self) -> Result<String>;
+
+    /// Parses the info produced by `serialize`.
+    fn deserialize(value: &str) -> Result<Self>;
+}
+
+/// The receiving side of grain transfers.
+pub trait Target {
+    /// Returns the index of a grain that has been received and committed to the flow, or `None`
+    /// if no new grain is available.
+    fn try_new_grain(&mut self) -> Result<Option<GrainIndex>>;
+
+    /// Same as `try_new_grain`, but waits up to `timeout` for a grain to arrive.
+    fn wait_for_new_grain(&mut self, timeout: Duration) -> Result<Option<GrainIndex>>;
+}
+
+/// The sending side of grain transfers.
+pub trait Initiator {
+    type TargetInfo: TargetInfo;
+
+    /// Registers a target. Never blocks, the connection is established while making progress.
+    fn add_target(&mut self, target_info: &Self::TargetInfo) -> Result<()>;
+
+    /// Unregisters a target. Never blocks, the shutdown is only guaranteed to be complete once
+    /// making progress returns `Progress::Done`.
+    fn remove_target(&mut self, target_info: &Self::TargetInfo) -> Result<()>;
+
+    /// Queues the transfer of the grain at `index` to all registered targets. The transfer is only
+    /// guaranteed to be complete once making progress returns `Progress::Done`.
+    fn transfer_grain(&mut self, index: GrainIndex) -> Result<()>;
+
+    fn make_progress_non_blocking(&mut self) -> Result<Progress>;
+
+    fn make_progress_blocking(&mut self, timeout: Duration) -> Result<Progress>;
+}
+
+/// Creates targets and initiators of a given transport, mirroring `mxlFabricsInstance`.
+pub trait Fabrics {
+    type TargetInfo: TargetInfo;
+    type Target: Target;
+    type Initiator: Initiator<TargetInfo = Self::TargetInfo>;
+
+    /// Creates a target ready to receive grains. The returned info must be passed to the
+    /// initiators that should send grains to it.
+    fn create_target(&self, config: TargetConfig) -> Result<(Self::Target, Self::TargetInfo)>;
+
+    fn create_initiator(&self, config: InitiatorConfig) -> Result<Self::Initiator>;
+}
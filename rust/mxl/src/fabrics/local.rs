@@ -0,0 +1,145 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod initiator;
+mod protocol;
+mod socket;
+pub mod target;
+
+use std::{
+    fmt::Display,
+    net::{SocketAddr, ToSocketAddrs},
+    path::PathBuf,
+    str::FromStr,
+};
+
+pub use initiator::LocalInitiator;
+pub use target::LocalTarget;
+
+use super::{EndpointAddress, Fabrics, InitiatorConfig, Provider, TargetConfig, TargetInfo};
+use crate::{Error, Result};
+
+/// Pure Rust fabrics implementation moving grains over TCP or Unix sockets.
+///
+/// It does not need the C fabrics library, nor any special hardware, which makes it handy for
+/// developing and testing grain replication on a single machine. The grains are copied through
+/// the socket, so it is not meant for production use.
+///
+/// The provider selects the socket family:
+/// - `Provider::Tcp` binds to `node:service`, `node` defaults to `127.0.0.1` and `service` to an
+///   ephemeral port.
+/// - `Provider::Shm` binds a Unix socket at the path given in `node`.
+/// - `Provider::Auto` picks a Unix socket if `node` looks like a path, TCP otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalFabrics;
+
+impl LocalFabrics {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Fabrics for LocalFabrics {
+    type TargetInfo = LocalTargetInfo;
+    type Target = LocalTarget;
+    type Initiator = LocalInitiator;
+
+    fn create_target(&self, config: TargetConfig) -> Result<(LocalTarget, LocalTargetInfo)> {
+        LocalTarget::new(config)
+    }
+
+    fn create_initiator(&self, config: InitiatorConfig) -> Result<LocalInitiator> {
+        LocalInitiator::new(config)
+    }
+}
+
+/// Socket address of a local target.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LocalAddress {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl LocalAddress {
+    fn resolve(address: &EndpointAddress, provider: Provider) -> Result<Self> {
+        let node = address.node.as_deref();
+        let is_path = node.is_some_and(|node| node.contains('/'));
+        match provider {
+            Provider::Shm => node
+                .map(|node| LocalAddress::Unix(PathBuf::from(node)))
+                .ok_or_else(|| {
+                    Error::Other("A Unix socket path is required in the node address.".to_string())
+                }),
+            Provider::Auto if is_path => {
+                Ok(LocalAddress::Unix(PathBuf::from(node.unwrap_or_default())))
+            }
+            Provider::Auto | Provider::Tcp => {
+                let node = node.unwrap_or("127.0.0.1");
+                let port = match address.service.as_deref() {
+                    Some(service) => service
+                        .parse::<u16>()
+                        .map_err(|_| Error::Other(format!("Invalid TCP port \"{service}\".")))?,
+                    None => 0,
+                };
+                (node, port)
+                    .to_socket_addrs()?
+                    .next()
+                    .map(LocalAddress::Tcp)
+                    .ok_or_else(|| Error::Other(format!("Failed to resolve \"{node}\".")))
+            }
+            Provider::Verbs | Provider::Efa => Err(Error::Other(format!(
+                "Provider \"{provider}\" is not supported by the local fabrics."
+            ))),
+        }
+    }
+}
+
+impl Display for LocalAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocalAddress::Tcp(address) => write!(f, "tcp:{address}"),
+            LocalAddress::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+impl FromStr for LocalAddress {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        if let Some(address) = value.strip_prefix("tcp:") {
+            let address = address
+                .parse()
+                .map_err(|_| Error::Other(format!("Invalid TCP address \"{address}\".")))?;
+            Ok(LocalAddress::Tcp(address))
+        } else if let Some(path) = value.strip_prefix("unix:") {
+            Ok(LocalAddress::Unix(PathBuf::from(path)))
+        } else {
+            Err(Error::Other(format!("Invalid local address \"{value}\".")))
+        }
+    }
+}
+
+/// Target info of the local fabrics, the address the target listens on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LocalTargetInfo {
+    address: LocalAddress,
+}
+
+impl LocalTargetInfo {
+    pub fn address(&self) -> &LocalAddress {
+        &self.address
+    }
+}
+
+impl TargetInfo for LocalTargetInfo {
+    fn serialize(&self) -> Result<String> {
+        Ok(self.address.to_string())
+    }
+
+    fn deserialize(value: &str) -> Result<Self> {
+        Ok(Self {
+            address: value.parse()?,
+        })
+    }
+}
@@ -0,0 +1,233 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::VecDeque,
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use super::{
+    LocalTargetInfo,
+    protocol::GrainHeader,
+    socket::{Stream, is_retryable},
+    target::POLL_INTERVAL,
+};
+use crate::{
+    Error, GrainIndex, GrainReader, Result,
+    fabrics::{Initiator, InitiatorConfig, Progress},
+};
+
+/// How many frames are queued for a connected target at most. Grains transferred while a target
+/// is that far behind are dropped for it.
+const MAX_PENDING_FRAMES: usize = 2;
+
+struct Peer {
+    info: LocalTargetInfo,
+    stream: Option<Stream>,
+    /// Encoded grains not yet written to the socket.
+    pending: Vec<u8>,
+    /// End offsets in `pending` of the frames not yet completely written.
+    frame_ends: VecDeque<usize>,
+    written: usize,
+    removed: bool,
+}
+
+impl Peer {
+    fn is_idle(&self) -> bool {
+        self.written == self.pending.len()
+    }
+
+    /// Connects unless already connected or removed. Targets that are not listening yet are
+    /// retried on the next call.
+    fn connect(&mut self) -> Result<()> {
+        if self.stream.is_some() || self.removed {
+            return Ok(());
+        }
+        match Stream::connect(&self.info.address) {
+            Ok(stream) => {
+                stream.set_nonblocking(true)?;
+                self.stream = Some(stream);
+                Ok(())
+            }
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    std::io::ErrorKind::ConnectionRefused | std::io::ErrorKind::NotFound
+                ) =>
+            {
+                Ok(())
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Queues a grain for a connected target. Grains are not queued for targets that are not
+    /// connected, nor for targets that already have `MAX_PENDING_FRAMES` frames queued.
+    fn queue(&mut self, header: &GrainHeader, payload: &[u8]) {
+        if self.stream.is_none() {
+            return;
+        }
+        if self.frame_ends.len() >= MAX_PENDING_FRAMES {
+            tracing::warn!(
+                "Dropping grain {} for local fabrics target {}, which is {} grains behind.",
+                header.index,
+                self.info.address,
+                self.frame_ends.len()
+            );
+            return;
+        }
+        header.encode(payload, &mut self.pending);
+        self.frame_ends.push_back(self.pending.len());
+    }
+
+    fn clear(&mut self) {
+        self.pending.clear();
+        self.frame_ends.clear();
+        self.written = 0;
+    }
+
+    /// Connects if needed and writes as much of the pending data as the socket accepts.
+    fn make_progress(&mut self) -> Result<()> {
+        self.connect()?;
+        let Some(stream) = self.stream.as_mut() else {
+            return Ok(());
+        };
+        while self.written < self.pending.len() {
+            match stream.write(&self.pending[self.written..]) {
+                Ok(0) => break,
+                Ok(written) => self.written += written,
+                Err(error) if is_retryable(&error) => break,
+                Err(error) => {
+                    // Drop the connection, it is re-established on the next call.
+                    self.stream = None;
+                    self.clear();
+                    return Err(error.into());
+                }
+            }
+        }
+        while self
+            .frame_ends
+            .front()
+            .is_some_and(|end| *end <= self.written)
+        {
+            self.frame_ends.pop_front();
+        }
+        if self.written == self.pending.len() {
+            self.clear();
+        }
+        Ok(())
+    }
+}
+
+/// Initiator of the local fabrics. Reads grains from the flow of the owned reader and sends them
+/// to all added targets.
+pub struct LocalInitiator {
+    reader: GrainReader,
+    peers: Vec<Peer>,
+}
+
+impl LocalInitiator {
+    pub(crate) fn new(config: InitiatorConfig) -> Result<Self> {
+        Ok(Self {
+            reader: config.regions.into_reader()?,
+            peers: Vec::new(),
+        })
+    }
+}
+
+impl Initiator for LocalInitiator {
+    type TargetInfo = LocalTargetInfo;
+
+    fn add_target(&mut self, target_info: &LocalTargetInfo) -> Result<()> {
+        if self
+            .peers
+            .iter()
+            .any(|peer| !peer.removed && peer.info == *target_info)
+        {
+            return Err(Error::Conflict);
+        }
+        self.peers.push(Peer {
+            info: target_info.clone(),
+            stream: None,
+            pending: Vec::new(),
+            frame_ends: VecDeque::new(),
+            written: 0,
+            removed: false,
+        });
+        Ok(())
+    }
+
+    fn remove_target(&mut self, target_info: &LocalTargetInfo) -> Result<()> {
+        let peer = self
+            .peers
+            .iter_mut()
+            .find(|peer| !peer.removed && peer.info == *target_info)
+            .ok_or(Error::InvalidArg)?;
+        peer.removed = true;
+        Ok(())
+    }
+
+    fn transfer_grain(&mut self, index: GrainIndex) -> Result<()> {
+        let (grain_info, payload) = self.reader.get_raw_grain_non_blocking(index)?;
+        let header = GrainHeader::from_grain_info(&grain_info, payload.len() as u32);
+        let mut result = Ok(());
+        for peer in self.peers.iter_mut().filter(|peer| !peer.removed) {
+            if let Err(error) = peer.connect()
+                && result.is_ok()
+            {
+                result = Err(error);
+            }
+            peer.queue(&header, payload);
+        }
+        result
+    }
+
+    fn make_progress_non_blocking(&mut self) -> Result<Progress> {
+        let mut result = Ok(());
+        for peer in self.peers.iter_mut() {
+            if let Err(error) = peer.make_progress()
+                && result.is_ok()
+            {
+                result = Err(error);
+            }
+        }
+        // Removed targets are disconnected once everything queued for them has been sent.
+        self.peers.retain(|peer| {
+            if !peer.removed {
+                return true;
+            }
+            match &peer.stream {
+                Some(stream) if peer.is_idle() => {
+                    if let Err(error) = stream.shutdown() {
+                        tracing::warn!("Failed to shut down local fabrics connection: {error}");
+                    }
+                    false
+                }
+                Some(_) => true,
+                None => false,
+            }
+        });
+        result?;
+
+        if self.peers.iter().all(Peer::is_idle) {
+            Ok(Progress::Done)
+        } else {
+            Ok(Progress::Pending)
+        }
+    }
+
+    fn make_progress_blocking(&mut self, timeout: Duration) -> Result<Progress> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.make_progress_non_blocking()? == Progress::Done {
+                return Ok(Progress::Done);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(Progress::Pending);
+            }
+            std::thread::sleep(remaining.min(POLL_INTERVAL));
+        }
+    }
+}
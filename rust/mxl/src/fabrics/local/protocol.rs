@@ -0,0 +1,80 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Wire format of the local fabrics. Every transferred grain is sent as a fixed size little endian
+//! header followed by the grain payload.
+
+use crate::{Error, Result};
+
+const MAGIC: u32 = u32::from_le_bytes(*b"MXLG");
+
+pub(super) const HEADER_SIZE: usize = 28;
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct GrainHeader {
+    pub(super) index: u64,
+    pub(super) flags: u32,
+    pub(super) grain_size: u32,
+    pub(super) total_slices: u16,
+    pub(super) valid_slices: u16,
+    pub(super) payload_size: u32,
+}
+
+impl GrainHeader {
+    pub(super) fn from_grain_info(info: &mxl_sys::mxlGrainInfo, payload_size: u32) -> Self {
+        Self {
+            index: info.index,
+            flags: info.flags,
+            grain_size: info.grainSize,
+            total_slices: info.totalSlices,
+            valid_slices: info.validSlices,
+            payload_size,
+        }
+    }
+
+    pub(super) fn encode(&self, payload: &[u8], out: &mut Vec<u8>) {
+        out.reserve(HEADER_SIZE + payload.len());
+        out.extend_from_slice(&MAGIC.to_le_bytes());
+        out.extend_from_slice(&self.index.to_le_bytes());
+        out.extend_from_slice(&self.flags.to_le_bytes());
+        out.extend_from_slice(&self.grain_size.to_le_bytes());
+        out.extend_from_slice(&self.total_slices.to_le_bytes());
+        out.extend_from_slice(&self.valid_slices.to_le_bytes());
+        out.extend_from_slice(&self.payload_size.to_le_bytes());
+        out.extend_from_slice(payload);
+    }
+
+    /// Decodes a header from the beginning of `buffer`. Returns `None` if the buffer does not yet
+    /// hold a whole header.
+    pub(super) fn decode(buffer: &[u8]) -> Result<Option<Self>> {
+        let Some(header) = buffer.get(..HEADER_SIZE) else {
+            return Ok(None);
+        };
+        let u16_at = |offset: usize| u16::from_le_bytes([header[offset], header[offset + 1]]);
+        let u32_at = |offset: usize| {
+            let mut bytes = [0u8; 4];
+            bytes.copy_from_slice(&header[offset..offset + 4]);
+            u32::from_le_bytes(bytes)
+        };
+        let mut index = [0u8; 8];
+        index.copy_from_slice(&header[4..12]);
+
+        if u32_at(0) != MAGIC {
+            return Err(Error::Other(
+                "Invalid grain header received by the local fabrics target.".to_string(),
+            ));
+        }
+        Ok(Some(Self {
+            index: u64::from_le_bytes(index),
+            flags: u32_at(12),
+            grain_size: u32_at(16),
+            total_slices: u16_at(20),
+            valid_slices: u16_at(22),
+            payload_size: u32_at(24),
+        }))
+    }
+
+    pub(super) fn frame_size(&self) -> usize {
+        HEADER_SIZE + self.payload_size as usize
+    }
+}
@@ -0,0 +1,238 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    io::Read,
+    time::{Duration, Instant},
+};
+
+use super::{
+    LocalAddress, LocalTargetInfo,
+    protocol::{GrainHeader, HEADER_SIZE},
+    socket::{Listener, Stream, is_retryable},
+};
+use crate::{
+    Error, GrainFlags, GrainIndex, GrainWriter, Result,
+    fabrics::{Target, TargetConfig},
+};
+
+/// How much is read from a connection at once.
+const READ_CHUNK_SIZE: usize = 256 * 1024;
+
+/// How often sockets are polled while waiting for a grain.
+pub(super) const POLL_INTERVAL: Duration = Duration::from_micros(500);
+
+/// Size and slice count shared by all grains of the target flow.
+#[derive(Debug, Clone, Copy)]
+struct GrainLayout {
+    grain_size: u32,
+    total_slices: u16,
+}
+
+impl GrainLayout {
+    /// Fails if a frame with this header cannot be written to the target flow, before its payload
+    /// is buffered.
+    fn check(&self, header: &GrainHeader) -> Result<()> {
+        if header.grain_size != self.grain_size
+            || header.total_slices != self.total_slices
+            || header.payload_size > self.grain_size
+        {
+            return Err(Error::Other(format!(
+                "Received grain {} of {} bytes ({} bytes of payload) in {} slices does not match the target flow grains of {} bytes in {} slices.",
+                header.index,
+                header.grain_size,
+                header.payload_size,
+                header.total_slices,
+                self.grain_size,
+                self.total_slices
+            )));
+        }
+        Ok(())
+    }
+}
+
+struct Connection {
+    stream: Stream,
+    /// Holds at most one frame, so that a fast initiator is held back by the socket buffers
+    /// rather than by the memory of the target. Frames are bounded by the grain size of the
+    /// target flow.
+    buffer: Vec<u8>,
+    layout: GrainLayout,
+}
+
+impl Connection {
+    /// Number of bytes missing to complete the frame at the beginning of the buffer, zero once it
+    /// is complete. Fails if the header is corrupt or does not fit the target flow.
+    fn missing_bytes(&self) -> Result<usize> {
+        let expected = match GrainHeader::decode(&self.buffer)? {
+            Some(header) => {
+                self.layout.check(&header)?;
+                header.frame_size()
+            }
+            None => HEADER_SIZE,
+        };
+        Ok(expected.saturating_sub(self.buffer.len()))
+    }
+
+    /// Reads until a whole frame is buffered or nothing more is available. Returns `false` once
+    /// the connection has been closed by the initiator.
+    fn fill(&mut self) -> Result<bool> {
+        loop {
+            let missing = self.missing_bytes()?;
+            if missing == 0 {
+                return Ok(true);
+            }
+            let length = self.buffer.len();
+            self.buffer.resize(length + missing.min(READ_CHUNK_SIZE), 0);
+            let read = self.stream.read(&mut self.buffer[length..]);
+            self.buffer
+                .truncate(length + read.as_ref().map_or(0, |read| *read));
+            match read {
+                Ok(0) => return Ok(false),
+                Ok(_) => continue,
+                Err(error) if is_retryable(&error) => return Ok(true),
+                Err(error) => return Err(Error::from(error)),
+            }
+        }
+    }
+}
+
+/// Target of the local fabrics. Accepts any number of initiators and writes the grains they send
+/// into the flow of the owned writer.
+pub struct LocalTarget {
+    writer: GrainWriter,
+    listener: Listener,
+    address: LocalAddress,
+    layout: GrainLayout,
+    connections: Vec<Connection>,
+}
+
+impl LocalTarget {
+    pub(crate) fn new(config: TargetConfig) -> Result<(Self, LocalTargetInfo)> {
+        let writer = config.regions.into_writer()?;
+        // All entries of the ring buffer are created with the same size and slice count.
+        let grain_info = writer.grain_info(GrainIndex::new(0))?;
+        let layout = GrainLayout {
+            grain_size: grain_info.grainSize,
+            total_slices: grain_info.totalSlices,
+        };
+        let requested = LocalAddress::resolve(&config.endpoint_address, config.provider)?;
+        let listener = Listener::bind(&requested)?;
+        let address = listener.local_address(&requested)?;
+        let info = LocalTargetInfo {
+            address: address.clone(),
+        };
+        Ok((
+            Self {
+                writer,
+                listener,
+                address,
+                layout,
+                connections: Vec::new(),
+            },
+            info,
+        ))
+    }
+
+    fn accept_connections(&mut self) -> Result<()> {
+        while let Some(stream) = self.listener.accept()? {
+            stream.set_nonblocking(true)?;
+            self.connections.push(Connection {
+                stream,
+                buffer: Vec::new(),
+                layout: self.layout,
+            });
+        }
+        Ok(())
+    }
+
+    /// Reads the next frame of every connection, as far as it is available. Closed connections
+    /// are dropped, as are connections that fail or send corrupt frames.
+    fn read_connections(&mut self) {
+        self.connections
+            .retain_mut(|connection| match connection.fill() {
+                Ok(open) => open,
+                Err(error) => {
+                    tracing::warn!("Dropping local fabrics connection: {error}");
+                    false
+                }
+            });
+    }
+
+    /// Commits the first complete grain found in the connection buffers.
+    fn commit_next_grain(&mut self) -> Result<Option<GrainIndex>> {
+        for connection in self.connections.iter_mut() {
+            // Corrupt headers are dropped with their connection when reading.
+            let Ok(Some(header)) = GrainHeader::decode(&connection.buffer) else {
+                continue;
+            };
+            if connection.buffer.len() < header.frame_size() {
+                continue;
+            }
+            let payload = &connection.buffer[HEADER_SIZE..header.frame_size()];
+            let result = commit_grain(&mut self.writer, &header, payload);
+            connection.buffer.clear();
+            return result.map(|_| Some(GrainIndex::new(header.index)));
+        }
+        Ok(None)
+    }
+}
+
+fn commit_grain(writer: &mut GrainWriter, header: &GrainHeader, payload: &[u8]) -> Result<()> {
+    let mut access = writer.open_grain(GrainIndex::new(header.index))?;
+    if header.grain_size != access.max_size()
+        || header.total_slices != access.total_slices()
+        || payload.len() > access.max_size() as usize
+    {
+        return Err(Error::Other(format!(
+            "Received grain {} of {} bytes in {} slices does not match the target flow grains of {} bytes in {} slices.",
+            header.index,
+            header.grain_size,
+            header.total_slices,
+            access.max_size(),
+            access.total_slices()
+        )));
+    }
+    access.payload_mut()[..payload.len()].copy_from_slice(payload);
+    access.set_flags(GrainFlags::from_bits(header.flags));
+    access.commit(header.valid_slices)
+}
+
+impl Target for LocalTarget {
+    fn try_new_grain(&mut self) -> Result<Option<GrainIndex>> {
+        if let Some(index) = self.commit_next_grain()? {
+            return Ok(Some(index));
+        }
+        self.accept_connections()?;
+        self.read_connections();
+        self.commit_next_grain()
+    }
+
+    fn wait_for_new_grain(&mut self, timeout: Duration) -> Result<Option<GrainIndex>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(index) = self.try_new_grain()? {
+                return Ok(Some(index));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            std::thread::sleep(remaining.min(POLL_INTERVAL));
+        }
+    }
+}
+
+impl Drop for LocalTarget {
+    fn drop(&mut self) {
+        if let LocalAddress::Unix(path) = &self.address
+            && let Err(error) = std::fs::remove_file(path)
+        {
+            tracing::error!(
+                "Failed to remove local fabrics socket \"{}\": {:?}",
+                path.display(),
+                error
+            );
+        }
+    }
+}
@@ -0,0 +1,150 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod initiator;
+pub mod target;
+pub mod target_info;
+
+use std::{ffi::CString, sync::Arc, time::Duration};
+
+pub use initiator::OfiInitiator;
+pub use target::OfiTarget;
+pub use target_info::OfiTargetInfo;
+
+use super::{EndpointAddress, Fabrics, InitiatorConfig, TargetConfig};
+use crate::{Error, MxlInstance, Result, api::MxlFabricsApiHandle, instance::InstanceContext};
+
+/// Context shared by the fabrics instance and all the targets and initiators created from it.
+pub(crate) struct FabricsContext {
+    pub(crate) api: MxlFabricsApiHandle,
+    pub(crate) fabrics: mxl_sys::fabrics::mxlFabricsInstance,
+    /// The fabrics instance refers to the MXL instance, so it must be kept alive.
+    _instance: Arc<InstanceContext>,
+}
+
+// The fabrics instance follows the same threading rules as the MXL instance it is created from.
+unsafe impl Send for FabricsContext {}
+unsafe impl Sync for FabricsContext {}
+
+impl Drop for FabricsContext {
+    fn drop(&mut self) {
+        if let Err(err) = Error::from_status(unsafe { self.api.destroy_instance(self.fabrics) }) {
+            tracing::error!("Failed to destroy MXL fabrics instance: {:?}", err);
+        }
+    }
+}
+
+/// Fabrics implementation backed by the C fabrics library (`libmxl-fabrics.so`).
+#[derive(Clone)]
+pub struct OfiFabrics {
+    context: Arc<FabricsContext>,
+}
+
+impl OfiFabrics {
+    pub fn new(api: MxlFabricsApiHandle, instance: &MxlInstance) -> Result<Self> {
+        let instance = instance.context().clone();
+        let mut fabrics: mxl_sys::fabrics::mxlFabricsInstance = std::ptr::null_mut();
+        unsafe {
+            Error::from_status(api.create_instance(instance.instance, &mut fabrics))?;
+        }
+        if fabrics.is_null() {
+            return Err(Error::NullPointer("fabrics instance"));
+        }
+        Ok(Self {
+            context: Arc::new(FabricsContext {
+                api,
+                fabrics,
+                _instance: instance,
+            }),
+        })
+    }
+}
+
+impl Fabrics for OfiFabrics {
+    type TargetInfo = OfiTargetInfo;
+    type Target = OfiTarget;
+    type Initiator = OfiInitiator;
+
+    fn create_target(&self, config: TargetConfig) -> Result<(OfiTarget, OfiTargetInfo)> {
+        OfiTarget::new(self.context.clone(), config)
+    }
+
+    fn create_initiator(&self, config: InitiatorConfig) -> Result<OfiInitiator> {
+        OfiInitiator::new(self.context.clone(), config)
+    }
+}
+
+/// RAII wrapper of `mxlRegions`. The regions are only needed during the setup of a target or an
+/// initiator and can be freed afterwards.
+pub(crate) struct RawRegions<'a> {
+    context: &'a FabricsContext,
+    regions: mxl_sys::fabrics::mxlRegions,
+}
+
+impl<'a> RawRegions<'a> {
+    pub(crate) fn for_flow_reader(
+        context: &'a FabricsContext,
+        reader: mxl_sys::mxlFlowReader,
+    ) -> Result<Self> {
+        let mut regions: mxl_sys::fabrics::mxlRegions = std::ptr::null_mut();
+        unsafe {
+            Error::from_status(context.api.regions_for_flow_reader(reader, &mut regions))?;
+        }
+        Ok(Self { context, regions })
+    }
+
+    pub(crate) fn for_flow_writer(
+        context: &'a FabricsContext,
+        writer: mxl_sys::mxlFlowWriter,
+    ) -> Result<Self> {
+        let mut regions: mxl_sys::fabrics::mxlRegions = std::ptr::null_mut();
+        unsafe {
+            Error::from_status(context.api.regions_for_flow_writer(writer, &mut regions))?;
+        }
+        Ok(Self { context, regions })
+    }
+}
+
+impl Drop for RawRegions<'_> {
+    fn drop(&mut self) {
+        if !self.regions.is_null()
+            && let Err(err) =
+                Error::from_status(unsafe { self.context.api.regions_free(self.regions) })
+        {
+            tracing::error!("Failed to free MXL fabrics regions: {:?}", err);
+        }
+    }
+}
+
+/// Keeps the C strings of an endpoint address alive while the raw address is in use.
+pub(crate) struct RawEndpointAddress {
+    node: Option<CString>,
+    service: Option<CString>,
+}
+
+impl RawEndpointAddress {
+    pub(crate) fn new(address: &EndpointAddress) -> Result<Self> {
+        Ok(Self {
+            node: address.node.as_deref().map(CString::new).transpose()?,
+            service: address.service.as_deref().map(CString::new).transpose()?,
+        })
+    }
+
+    pub(crate) fn as_raw(&self) -> mxl_sys::fabrics::mxlEndpointAddress {
+        mxl_sys::fabrics::mxlEndpointAddress {
+            node: self
+                .node
+                .as_ref()
+                .map_or(std::ptr::null(), |node| node.as_ptr()),
+            service: self
+                .service
+                .as_ref()
+                .map_or(std::ptr::null(), |service| service.as_ptr()),
+        }
+    }
+}
+
+/// The C API takes timeouts in milliseconds as `u16`.
+pub(crate) fn timeout_to_ms(timeout: Duration) -> u16 {
+    timeout.as_millis().min(u16::MAX as u128) as u16
+}
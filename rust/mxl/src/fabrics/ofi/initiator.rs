@@ -0,0 +1,173 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{sync::Arc, time::Duration};
+
+use super::{
+    FabricsContext, OfiTargetInfo, RawEndpointAddress, RawRegions,
+    target_info::free_raw_target_info, timeout_to_ms,
+};
+use crate::{
+    Error, GrainIndex, GrainReader, Result,
+    fabrics::{Initiator, InitiatorConfig, Progress},
+};
+
+/// Fabrics initiator backed by the C fabrics library.
+pub struct OfiInitiator {
+    context: Arc<FabricsContext>,
+    initiator: mxl_sys::fabrics::mxlFabricsInitiator,
+    /// The registered memory regions belong to the flow of this reader.
+    reader: GrainReader,
+    /// Raw target infos handed over to the library. The same object is used to add and to remove
+    /// a target, and it is only freed once the initiator is destroyed, because the library may
+    /// still use it while shutting down the connection.
+    targets: Vec<(OfiTargetInfo, mxl_sys::fabrics::mxlTargetInfo)>,
+    removed_targets: Vec<mxl_sys::fabrics::mxlTargetInfo>,
+}
+
+// The fabrics initiator owns its libfabric endpoints, completion queue and the raw target
+// infos, none of which are tied to the thread that created them, so the initiator can be moved
+// to another thread. Adding targets, transferring grains and making progress all update the
+// endpoint and connection state without any locking on the C side, and the fabrics library
+// requests no particular libfabric threading level, so calls must never overlap and the
+// initiator is not `Sync`.
+unsafe impl Send for OfiInitiator {}
+
+impl OfiInitiator {
+    pub(crate) fn new(context: Arc<FabricsContext>, config: InitiatorConfig) -> Result<Self> {
+        let reader = config.regions.into_reader()?;
+
+        let mut initiator: mxl_sys::fabrics::mxlFabricsInitiator = std::ptr::null_mut();
+        unsafe {
+            Error::from_status(
+                context
+                    .api
+                    .create_initiator(context.fabrics, &mut initiator),
+            )?;
+        }
+        if initiator.is_null() {
+            return Err(Error::NullPointer("fabrics initiator"));
+        }
+        let result = Self {
+            context,
+            initiator,
+            reader,
+            targets: Vec::new(),
+            removed_targets: Vec::new(),
+        };
+
+        let regions = RawRegions::for_flow_reader(&result.context, result.reader.handle())?;
+        let address = RawEndpointAddress::new(&config.endpoint_address)?;
+        let raw_config = mxl_sys::fabrics::mxlInitiatorConfig {
+            endpointAddress: address.as_raw(),
+            provider: config.provider.to_raw(),
+            regions: regions.regions,
+            deviceSupport: config.device_support,
+        };
+        unsafe {
+            Error::from_status(
+                result
+                    .context
+                    .api
+                    .initiator_setup(result.initiator, &raw_config),
+            )?;
+        }
+        drop(regions);
+
+        Ok(result)
+    }
+}
+
+impl Initiator for OfiInitiator {
+    type TargetInfo = OfiTargetInfo;
+
+    fn add_target(&mut self, target_info: &OfiTargetInfo) -> Result<()> {
+        if self.targets.iter().any(|(info, _)| info == target_info) {
+            return Err(Error::Conflict);
+        }
+        let raw_info = target_info.to_raw(&self.context)?;
+        let status = unsafe {
+            self.context
+                .api
+                .initiator_add_target(self.initiator, raw_info)
+        };
+        if let Err(error) = Error::from_status(status) {
+            free_raw_target_info(&self.context, raw_info);
+            return Err(error);
+        }
+        self.targets.push((target_info.clone(), raw_info));
+        Ok(())
+    }
+
+    fn remove_target(&mut self, target_info: &OfiTargetInfo) -> Result<()> {
+        let position = self
+            .targets
+            .iter()
+            .position(|(info, _)| info == target_info)
+            .ok_or(Error::InvalidArg)?;
+        let raw_info = self.targets[position].1;
+        unsafe {
+            Error::from_status(
+                self.context
+                    .api
+                    .initiator_remove_target(self.initiator, raw_info),
+            )?;
+        }
+        self.targets.remove(position);
+        self.removed_targets.push(raw_info);
+        Ok(())
+    }
+
+    fn transfer_grain(&mut self, index: GrainIndex) -> Result<()> {
+        unsafe {
+            Error::from_status(
+                self.context
+                    .api
+                    .initiator_transfer_grain(self.initiator, index.value()),
+            )
+        }
+    }
+
+    fn make_progress_non_blocking(&mut self) -> Result<Progress> {
+        let status = unsafe {
+            self.context
+                .api
+                .initiator_make_progress_non_blocking(self.initiator)
+        };
+        status_to_progress(status)
+    }
+
+    fn make_progress_blocking(&mut self, timeout: Duration) -> Result<Progress> {
+        let status = unsafe {
+            self.context
+                .api
+                .initiator_make_progress_blocking(self.initiator, timeout_to_ms(timeout))
+        };
+        status_to_progress(status)
+    }
+}
+
+fn status_to_progress(status: mxl_sys::mxlStatus) -> Result<Progress> {
+    if status == mxl_sys::MXL_ERR_NOT_READY {
+        return Ok(Progress::Pending);
+    }
+    Error::from_status(status).map(|_| Progress::Done)
+}
+
+impl Drop for OfiInitiator {
+    fn drop(&mut self) {
+        if let Err(err) = Error::from_status(unsafe {
+            self.context
+                .api
+                .destroy_initiator(self.context.fabrics, self.initiator)
+        }) {
+            tracing::error!("Failed to destroy MXL fabrics initiator: {:?}", err);
+        }
+        for (_, raw_info) in self.targets.drain(..) {
+            free_raw_target_info(&self.context, raw_info);
+        }
+        for raw_info in self.removed_targets.drain(..) {
+            free_raw_target_info(&self.context, raw_info);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{sync::Arc, time::Duration};
+
+use super::{FabricsContext, OfiTargetInfo, RawEndpointAddress, RawRegions, timeout_to_ms};
+use crate::{
+    Error, GrainIndex, GrainWriter, Result,
+    fabrics::{Target, TargetConfig},
+};
+
+/// Fabrics target backed by the C fabrics library.
+///
+/// The grains are written directly into the flow by the remote initiator. Every time a new grain
+/// is reported by the library, it is committed through the owned writer so that local readers get
+/// notified.
+pub struct OfiTarget {
+    context: Arc<FabricsContext>,
+    target: mxl_sys::fabrics::mxlFabricsTarget,
+    writer: GrainWriter,
+}
+
+// The fabrics target owns its libfabric endpoint, completion queue and registered regions, none
+// of which are tied to the thread that created them, so the target can be moved to another
+// thread. Polling for new grains updates the endpoint state without any locking on the C side,
+// and the fabrics library requests no particular libfabric threading level, so calls must never
+// overlap and the target is not `Sync`.
+unsafe impl Send for OfiTarget {}
+
+impl OfiTarget {
+    pub(crate) fn new(
+        context: Arc<FabricsContext>,
+        config: TargetConfig,
+    ) -> Result<(Self, OfiTargetInfo)> {
+        let writer = config.regions.into_writer()?;
+
+        let mut target: mxl_sys::fabrics::mxlFabricsTarget = std::ptr::null_mut();
+        unsafe {
+            Error::from_status(context.api.create_target(context.fabrics, &mut target))?;
+        }
+        if target.is_null() {
+            return Err(Error::NullPointer("fabrics target"));
+        }
+        let result = Self {
+            context,
+            target,
+            writer,
+        };
+
+        let regions = RawRegions::for_flow_writer(&result.context, result.writer.handle())?;
+        let address = RawEndpointAddress::new(&config.endpoint_address)?;
+        let mut raw_config = mxl_sys::fabrics::mxlTargetConfig {
+            endpointAddress: address.as_raw(),
+            provider: config.provider.to_raw(),
+            regions: regions.regions,
+            deviceSupport: config.device_support,
+        };
+        let mut info: mxl_sys::fabrics::mxlTargetInfo = std::ptr::null_mut();
+        unsafe {
+            Error::from_status(result.context.api.target_setup(
+                result.target,
+                &mut raw_config,
+                &mut info,
+            ))?;
+        }
+        let info = OfiTargetInfo::from_raw(&result.context, info)?;
+        drop(regions);
+
+        Ok((result, info))
+    }
+
+    /// The grain payload and header have already been written by the initiator, the grain only
+    /// needs to be committed as is.
+    fn commit_received_grain(&mut self, index: GrainIndex) -> Result<GrainIndex> {
+        let mut access = self.writer.open_grain_as_stored(index)?;
+        let valid_slices = access.grain_info_mut().validSlices;
+        access.commit(valid_slices)?;
+        Ok(index)
+    }
+}
+
+impl Target for OfiTarget {
+    fn try_new_grain(&mut self) -> Result<Option<GrainIndex>> {
+        let mut index = 0u64;
+        let status = unsafe {
+            self.context
+                .api
+                .target_try_new_grain(self.target, &mut index)
+        };
+        if status == mxl_sys::MXL_ERR_NOT_READY {
+            return Ok(None);
+        }
+        Error::from_status(status)?;
+        self.commit_received_grain(GrainIndex::new(index)).map(Some)
+    }
+
+    fn wait_for_new_grain(&mut self, timeout: Duration) -> Result<Option<GrainIndex>> {
+        let mut index = 0u64;
+        let status = unsafe {
+            self.context.api.target_wait_for_new_grain(
+                self.target,
+                &mut index,
+                timeout_to_ms(timeout),
+            )
+        };
+        if status == mxl_sys::MXL_ERR_NOT_READY || status == mxl_sys::MXL_ERR_TIMEOUT {
+            return Ok(None);
+        }
+        Error::from_status(status)?;
+        self.commit_received_grain(GrainIndex::new(index)).map(Some)
+    }
+}
+
+impl Drop for OfiTarget {
+    fn drop(&mut self) {
+        if let Err(err) = Error::from_status(unsafe {
+            self.context
+                .api
+                .destroy_target(self.context.fabrics, self.target)
+        }) {
+            tracing::error!("Failed to destroy MXL fabrics target: {:?}", err);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ffi::CString;
+
+use super::FabricsContext;
+use crate::{Error, Result, fabrics::TargetInfo};
+
+/// Target info of the C fabrics library, kept in its serialized form.
+///
+/// The C `mxlTargetInfo` object is only materialized when an initiator needs it, so that this type
+/// can be freely cloned, sent across threads and parsed without access to the fabrics library.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OfiTargetInfo {
+    value: String,
+}
+
+impl OfiTargetInfo {
+    /// Takes ownership of the raw target info and serializes it.
+    pub(crate) fn from_raw(
+        context: &FabricsContext,
+        info: mxl_sys::fabrics::mxlTargetInfo,
+    ) -> Result<Self> {
+        let raw = RawTargetInfo { context, info };
+        let mut size = 0usize;
+        unsafe {
+            Error::from_status(context.api.target_info_to_string(
+                raw.info,
+                std::ptr::null_mut(),
+                &mut size,
+            ))?;
+        }
+        let mut buffer: Vec<u8> = vec![0; size];
+        unsafe {
+            Error::from_status(context.api.target_info_to_string(
+                raw.info,
+                buffer.as_mut_ptr() as *mut std::os::raw::c_char,
+                &mut size,
+            ))?;
+        }
+        buffer.truncate(size);
+        while buffer.last() == Some(&0) {
+            buffer.pop();
+        }
+        let value = String::from_utf8(buffer)
+            .map_err(|_| Error::Other("Invalid UTF-8 in fabrics target info".to_string()))?;
+        Ok(Self { value })
+    }
+
+    /// Parses the serialized form into a new raw target info. The caller owns the returned object
+    /// and must free it with `mxlFabricsFreeTargetInfo`.
+    pub(crate) fn to_raw(
+        &self,
+        context: &FabricsContext,
+    ) -> Result<mxl_sys::fabrics::mxlTargetInfo> {
+        let value = CString::new(self.value.as_str())?;
+        let mut info: mxl_sys::fabrics::mxlTargetInfo = std::ptr::null_mut();
+        unsafe {
+            Error::from_status(
+                context
+                    .api
+                    .target_info_from_string(value.as_ptr(), &mut info),
+            )?;
+        }
+        if info.is_null() {
+            return Err(Error::NullPointer("fabrics target info"));
+        }
+        Ok(info)
+    }
+}
+
+impl TargetInfo for OfiTargetInfo {
+    fn serialize(&self) -> Result<String> {
+        Ok(self.value.clone())
+    }
+
+    fn deserialize(value: &str) -> Result<Self> {
+        if value.is_empty() {
+            return Err(Error::InvalidArg);
+        }
+        Ok(Self {
+            value: value.to_string(),
+        })
+    }
+}
+
+/// Frees the raw target info once it has been serialized.
+struct RawTargetInfo<'a> {
+    context: &'a FabricsContext,
+    info: mxl_sys::fabrics::mxlTargetInfo,
+}
+
+impl Drop for RawTargetInfo<'_> {
+    fn drop(&mut self) {
+        free_raw_target_info(self.context, self.info);
+    }
+}
+
+pub(crate) fn free_raw_target_info(
+    context: &FabricsContext,
+    info: mxl_sys::fabrics::mxlTargetInfo,
+) {
+    if !info.is_null()
+        && let Err(err) = Error::from_status(unsafe { context.api.free_target_info(info) })
+    {
+        tracing::error!("Failed to free MXL fabrics target info: {:?}", err);
+    }
+}
@@ -1,12 +1,15 @@
 // SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod def;
 pub mod reader;
 pub mod writer;
 
+use std::time::Duration;
+
 use uuid::Uuid;
 
-use crate::{Error, Result};
+use crate::{Error, GrainIndex, GrainRate, Rational, Result, SampleIndex, SampleRate, Timestamp};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DataFormat {
@@ -35,11 +38,30 @@ pub(crate) fn is_discrete_data_format(format: u32) -> bool {
     format == mxl_sys::MXL_DATA_FORMAT_VIDEO || format == mxl_sys::MXL_DATA_FORMAT_DATA
 }
 
+/// The info accessors shared by the flow readers, generic or typed.
+pub trait FlowRead {
+    fn flow_id(&self) -> Uuid;
+
+    /// The config of the flow, captured when the reader was created. It does not change during
+    /// the lifetime of the flow.
+    fn config_info(&self) -> &FlowConfigInfo;
+
+    /// The whole FlowInfo is quite a chunk of data. Go for `config_info` or `get_runtime_info`
+    /// if they contain what you need.
+    fn get_info(&self) -> Result<FlowInfo>;
+
+    /// Reads the config of the flow from the library again.
+    fn get_config_info(&self) -> Result<FlowConfigInfo>;
+
+    fn get_runtime_info(&self) -> Result<FlowRuntimeInfo>;
+}
+
 pub struct FlowInfo {
     pub config: FlowConfigInfo,
     pub runtime: FlowRuntimeInfo,
 }
 
+#[derive(Clone)]
 pub struct FlowConfigInfo {
     pub(crate) value: mxl_sys::mxlFlowConfigInfo,
 }
@@ -47,20 +69,20 @@ pub struct FlowConfigInfo {
 impl FlowConfigInfo {
     pub fn discrete(&self) -> Result<&mxl_sys::mxlDiscreteFlowConfigInfo> {
         if !is_discrete_data_format(self.value.common.format) {
-            return Err(Error::Other(format!(
-                "Flow format is {}, video or data required.",
-                self.value.common.format
-            )));
+            return Err(Error::FlowFormatMismatch {
+                expected: "video or data",
+                actual: DataFormat::from(self.value.common.format),
+            });
         }
         Ok(unsafe { &self.value.__bindgen_anon_1.discrete })
     }
 
     pub fn continuous(&self) -> Result<&mxl_sys::mxlContinuousFlowConfigInfo> {
         if is_discrete_data_format(self.value.common.format) {
-            return Err(Error::Other(format!(
-                "Flow format is {}, audio required.",
-                self.value.common.format
-            )));
+            return Err(Error::FlowFormatMismatch {
+                expected: "audio",
+                actual: DataFormat::from(self.value.common.format),
+            });
         }
         Ok(unsafe { &self.value.__bindgen_anon_1.continuous })
     }
@@ -89,30 +111,30 @@ impl CommonFlowConfigInfo<'_> {
         is_discrete_data_format(self.0.format)
     }
 
-    pub fn grain_or_sample_rate(&self) -> mxl_sys::mxlRational {
-        self.0.grainRate
+    pub fn grain_or_sample_rate(&self) -> Result<Rational> {
+        Rational::try_from(self.0.grainRate)
     }
 
-    pub fn grain_rate(&self) -> Result<mxl_sys::mxlRational> {
+    pub fn grain_rate(&self) -> Result<GrainRate> {
         let data_format = self.data_format();
         if data_format != DataFormat::Video && data_format != DataFormat::Data {
-            return Err(Error::Other(format!(
-                "Flow format is {:?}, grain rate is only relevant for discrete flows.",
-                data_format
-            )));
+            return Err(Error::FlowFormatMismatch {
+                expected: "video or data",
+                actual: data_format,
+            });
         }
-        Ok(self.0.grainRate)
+        GrainRate::new(self.grain_or_sample_rate()?)
     }
 
-    pub fn sample_rate(&self) -> Result<mxl_sys::mxlRational> {
+    pub fn sample_rate(&self) -> Result<SampleRate> {
         let data_format = self.data_format();
         if data_format != DataFormat::Audio {
-            return Err(Error::Other(format!(
-                "Flow format is {:?}, sample rate is only relevant for continuous flows.",
-                data_format
-            )));
+            return Err(Error::FlowFormatMismatch {
+                expected: "audio",
+                actual: data_format,
+            });
         }
-        Ok(self.0.grainRate)
+        SampleRate::new(self.grain_or_sample_rate()?)
     }
 
     pub fn max_commit_batch_size_hint(&self) -> u32 {
@@ -134,18 +156,79 @@ impl CommonFlowConfigInfo<'_> {
 
 pub struct FlowRuntimeInfo {
     pub(crate) value: mxl_sys::mxlFlowRuntimeInfo,
+    /// Format of the flow, which tells whether the head counts grains or samples.
+    pub(crate) format: u32,
 }
 
 impl FlowRuntimeInfo {
-    pub fn head_index(&self) -> u64 {
-        self.value.headIndex
+    /// Index of the last grain committed to a discrete flow.
+    pub fn grain_head_index(&self) -> Result<GrainIndex> {
+        if !is_discrete_data_format(self.format) {
+            return Err(Error::FlowFormatMismatch {
+                expected: "video or data",
+                actual: DataFormat::from(self.format),
+            });
+        }
+        Ok(GrainIndex::new(self.value.headIndex))
+    }
+
+    /// Index following the last sample committed to a continuous flow.
+    pub fn sample_head_index(&self) -> Result<SampleIndex> {
+        if is_discrete_data_format(self.format) {
+            return Err(Error::FlowFormatMismatch {
+                expected: "audio",
+                actual: DataFormat::from(self.format),
+            });
+        }
+        Ok(SampleIndex::new(self.value.headIndex))
     }
 
-    pub fn last_write_time(&self) -> u64 {
-        self.value.lastWriteTime
+    pub fn last_write_time(&self) -> Timestamp {
+        Timestamp::from_nanos(self.value.lastWriteTime)
+    }
+
+    pub fn last_read_time(&self) -> Timestamp {
+        Timestamp::from_nanos(self.value.lastReadTime)
+    }
+}
+
+/// Liveness of a flow as seen by a supervisor, see `MxlInstance::get_flow_status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowStatus {
+    /// No writer has the flow open anymore.
+    WriterGone,
+    /// A writer has the flow open, but nothing has ever been written to the flow. This is the
+    /// state of a writer that is still starting up, rather than a stalled one.
+    Starting,
+    /// A writer has the flow open, but has not written anything for longer than the stall
+    /// threshold.
+    Stalled { idle: Duration },
+    /// A writer has the flow open and wrote recently.
+    Healthy { idle: Duration },
+}
+
+impl FlowStatus {
+    pub(crate) fn new(
+        is_active: bool,
+        last_write_time: Timestamp,
+        now: Timestamp,
+        stall_threshold: Duration,
+    ) -> Self {
+        if !is_active {
+            return FlowStatus::WriterGone;
+        }
+        if last_write_time == Timestamp::EPOCH {
+            return FlowStatus::Starting;
+        }
+        let idle = now.saturating_duration_since(last_write_time);
+        if idle > stall_threshold {
+            FlowStatus::Stalled { idle }
+        } else {
+            FlowStatus::Healthy { idle }
+        }
     }
 
-    pub fn last_read_time(&self) -> u64 {
-        self.value.lastReadTime
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, FlowStatus::Healthy { .. })
     }
 }
@@ -0,0 +1,444 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed NMOS IS-04 flow definitions, the JSON documents accepted by `MxlInstance::create_flow`.
+//!
+//! `FlowDef::validate` applies the same rules as the flow parser of the MXL library, so that
+//! mistakes are reported before the definition reaches it.
+
+use uuid::Uuid;
+
+use crate::{
+    Error, Rational, Result,
+    json::{json_array, json_object, json_rational, json_string},
+};
+
+/// Tag holding the group hints of a flow, required by MXL.
+pub const GROUP_HINT_TAG: &str = "urn:x-nmos:tag:grouphint/v1.0";
+
+// Same limits as the flow parser of the MXL library.
+const MAX_FRAME_WIDTH: u32 = 7680;
+const MAX_FRAME_HEIGHT: u32 = 4320;
+
+#[derive(Debug, Clone)]
+pub struct FlowDef {
+    pub id: Uuid,
+    pub label: String,
+    pub description: String,
+    /// NMOS tags, in order. MXL requires the group hint tag.
+    pub tags: Vec<(String, Vec<String>)>,
+    pub parents: Vec<Uuid>,
+    pub source_id: Option<Uuid>,
+    pub device_id: Option<Uuid>,
+    pub media: FlowMedia,
+}
+
+/// The format specific part of a flow definition.
+#[derive(Debug, Clone)]
+pub enum FlowMedia {
+    Video(VideoFlowDef),
+    Audio(AudioFlowDef),
+    Data(DataFlowDef),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoMediaType {
+    /// 10 bit 4:2:2, `video/v210`.
+    V210,
+    /// 10 bit 4:2:2 with a 10 bit alpha plane, `video/v210a`.
+    V210a,
+}
+
+impl VideoMediaType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoMediaType::V210 => "video/v210",
+            VideoMediaType::V210a => "video/v210a",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InterlaceMode {
+    #[default]
+    Progressive,
+    InterlacedTff,
+    InterlacedBff,
+}
+
+impl InterlaceMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InterlaceMode::Progressive => "progressive",
+            InterlaceMode::InterlacedTff => "interlaced_tff",
+            InterlaceMode::InterlacedBff => "interlaced_bff",
+        }
+    }
+
+    pub fn is_interlaced(&self) -> bool {
+        *self != InterlaceMode::Progressive
+    }
+}
+
+/// A component of the video picture, as listed in the `components` of an NMOS video flow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoComponent {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub bit_depth: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct VideoFlowDef {
+    pub media_type: VideoMediaType,
+    /// Frame rate. Interlaced flows are stored as fields, at twice this rate.
+    pub grain_rate: Rational,
+    pub frame_width: u32,
+    pub frame_height: u32,
+    pub interlace_mode: InterlaceMode,
+    pub colorspace: String,
+    pub components: Vec<VideoComponent>,
+}
+
+impl VideoFlowDef {
+    /// A progressive BT709 flow, with the Y, Cb and Cr components of 10 bit 4:2:2 video.
+    pub fn new(
+        media_type: VideoMediaType,
+        grain_rate: Rational,
+        frame_width: u32,
+        frame_height: u32,
+    ) -> Self {
+        let component = |name: &str, width| VideoComponent {
+            name: name.to_string(),
+            width,
+            height: frame_height,
+            bit_depth: 10,
+        };
+        Self {
+            media_type,
+            grain_rate,
+            frame_width,
+            frame_height,
+            interlace_mode: InterlaceMode::Progressive,
+            colorspace: "BT709".to_string(),
+            components: vec![
+                component("Y", frame_width),
+                component("Cb", frame_width / 2),
+                component("Cr", frame_width / 2),
+            ],
+        }
+    }
+
+    pub fn interlace_mode(mut self, interlace_mode: InterlaceMode) -> Self {
+        self.interlace_mode = interlace_mode;
+        self
+    }
+
+    pub fn colorspace(mut self, colorspace: impl Into<String>) -> Self {
+        self.colorspace = colorspace.into();
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AudioFlowDef {
+    pub media_type: String,
+    pub sample_rate: Rational,
+    pub channel_count: u32,
+    /// Bits per sample, MXL supports 32 and 64.
+    pub bit_depth: u32,
+}
+
+impl AudioFlowDef {
+    /// Single precision float samples, `audio/float32`.
+    pub fn float32(sample_rate: Rational, channel_count: u32) -> Self {
+        Self {
+            media_type: "audio/float32".to_string(),
+            sample_rate,
+            channel_count,
+            bit_depth: 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DataFlowDef {
+    /// MXL only supports SMPTE ST 291 ancillary data, `video/smpte291`.
+    pub media_type: String,
+    pub grain_rate: Rational,
+}
+
+impl DataFlowDef {
+    pub fn smpte291(grain_rate: Rational) -> Self {
+        Self {
+            media_type: "video/smpte291".to_string(),
+            grain_rate,
+        }
+    }
+}
+
+impl FlowDef {
+    /// A flow with a random ID. The label is also used as description, and the group hint is
+    /// `"<label>:<role>"`, where the role is the kind of media and the colons of the label are
+    /// replaced by spaces.
+    pub fn new(label: impl Into<String>, media: FlowMedia) -> Self {
+        let label = label.into();
+        let role = match media {
+            FlowMedia::Video(_) => "Video",
+            FlowMedia::Audio(_) => "Audio",
+            FlowMedia::Data(_) => "Data",
+        };
+        Self {
+            id: Uuid::new_v4(),
+            description: label.clone(),
+            tags: vec![(
+                GROUP_HINT_TAG.to_string(),
+                vec![format!("{}:{role}", label.replace(':', " "))],
+            )],
+            label,
+            parents: Vec::new(),
+            source_id: None,
+            device_id: None,
+            media,
+        }
+    }
+
+    pub fn video(label: impl Into<String>, video: VideoFlowDef) -> Self {
+        Self::new(label, FlowMedia::Video(video))
+    }
+
+    pub fn audio(label: impl Into<String>, audio: AudioFlowDef) -> Self {
+        Self::new(label, FlowMedia::Audio(audio))
+    }
+
+    pub fn data(label: impl Into<String>, data: DataFlowDef) -> Self {
+        Self::new(label, FlowMedia::Data(data))
+    }
+
+    /// 1920x1080 interlaced v210 at 25 frames per second, top field first.
+    pub fn v210_1080i50() -> Self {
+        let video = VideoFlowDef::new(VideoMediaType::V210, Rational::FPS_25, 1920, 1080)
+            .interlace_mode(InterlaceMode::InterlacedTff);
+        Self::video("1080i50 v210", video)
+    }
+
+    /// 3840x2160 progressive v210 with alpha at 60000/1001 frames per second.
+    pub fn v210a_2160p59_94() -> Self {
+        let video = VideoFlowDef::new(VideoMediaType::V210a, Rational::FPS_59_94, 3840, 2160)
+            .colorspace("BT2020");
+        Self::video("2160p59.94 v210a", video)
+    }
+
+    /// 48 kHz single precision float audio with `channel_count` channels.
+    pub fn float32_48khz(channel_count: u32) -> Self {
+        let audio = AudioFlowDef::float32(Rational::HZ_48000, channel_count);
+        Self::audio(format!("48kHz float32 {channel_count}ch"), audio)
+    }
+
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Sets the values of the tag `name`, replacing the previous ones.
+    pub fn tag(mut self, name: impl Into<String>, values: Vec<String>) -> Self {
+        let name = name.into();
+        match self.tags.iter_mut().find(|(tag, _)| *tag == name) {
+            Some((_, existing)) => *existing = values,
+            None => self.tags.push((name, values)),
+        }
+        self
+    }
+
+    /// Replaces the group hints with `group_hint`, formatted as
+    /// `"<group-name>:<role-in-group>[:<group-scope>]"`.
+    pub fn group_hint(self, group_hint: impl Into<String>) -> Self {
+        self.tag(GROUP_HINT_TAG, vec![group_hint.into()])
+    }
+
+    pub fn parent(mut self, parent: Uuid) -> Self {
+        self.parents.push(parent);
+        self
+    }
+
+    pub fn source_id(mut self, source_id: Uuid) -> Self {
+        self.source_id = Some(source_id);
+        self
+    }
+
+    pub fn device_id(mut self, device_id: Uuid) -> Self {
+        self.device_id = Some(device_id);
+        self
+    }
+
+    /// Checks the definition against the rules of the MXL flow parser.
+    pub fn validate(&self) -> Result<()> {
+        if self.label.is_empty() {
+            return Err(invalid("the label is empty"));
+        }
+        self.validate_group_hints()?;
+
+        match &self.media {
+            FlowMedia::Video(video) => {
+                video.grain_rate.validate_rate()?;
+                if !(2..=MAX_FRAME_WIDTH).contains(&video.frame_width)
+                    || !(1..=MAX_FRAME_HEIGHT).contains(&video.frame_height)
+                {
+                    return Err(Error::InvalidFlowDef(format!(
+                        "invalid video dimensions {}x{}, the range is 2x1 to \
+                         {MAX_FRAME_WIDTH}x{MAX_FRAME_HEIGHT}",
+                        video.frame_width, video.frame_height
+                    )));
+                }
+                if video.interlace_mode.is_interlaced() {
+                    let rate = &video.grain_rate;
+                    if *rate != Rational::FPS_29_97 && *rate != Rational::FPS_25 {
+                        return Err(Error::InvalidFlowDef(format!(
+                            "invalid grain rate {rate} for interlaced video, 30000/1001 or 25/1 \
+                             expected"
+                        )));
+                    }
+                    if video.frame_height % 2 != 0 {
+                        return Err(invalid("the height of interlaced video must be even"));
+                    }
+                }
+            }
+            FlowMedia::Audio(audio) => {
+                audio.sample_rate.validate_rate()?;
+                if audio.channel_count == 0 {
+                    return Err(invalid("the channel count must be positive"));
+                }
+                if audio.bit_depth != 32 && audio.bit_depth != 64 {
+                    return Err(Error::InvalidFlowDef(format!(
+                        "unsupported audio bit depth {}",
+                        audio.bit_depth
+                    )));
+                }
+            }
+            FlowMedia::Data(data) => {
+                data.grain_rate.validate_rate()?;
+                if data.media_type != "video/smpte291" {
+                    return Err(Error::InvalidFlowDef(format!(
+                        "unsupported data media type \"{}\"",
+                        data.media_type
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_group_hints(&self) -> Result<()> {
+        let group_hints = self
+            .tags
+            .iter()
+            .find(|(tag, _)| tag == GROUP_HINT_TAG)
+            .map(|(_, values)| values)
+            .ok_or_else(|| invalid("the group hint tag is missing"))?;
+        if group_hints.is_empty() {
+            return Err(invalid("the group hint tag is empty"));
+        }
+        for group_hint in group_hints {
+            let parts: Vec<&str> = group_hint.split(':').collect();
+            let valid = matches!(
+                parts.as_slice(),
+                [name, role] | [name, role, "device" | "node"]
+                    if !name.is_empty() && !role.is_empty()
+            );
+            if !valid {
+                return Err(Error::InvalidFlowDef(format!(
+                    "invalid group hint \"{group_hint}\", \
+                     \"<group-name>:<role-in-group>[:device|node]\" expected"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Serializes the definition to the NMOS JSON document expected by `create_flow`.
+    pub fn to_json(&self) -> String {
+        let mut fields = vec![
+            ("id", json_string(&self.id.to_string())),
+            ("description", json_string(&self.description)),
+            (
+                "tags",
+                json_object(
+                    self.tags
+                        .iter()
+                        .map(|(tag, values)| {
+                            (
+                                tag.as_str(),
+                                json_array(values.iter().map(|v| json_string(v))),
+                            )
+                        })
+                        .collect(),
+                ),
+            ),
+        ];
+        let format = match self.media {
+            FlowMedia::Video(_) => "urn:x-nmos:format:video",
+            FlowMedia::Audio(_) => "urn:x-nmos:format:audio",
+            FlowMedia::Data(_) => "urn:x-nmos:format:data",
+        };
+        fields.push(("format", json_string(format)));
+        fields.push(("label", json_string(&self.label)));
+        fields.push((
+            "parents",
+            json_array(self.parents.iter().map(|p| json_string(&p.to_string()))),
+        ));
+        if let Some(source_id) = self.source_id {
+            fields.push(("source_id", json_string(&source_id.to_string())));
+        }
+        if let Some(device_id) = self.device_id {
+            fields.push(("device_id", json_string(&device_id.to_string())));
+        }
+
+        match &self.media {
+            FlowMedia::Video(video) => {
+                fields.push(("media_type", json_string(video.media_type.as_str())));
+                fields.push(("grain_rate", json_rational(&video.grain_rate)));
+                fields.push(("frame_width", video.frame_width.to_string()));
+                fields.push(("frame_height", video.frame_height.to_string()));
+                fields.push(("interlace_mode", json_string(video.interlace_mode.as_str())));
+                fields.push(("colorspace", json_string(&video.colorspace)));
+                fields.push((
+                    "components",
+                    json_array(video.components.iter().map(|component| {
+                        json_object(vec![
+                            ("name", json_string(&component.name)),
+                            ("width", component.width.to_string()),
+                            ("height", component.height.to_string()),
+                            ("bit_depth", component.bit_depth.to_string()),
+                        ])
+                    })),
+                ));
+            }
+            FlowMedia::Audio(audio) => {
+                fields.push(("media_type", json_string(&audio.media_type)));
+                fields.push(("sample_rate", json_rational(&audio.sample_rate)));
+                fields.push(("channel_count", audio.channel_count.to_string()));
+                fields.push(("bit_depth", audio.bit_depth.to_string()));
+            }
+            FlowMedia::Data(data) => {
+                fields.push(("media_type", json_string(&data.media_type)));
+                fields.push(("grain_rate", json_rational(&data.grain_rate)));
+            }
+        }
+        json_object(fields)
+    }
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidFlowDef(reason.to_string())
+}
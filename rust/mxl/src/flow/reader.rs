@@ -4,7 +4,9 @@
 use std::sync::Arc;
 
 use crate::{
-    DataFormat, Error, FlowConfigInfo, FlowRuntimeInfo, GrainReader, Result, SamplesReader,
+    DataFormat, Error, FlowConfigInfo, FlowRead, FlowRuntimeInfo, GrainReader, Result,
+    SamplesReader,
+    error::{ErrorContext, ResultExt},
     flow::{FlowInfo, is_discrete_data_format},
     instance::InstanceContext,
 };
@@ -12,12 +14,22 @@ use crate::{
 pub struct FlowReader {
     context: Arc<InstanceContext>,
     reader: mxl_sys::mxlFlowReader,
+    id: uuid::Uuid,
+    config: FlowConfigInfo,
 }
 
 /// The MXL readers and writers are not thread-safe, so we do not implement `Sync` for them, but
 /// there is no reason to not implement `Send`.
 unsafe impl Send for FlowReader {}
 
+/// A flow reader of the kind the format of the flow requires, see `MxlInstance::open_flow`.
+pub enum OpenedFlow {
+    /// Video or data flow.
+    Discrete(GrainReader),
+    /// Audio flow.
+    Continuous(SamplesReader),
+}
+
 pub(crate) fn get_flow_info(
     context: &Arc<InstanceContext>,
     reader: mxl_sys::mxlFlowReader,
@@ -32,6 +44,7 @@ pub(crate) fn get_flow_info(
         },
         runtime: FlowRuntimeInfo {
             value: flow_info.runtime,
+            format: flow_info.config.common.format,
         },
     })
 }
@@ -51,10 +64,12 @@ pub(crate) fn get_config_info(
     Ok(FlowConfigInfo { value: config_info })
 }
 
+/// `config` is the one captured with the reader, which tells what the head index counts.
 pub(crate) fn get_runtime_info(
     context: &Arc<InstanceContext>,
     reader: mxl_sys::mxlFlowReader,
-) -> Result<mxl_sys::mxlFlowRuntimeInfo> {
+    config: &FlowConfigInfo,
+) -> Result<FlowRuntimeInfo> {
     let mut runtime_info: mxl_sys::mxlFlowRuntimeInfo = unsafe { std::mem::zeroed() };
     unsafe {
         Error::from_status(
@@ -63,42 +78,131 @@ pub(crate) fn get_runtime_info(
                 .flow_reader_get_runtime_info(reader, &mut runtime_info),
         )?;
     }
-    Ok(runtime_info)
+    Ok(FlowRuntimeInfo {
+        value: runtime_info,
+        format: config.value.common.format,
+    })
 }
 
 impl FlowReader {
-    pub(crate) fn new(context: Arc<InstanceContext>, reader: mxl_sys::mxlFlowReader) -> Self {
-        Self { context, reader }
+    /// Takes ownership of `reader` and captures the config of the flow. The reader is released if
+    /// the config cannot be read.
+    pub(crate) fn new(
+        context: Arc<InstanceContext>,
+        reader: mxl_sys::mxlFlowReader,
+        id: uuid::Uuid,
+    ) -> Result<Self> {
+        match get_config_info(&context, reader) {
+            Ok(config) => Ok(Self {
+                context,
+                reader,
+                id,
+                config,
+            }),
+            Err(error) => {
+                if let Err(err) = Error::from_status(unsafe {
+                    context.api.release_flow_reader(context.instance, reader)
+                }) {
+                    tracing::error!("Failed to release MXL flow reader: {:?}", err);
+                }
+                Err(error.with_context(ErrorContext::new("get flow config").flow_id(id)))
+            }
+        }
+    }
+
+    pub fn flow_id(&self) -> uuid::Uuid {
+        self.id
+    }
+
+    /// The config of the flow, captured when the reader was created.
+    pub fn config_info(&self) -> &FlowConfigInfo {
+        &self.config
     }
 
     pub fn get_info(&self) -> Result<FlowInfo> {
         get_flow_info(&self.context, self.reader)
+            .context(|| ErrorContext::new("get flow info").flow_id(self.id))
+    }
+
+    pub fn get_config_info(&self) -> Result<FlowConfigInfo> {
+        get_config_info(&self.context, self.reader)
+            .context(|| ErrorContext::new("get flow config").flow_id(self.id))
     }
 
-    pub fn to_grain_reader(mut self) -> Result<GrainReader> {
-        let flow_type = self.get_info()?.config.value.common.format;
+    pub fn get_runtime_info(&self) -> Result<FlowRuntimeInfo> {
+        get_runtime_info(&self.context, self.reader, &self.config)
+            .context(|| ErrorContext::new("get flow runtime info").flow_id(self.id))
+    }
+
+    /// Turns the reader into the grain or samples reader the format of the flow requires. Fails
+    /// with `Error::FlowFormatMismatch` at the root for flows that are neither discrete nor audio
+    /// flows.
+    pub fn to_typed_reader(self) -> Result<OpenedFlow> {
+        if self.config.is_discrete_flow() {
+            Ok(OpenedFlow::Discrete(self.into_grain_reader()))
+        } else {
+            self.to_samples_reader().map(OpenedFlow::Continuous)
+        }
+    }
+
+    pub fn to_grain_reader(self) -> Result<GrainReader> {
+        let flow_type = self.config.value.common.format;
         if !is_discrete_data_format(flow_type) {
-            return Err(Error::Other(format!(
-                "Cannot convert MxlFlowReader to GrainReader for continuous flow of type \"{:?}\".",
-                DataFormat::from(flow_type)
-            )));
+            return Err(Error::FlowFormatMismatch {
+                expected: "video or data",
+                actual: DataFormat::from(flow_type),
+            }
+            .with_context(ErrorContext::new("create grain reader").flow_id(self.id)));
         }
-        let result = GrainReader::new(self.context.clone(), self.reader);
-        self.reader = std::ptr::null_mut();
-        Ok(result)
+        Ok(self.into_grain_reader())
     }
 
-    pub fn to_samples_reader(mut self) -> Result<SamplesReader> {
-        let flow_type = self.get_info()?.config.value.common.format;
-        if is_discrete_data_format(flow_type) {
-            return Err(Error::Other(format!(
-                "Cannot convert MxlFlowReader to SamplesReader for discrete flow of type \"{:?}\".",
-                DataFormat::from(flow_type)
-            )));
+    pub fn to_samples_reader(self) -> Result<SamplesReader> {
+        let flow_type = self.config.value.common.format;
+        if DataFormat::from(flow_type) != DataFormat::Audio {
+            return Err(Error::FlowFormatMismatch {
+                expected: "audio",
+                actual: DataFormat::from(flow_type),
+            }
+            .with_context(ErrorContext::new("create samples reader").flow_id(self.id)));
         }
-        let result = SamplesReader::new(self.context.clone(), self.reader);
+        Ok(self.into_samples_reader())
+    }
+
+    fn into_grain_reader(mut self) -> GrainReader {
+        let config = self.config.clone();
+        let result = GrainReader::new(self.context.clone(), self.reader, self.id, config);
+        self.reader = std::ptr::null_mut();
+        result
+    }
+
+    fn into_samples_reader(mut self) -> SamplesReader {
+        let config = self.config.clone();
+        let result = SamplesReader::new(self.context.clone(), self.reader, self.id, config);
         self.reader = std::ptr::null_mut();
-        Ok(result)
+        result
+    }
+}
+
+impl FlowRead for FlowReader {
+    fn flow_id(&self) -> uuid::Uuid {
+        self.flow_id()
+    }
+
+    fn config_info(&self) -> &FlowConfigInfo {
+        self.config_info()
+    }
+
+    fn get_info(&self) -> Result<FlowInfo> {
+        self.get_info()
+    }
+
+    fn get_config_info(&self) -> Result<FlowConfigInfo> {
+        self.get_config_info()
+    }
+
+    fn get_runtime_info(&self) -> Result<FlowRuntimeInfo> {
+        self.get_runtime_info()
     }
 }
 
@@ -115,3 +219,38 @@ impl Drop for FlowReader {
         }
     }
 }
+
+impl OpenedFlow {
+    pub fn is_discrete_flow(&self) -> bool {
+        matches!(self, OpenedFlow::Discrete(_))
+    }
+
+    fn as_flow_read(&self) -> &dyn FlowRead {
+        match self {
+            OpenedFlow::Discrete(reader) => reader,
+            OpenedFlow::Continuous(reader) => reader,
+        }
+    }
+}
+
+impl FlowRead for OpenedFlow {
+    fn flow_id(&self) -> uuid::Uuid {
+        self.as_flow_read().flow_id()
+    }
+
+    fn config_info(&self) -> &FlowConfigInfo {
+        self.as_flow_read().config_info()
+    }
+
+    fn get_info(&self) -> Result<FlowInfo> {
+        self.as_flow_read().get_info()
+    }
+
+    fn get_config_info(&self) -> Result<FlowConfigInfo> {
+        self.as_flow_read().get_config_info()
+    }
+
+    fn get_runtime_info(&self) -> Result<FlowRuntimeInfo> {
+        self.as_flow_read().get_runtime_info()
+    }
+}
@@ -4,7 +4,8 @@
 use std::sync::Arc;
 
 use crate::{
-    DataFormat, Error, GrainWriter, Result, SamplesWriter,
+    DataFormat, Error, FlowConfigInfo, GrainWriter, Result, SamplesWriter,
+    error::ErrorContext,
     flow::is_discrete_data_format,
     instance::{InstanceContext, create_flow_reader},
 };
@@ -15,6 +16,7 @@ pub struct FlowWriter {
     context: Arc<InstanceContext>,
     writer: mxl_sys::mxlFlowWriter,
     id: uuid::Uuid,
+    config: FlowConfigInfo,
 }
 
 /// The MXL readers and writers are not thread-safe, so we do not implement `Sync` for them, but
@@ -22,59 +24,86 @@ pub struct FlowWriter {
 unsafe impl Send for FlowWriter {}
 
 impl FlowWriter {
+    /// Takes ownership of `writer` and captures the config of the flow. The writer is released if
+    /// the config cannot be read.
     pub(crate) fn new(
         context: Arc<InstanceContext>,
         writer: mxl_sys::mxlFlowWriter,
         id: uuid::Uuid,
+    ) -> Result<Self> {
+        // MXL only provides the flow config through a reader, so a reader is opened once here, and
+        // the typed writers carry the config from then on.
+        match create_flow_reader(&context, &id.to_string()) {
+            Ok(reader) => Ok(Self::with_config(
+                context,
+                writer,
+                id,
+                reader.config_info().clone(),
+            )),
+            Err(error) => {
+                if let Err(err) = Error::from_status(unsafe {
+                    context.api.release_flow_writer(context.instance, writer)
+                }) {
+                    tracing::error!("Failed to release MXL flow writer: {:?}", err);
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Takes ownership of `writer`, the config of the flow being already known.
+    pub(crate) fn with_config(
+        context: Arc<InstanceContext>,
+        writer: mxl_sys::mxlFlowWriter,
+        id: uuid::Uuid,
+        config: FlowConfigInfo,
     ) -> Self {
         Self {
             context,
             writer,
             id,
+            config,
         }
     }
 
+    pub fn flow_id(&self) -> uuid::Uuid {
+        self.id
+    }
+
+    /// The config of the flow, captured when the writer was created.
+    pub fn config_info(&self) -> &FlowConfigInfo {
+        &self.config
+    }
+
     pub fn to_grain_writer(mut self) -> Result<GrainWriter> {
-        let flow_type = self.get_flow_type()?;
+        let flow_type = self.config.value.common.format;
         if !is_discrete_data_format(flow_type) {
-            return Err(Error::Other(format!(
-                "Cannot convert MxlFlowWriter to GrainWriter for continuous flow of type \"{:?}\".",
-                DataFormat::from(flow_type)
-            )));
+            return Err(Error::FlowFormatMismatch {
+                expected: "video or data",
+                actual: DataFormat::from(flow_type),
+            }
+            .with_context(ErrorContext::new("create grain writer").flow_id(self.id)));
         }
-        let result = GrainWriter::new(self.context.clone(), self.writer);
+        let config = self.config.clone();
+        let result = GrainWriter::new(self.context.clone(), self.writer, self.id, config);
         self.writer = std::ptr::null_mut();
         Ok(result)
     }
 
     pub fn to_samples_writer(mut self) -> Result<SamplesWriter> {
-        let flow_type = self.get_flow_type()?;
-        if is_discrete_data_format(flow_type) {
-            return Err(Error::Other(format!(
-                "Cannot convert MxlFlowWriter to SamplesWriter for discrete flow of type \"{:?}\".",
-                DataFormat::from(flow_type)
-            )));
+        let flow_type = self.config.value.common.format;
+        if DataFormat::from(flow_type) != DataFormat::Audio {
+            return Err(Error::FlowFormatMismatch {
+                expected: "audio",
+                actual: DataFormat::from(flow_type),
+            }
+            .with_context(ErrorContext::new("create samples writer").flow_id(self.id)));
         }
-        let result = SamplesWriter::new(self.context.clone(), self.writer);
+        let config = self.config.clone();
+        let result = SamplesWriter::new(self.context.clone(), self.writer, self.id, config);
         self.writer = std::ptr::null_mut();
         Ok(result)
     }
-
-    fn get_flow_type(&self) -> Result<u32> {
-        // This feels pretty ugly, but currently, the only way how to get a flow type in MXL is to
-        // use a reader.
-        let reader = create_flow_reader(&self.context, &self.id.to_string()).map_err(|error| {
-            Error::Other(format!(
-                "Error while creating flow reader to get the flow type: {error}"
-            ))
-        })?;
-        let flow_info = reader.get_info().map_err(|error| {
-            Error::Other(format!(
-                "Error while getting flow type from temporary reader: {error}"
-            ))
-        })?;
-        Ok(flow_info.config.value.common.format)
-    }
 }
 
 impl Drop for FlowWriter {
@@ -2,6 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod data;
+pub mod flags;
+pub mod iter;
 pub mod reader;
+pub mod sink;
+pub mod slices;
+pub mod stream;
 pub mod write_access;
 pub mod writer;
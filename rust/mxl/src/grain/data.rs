@@ -1,6 +1,18 @@
 // SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::sync::{
+    Arc,
+    atomic::{self, Ordering},
+};
+
+use super::flags::GrainFlags;
+use crate::{
+    Error, GrainIndex, Result,
+    error::{ErrorContext, ResultExt},
+    instance::InstanceContext,
+};
+
 pub struct GrainData<'a> {
     /// The grain payload. This may be a partial payload if the grain is not complete.
     /// The length of this slice is given by `commitedSize` in `mxlGrainInfo`.
@@ -8,12 +20,103 @@ pub struct GrainData<'a> {
 
     /// The total size of the grain payload, which may be larger than `payload.len()` if the grain is partial.
     pub total_size: usize,
+
+    /// Number of slices (lines for video, bytes for data) that were valid when the grain was read.
+    pub valid_slices: u16,
+
+    /// Number of slices that make up the complete grain.
+    pub total_slices: u16,
+
+    pub(crate) flags: GrainFlags,
+
+    pub(crate) index: GrainIndex,
+
+    /// Index of the grain in the header of the ring buffer slot when the grain was read.
+    pub(crate) header_index: u64,
+
+    pub(crate) slot: SlotProbe,
 }
 
 impl<'a> GrainData<'a> {
+    pub fn is_complete(&self) -> bool {
+        self.valid_slices == self.total_slices
+    }
+
+    /// Flags set by the writer when committing the grain.
+    pub fn flags(&self) -> GrainFlags {
+        self.flags
+    }
+
+    /// Whether the writer flagged the grain as invalid, in which case the payload must not be
+    /// used.
+    pub fn is_invalid(&self) -> bool {
+        self.flags.is_invalid()
+    }
+
+    pub fn index(&self) -> GrainIndex {
+        self.index
+    }
+
+    /// Whether the payload still holds the grain, i.e. the writer has not reused its ring buffer
+    /// slot for a later grain. This only reads the header of the slot.
+    ///
+    /// The payload is read straight from the ring buffer, which the writer overwrites once it has
+    /// gone around it, e.g. when the reader is slow and the history of the flow short. Checking
+    /// after processing the payload tells whether it may have been corrupted, see
+    /// `read_consistent`.
+    pub fn still_valid(&self) -> bool {
+        // The payload must have been read before the header is read again, as for a seqlock.
+        atomic::fence(Ordering::Acquire);
+        self.header_index == self.index.value()
+            && self.slot.holds_grain(self.index, self.is_complete())
+    }
+
+    /// Calls `read` with the payload, then checks that the writer has not overwritten the grain in
+    /// the meantime. Fails with `Error::Overwritten` at the root if it has, in which case whatever
+    /// `read` got must be discarded.
+    pub fn read_consistent<T>(&self, read: impl FnOnce(&[u8]) -> T) -> Result<T> {
+        let value = read(self.payload);
+        self.check_consistent()?;
+        Ok(value)
+    }
+
+    /// Copies the payload to the beginning of `buffer` and returns its length, checking that the
+    /// writer has not overwritten the grain during the copy as `read_consistent` does. Fails with
+    /// `Error::InvalidArg` if `buffer` is too small.
+    pub fn copy_into(&self, buffer: &mut [u8]) -> Result<usize> {
+        let target = buffer
+            .get_mut(..self.payload.len())
+            .ok_or(Error::InvalidArg)
+            .context(|| self.error_context())?;
+        self.read_consistent(|payload| target.copy_from_slice(payload))?;
+        Ok(self.payload.len())
+    }
+
+    /// Same as `to_owned`, checking that the writer has not overwritten the grain during the copy
+    /// as `read_consistent` does.
+    pub fn to_owned_consistent(&self) -> Result<OwnedGrainData> {
+        self.read_consistent(|payload| OwnedGrainData {
+            payload: payload.to_vec(),
+        })
+    }
+
     pub fn to_owned(&self) -> OwnedGrainData {
         self.into()
     }
+
+    fn check_consistent(&self) -> Result<()> {
+        if self.still_valid() {
+            Ok(())
+        } else {
+            Err(Error::Overwritten.with_context(self.error_context()))
+        }
+    }
+
+    fn error_context(&self) -> ErrorContext {
+        ErrorContext::new("read grain")
+            .flow_id(self.slot.flow_id)
+            .index(self.index)
+    }
 }
 
 impl<'a> AsRef<GrainData<'a>> for GrainData<'a> {
@@ -22,6 +125,67 @@ impl<'a> AsRef<GrainData<'a>> for GrainData<'a> {
     }
 }
 
+/// Reads the header of the ring buffer slot of a grain again for `GrainData::still_valid`, without
+/// borrowing the `GrainReader`, which is not `Sync`, so that `GrainData` stays `Send`.
+pub(crate) struct SlotProbe {
+    context: Arc<InstanceContext>,
+    reader: mxl_sys::mxlFlowReader,
+    flow_id: uuid::Uuid,
+}
+
+/// The probe only makes non-blocking grain reads, which read the shared memory of the flow without
+/// modifying the reader, so they may run on another thread while the reader is used. The grain
+/// data borrows the reader, which therefore outlives the probe.
+unsafe impl Send for SlotProbe {}
+
+impl SlotProbe {
+    pub(crate) fn new(
+        context: Arc<InstanceContext>,
+        reader: mxl_sys::mxlFlowReader,
+        flow_id: uuid::Uuid,
+    ) -> Self {
+        Self {
+            context,
+            reader,
+            flow_id,
+        }
+    }
+
+    /// Whether the ring buffer slot of the grain at `index` still holds it, from the index in the
+    /// header of the slot, which the writer updates as soon as it opens a later grain in the slot.
+    fn holds_grain(&self, index: GrainIndex, complete: bool) -> bool {
+        let mut grain_info: mxl_sys::mxlGrainInfo = unsafe { std::mem::zeroed() };
+        let mut payload_ptr: *mut u8 = std::ptr::null_mut();
+        // Asking for no valid slice returns the header whatever the state of the grain.
+        let status = unsafe {
+            self.context.api.flow_reader_get_grain_slice_non_blocking(
+                self.reader,
+                index.value(),
+                0,
+                &mut grain_info,
+                &mut payload_ptr,
+            )
+        };
+        match status {
+            Some(status) => status == mxl_sys::MXL_STATUS_OK && grain_info.index == index.value(),
+            // Older libraries only return complete grains, a partial grain can only be told apart
+            // from a grain the writer has just reopened if it was complete when read.
+            None => match Error::from_status(unsafe {
+                self.context.api.flow_reader_get_grain_non_blocking(
+                    self.reader,
+                    index.value(),
+                    &mut grain_info,
+                    &mut payload_ptr,
+                )
+            }) {
+                Ok(()) => grain_info.index == index.value(),
+                Err(Error::OutOfRangeTooEarly) => !complete,
+                Err(_) => false,
+            },
+        }
+    }
+}
+
 pub struct OwnedGrainData {
     pub payload: Vec<u8>,
 }
@@ -0,0 +1,59 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::{BitOr, BitOrAssign};
+
+/// Flags of a grain, mirroring the `MXL_GRAIN_FLAG_*` constants of `mxl/flow.h`.
+///
+/// Unknown bits set by other writers are kept as is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GrainFlags(u32);
+
+impl GrainFlags {
+    /// The grain does not hold usable data. Writers commit invalid grains to move the ring buffer
+    /// forward when they have nothing to write (e.g. the input timed out), consumers may repeat
+    /// the previous grain, insert black, etc.
+    pub const INVALID: GrainFlags = GrainFlags(mxl_sys::MXL_GRAIN_FLAG_INVALID);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(self, other: GrainFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: GrainFlags) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: GrainFlags) {
+        self.0 &= !other.0;
+    }
+
+    pub const fn is_invalid(self) -> bool {
+        self.contains(GrainFlags::INVALID)
+    }
+}
+
+impl BitOr for GrainFlags {
+    type Output = GrainFlags;
+
+    fn bitor(self, rhs: GrainFlags) -> GrainFlags {
+        GrainFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for GrainFlags {
+    fn bitor_assign(&mut self, rhs: GrainFlags) {
+        self.insert(rhs);
+    }
+}
@@ -0,0 +1,132 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use crate::{Error, GrainData, GrainIndex, GrainReader, Result, StreamStart};
+
+/// What a `GrainIter` does when the next grain has already been overwritten, i.e. when the reader
+/// has fallen behind the tail of the ring buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CatchUp {
+    /// Yields the `Error::OutOfRangeTooLate` error and ends the iteration.
+    #[default]
+    Fail,
+    /// Continues from the head of the flow.
+    SkipToHead,
+    /// Continues from the given number of grains before the head of the flow, or from the oldest
+    /// grain still in the ring buffer if it holds fewer grains.
+    SkipToHeadMinus(u64),
+}
+
+/// Item of a `GrainIter`.
+pub enum GrainEvent<'a> {
+    /// A complete grain, or a grain the writer flagged as invalid.
+    Grain {
+        index: GrainIndex,
+        grain: GrainData<'a>,
+    },
+    /// The grain at `missed` had been overwritten, the iteration continues at `resumed` following
+    /// the catch-up policy.
+    CaughtUp {
+        missed: GrainIndex,
+        resumed: GrainIndex,
+    },
+}
+
+impl GrainEvent<'_> {
+    /// Number of grains skipped by a catch-up, zero for grains.
+    pub fn dropped_grains(&self) -> u64 {
+        match self {
+            GrainEvent::Grain { .. } => 0,
+            GrainEvent::CaughtUp { missed, resumed } => resumed.saturating_distance_since(*missed),
+        }
+    }
+}
+
+/// Iterator over the complete grains of a flow, in index order, see `GrainReader::iter`.
+///
+/// Errors are yielded as items:
+/// - `Error::Timeout` if the next grain is still missing after the timeout, the iteration keeps
+///   waiting for it;
+/// - `Error::OutOfRangeTooLate` if the next grain has already been overwritten and the catch-up
+///   policy is `CatchUp::Fail`, which ends the iteration;
+/// - other errors end the iteration.
+pub struct GrainIter<'a> {
+    reader: &'a GrainReader,
+    /// `None` until the head of the flow has been read.
+    next: Option<GrainIndex>,
+    timeout: Duration,
+    catch_up: CatchUp,
+    done: bool,
+}
+
+impl<'a> GrainIter<'a> {
+    pub(crate) fn new(reader: &'a GrainReader, start: StreamStart, timeout: Duration) -> Self {
+        Self {
+            reader,
+            next: match start {
+                StreamStart::Head => None,
+                StreamStart::Index(index) => Some(index),
+            },
+            timeout,
+            catch_up: CatchUp::default(),
+            done: false,
+        }
+    }
+
+    /// Sets what to do when the reader falls behind the tail of the ring buffer, `CatchUp::Fail`
+    /// by default.
+    pub fn catch_up(mut self, catch_up: CatchUp) -> Self {
+        self.catch_up = catch_up;
+        self
+    }
+
+    /// Index of the next grain to read, `None` until it has been resolved from the head of the
+    /// flow.
+    pub fn next_index(&self) -> Option<GrainIndex> {
+        self.next
+    }
+
+    fn next_event(&mut self) -> Result<GrainEvent<'a>> {
+        let index = match self.next {
+            Some(index) => index,
+            None => *self.next.insert(*self.reader.readable_window()?.end()),
+        };
+        match self.reader.get_complete_grain(index, self.timeout) {
+            Ok(grain) => {
+                self.next = Some(index + 1);
+                Ok(GrainEvent::Grain { index, grain })
+            }
+            Err(error) if matches!(error.root(), Error::OutOfRangeTooLate) => {
+                let window = self.reader.readable_window()?;
+                let resumed = match self.catch_up {
+                    CatchUp::Fail => return Err(error),
+                    CatchUp::SkipToHead => *window.end(),
+                    CatchUp::SkipToHeadMinus(count) => (*window.end() - count).max(*window.start()),
+                };
+                self.next = Some(resumed);
+                Ok(GrainEvent::CaughtUp {
+                    missed: index,
+                    resumed,
+                })
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
+
+impl<'a> Iterator for GrainIter<'a> {
+    type Item = Result<GrainEvent<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let event = self.next_event();
+        if let Err(error) = &event {
+            self.done = !matches!(error.root(), Error::Timeout);
+        }
+        Some(event)
+    }
+}
@@ -1,20 +1,34 @@
 // SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{sync::Arc, time::Duration};
+use std::{
+    ops::RangeInclusive,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    Error, FlowConfigInfo, GrainData, Result,
+    Error, FlowConfigInfo, FlowRead, FlowRuntimeInfo, GrainData, GrainFlags, GrainIndex,
+    GrainSlices, Result,
+    error::{ErrorContext, ResultExt},
     flow::{
         FlowInfo,
         reader::{get_config_info, get_flow_info, get_runtime_info},
     },
-    instance::InstanceContext,
+    grain::{
+        data::SlotProbe,
+        iter::GrainIter,
+        stream::{GrainStream, OwnedGrainStream, StreamStart},
+    },
+    instance::{InstanceContext, create_flow_reader},
+    timing::GrainRing,
 };
 
 pub struct GrainReader {
     context: Arc<InstanceContext>,
     reader: mxl_sys::mxlFlowReader,
+    id: uuid::Uuid,
+    config: FlowConfigInfo,
 }
 
 /// The MXL readers and writers are not thread-safe, so we do not implement `Sync` for them, but
@@ -22,88 +36,290 @@ pub struct GrainReader {
 unsafe impl Send for GrainReader {}
 
 impl GrainReader {
-    pub(crate) fn new(context: Arc<InstanceContext>, reader: mxl_sys::mxlFlowReader) -> Self {
-        Self { context, reader }
+    pub(crate) fn new(
+        context: Arc<InstanceContext>,
+        reader: mxl_sys::mxlFlowReader,
+        id: uuid::Uuid,
+        config: FlowConfigInfo,
+    ) -> Self {
+        Self {
+            context,
+            reader,
+            id,
+            config,
+        }
+    }
+
+    pub fn flow_id(&self) -> uuid::Uuid {
+        self.id
+    }
+
+    /// The config of the flow, captured when the reader was created.
+    pub fn config_info(&self) -> &FlowConfigInfo {
+        &self.config
     }
 
     pub fn destroy(mut self) -> Result<()> {
         self.destroy_inner()
     }
 
-    /// The whole FlowInfo is quite a chunk of data. Go for `get_config_info` or `get_runtime_info`
+    /// The whole FlowInfo is quite a chunk of data. Go for `config_info` or `get_runtime_info`
     /// if they contain what you need.
     pub fn get_info(&self) -> Result<FlowInfo> {
         get_flow_info(&self.context, self.reader)
+            .context(|| ErrorContext::new("get flow info").flow_id(self.id))
     }
 
     pub fn get_config_info(&self) -> Result<FlowConfigInfo> {
         get_config_info(&self.context, self.reader)
+            .context(|| ErrorContext::new("get flow config").flow_id(self.id))
     }
 
-    pub fn get_runtime_info(&self) -> Result<mxl_sys::mxlFlowRuntimeInfo> {
-        get_runtime_info(&self.context, self.reader)
+    pub fn get_runtime_info(&self) -> Result<FlowRuntimeInfo> {
+        get_runtime_info(&self.context, self.reader, &self.config)
+            .context(|| ErrorContext::new("get flow runtime info").flow_id(self.id))
     }
 
+    /// Index of the last grain committed to the flow.
+    pub fn head_index(&self) -> Result<GrainIndex> {
+        self.get_runtime_info()?.grain_head_index()
+    }
+
+    /// Grains currently in the ring buffer, from the oldest one to the head of the flow.
+    pub fn readable_window(&self) -> Result<RangeInclusive<GrainIndex>> {
+        let ring = GrainRing::new(u64::from(self.config.discrete()?.grainCount))?;
+        let head = self.head_index()?;
+        Ok(ring.oldest(head)..=head)
+    }
+
+    /// Waits until the grain is complete and returns it. Fails with `Error::Timeout` at the root if
+    /// the grain is still missing or partial once `timeout` has elapsed.
+    ///
+    /// A grain flagged as invalid by the writer is returned as soon as it is available, even if
+    /// it is partial.
     pub fn get_complete_grain<'a>(
         &'a self,
-        index: u64,
+        index: GrainIndex,
         timeout: Duration,
     ) -> Result<GrainData<'a>> {
+        let deadline = Instant::now() + timeout;
+        let result = loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            // Recent versions of `mxlFlowReaderGetGrain` wait for all the slices of the grain, but
+            // older ones return as soon as some slices are committed, so partial grains are read
+            // again until they are complete.
+            match self.get_grain(index, remaining) {
+                Ok(grain) if grain.is_complete() || grain.is_invalid() => break Ok(grain),
+                Ok(_) | Err(Error::OutOfRangeTooEarly) if remaining.is_zero() => {
+                    break Err(Error::Timeout);
+                }
+                Ok(_) | Err(Error::OutOfRangeTooEarly) => {}
+                Err(error) => break Err(error),
+            }
+        };
+        result.context(|| self.error_context("get complete grain", index))
+    }
+
+    /// Reads the grain, the error is returned without context.
+    fn get_grain(&self, index: GrainIndex, timeout: Duration) -> Result<GrainData<'_>> {
         let mut grain_info: mxl_sys::mxlGrainInfo = unsafe { std::mem::zeroed() };
         let mut payload_ptr: *mut u8 = std::ptr::null_mut();
-        let timeout_ns = timeout.as_nanos() as u64;
-        loop {
-            unsafe {
-                Error::from_status(self.context.api.flow_reader_get_grain(
-                    self.reader,
-                    index,
-                    timeout_ns,
-                    &mut grain_info,
-                    &mut payload_ptr,
-                ))?;
-            }
-            if grain_info.validSlices != grain_info.totalSlices {
-                // We don't need partial grains. Wait for the grain to be complete.
-                continue;
-            }
-            if payload_ptr.is_null() {
-                return Err(Error::Other(format!(
-                    "Failed to get grain payload for index {index}.",
-                )));
-            }
-            break;
+        unsafe {
+            Error::from_status(self.context.api.flow_reader_get_grain(
+                self.reader,
+                index.value(),
+                timeout.as_nanos() as u64,
+                &mut grain_info,
+                &mut payload_ptr,
+            ))?;
+        }
+        self.to_valid_slices(index, &grain_info, payload_ptr)
+    }
+
+    /// Non-blocking version of `get_complete_grain`. If the grain is not available, returns an error.
+    /// If the grain is partial, it is returned as is and the payload length will be smaller than the total grain size.
+    pub fn get_grain_non_blocking<'a>(&'a self, index: GrainIndex) -> Result<GrainData<'a>> {
+        let (grain_info, payload) = self.get_raw_grain_non_blocking(index)?;
+
+        Ok(GrainData {
+            payload,
+            total_size: grain_info.grainSize as usize,
+            valid_slices: grain_info.validSlices,
+            total_slices: grain_info.totalSlices,
+            flags: GrainFlags::from_bits(grain_info.flags),
+            index,
+            header_index: grain_info.index,
+            slot: self.slot_probe(),
+        })
+    }
+
+    /// Waits until at least `min_valid_slices` slices of the grain are valid (or the timeout
+    /// expires) and returns them. Unlike `get_complete_grain`, the payload only holds the valid
+    /// slices, so the beginning of a grain can be processed while the writer is still filling the
+    /// rest.
+    ///
+    /// A `min_valid_slices` larger than the number of slices of the grain waits for the complete
+    /// grain. Grains flagged as invalid are returned as soon as they are available, whatever the
+    /// number of valid slices.
+    ///
+    /// For flows with several planes, the slices of a partial grain are those of the first plane.
+    /// The other planes are only included once the grain is complete.
+    pub fn get_grain_slice<'a>(
+        &'a self,
+        index: GrainIndex,
+        min_valid_slices: u16,
+        timeout: Duration,
+    ) -> Result<GrainData<'a>> {
+        let mut grain_info: mxl_sys::mxlGrainInfo = unsafe { std::mem::zeroed() };
+        let mut payload_ptr: *mut u8 = std::ptr::null_mut();
+        let status = unsafe {
+            self.context.api.flow_reader_get_grain_slice(
+                self.reader,
+                index.value(),
+                min_valid_slices,
+                timeout.as_nanos() as u64,
+                &mut grain_info,
+                &mut payload_ptr,
+            )
+        }
+        .ok_or_else(|| self.context.api.unsupported("mxlFlowReaderGetGrainSlice"))?;
+        Error::from_status(status)
+            .and_then(|()| self.to_valid_slices(index, &grain_info, payload_ptr))
+            .context(|| self.error_context("get grain slice", index))
+    }
+
+    /// Non-blocking version of `get_grain_slice`. Fails with `Error::OutOfRangeTooEarly` if fewer
+    /// than `min_valid_slices` slices are valid.
+    pub fn get_grain_slice_non_blocking<'a>(
+        &'a self,
+        index: GrainIndex,
+        min_valid_slices: u16,
+    ) -> Result<GrainData<'a>> {
+        let mut grain_info: mxl_sys::mxlGrainInfo = unsafe { std::mem::zeroed() };
+        let mut payload_ptr: *mut u8 = std::ptr::null_mut();
+        let status = unsafe {
+            self.context.api.flow_reader_get_grain_slice_non_blocking(
+                self.reader,
+                index.value(),
+                min_valid_slices,
+                &mut grain_info,
+                &mut payload_ptr,
+            )
         }
+        .ok_or_else(|| {
+            self.context
+                .api
+                .unsupported("mxlFlowReaderGetGrainSliceNonBlocking")
+        })?;
+        Error::from_status(status)
+            .and_then(|()| self.to_valid_slices(index, &grain_info, payload_ptr))
+            .context(|| self.error_context("get grain slice", index))
+    }
+
+    /// Iterates over the slices of a grain as the writer makes them valid. Every item holds the
+    /// slices that became valid since the previous one, the iteration ends once the grain is
+    /// complete. `timeout` applies to the wait for each item.
+    pub fn slices(&self, index: GrainIndex, timeout: Duration) -> GrainSlices<'_> {
+        GrainSlices::new(self, index, timeout)
+    }
+
+    /// Iterates over the complete grains of the flow, from `start` on. `timeout` applies to the
+    /// wait for each grain, see `GrainIter` for the catch-up policies.
+    pub fn iter(&self, start: StreamStart, timeout: Duration) -> GrainIter<'_> {
+        GrainIter::new(self, start, timeout)
+    }
+
+    /// Asynchronous stream of the complete grains of the flow, from `start` on. `timeout` is how
+    /// long the stream waits for a grain before reporting it as too early, see `GrainStream`.
+    pub fn stream(&self, start: StreamStart, timeout: Duration) -> Result<GrainStream<'_>> {
+        GrainStream::new(self, start, timeout)
+    }
+
+    /// Same as `stream`, but the stream owns the reader and yields copies of the grains, so that
+    /// it can be moved to another task.
+    pub fn into_stream(self, start: StreamStart, timeout: Duration) -> Result<OwnedGrainStream> {
+        OwnedGrainStream::new(self, start, timeout)
+    }
+
+    /// Opens another reader on the same flow.
+    pub(crate) fn reopen(&self) -> Result<GrainReader> {
+        create_flow_reader(&self.context, &self.id.to_string())?.to_grain_reader()
+    }
+
+    /// Waits until the grain is complete, as `get_complete_grain` but for at most a single call to
+    /// the MXL library. The error is returned without context.
+    pub(crate) fn wait_for_grain(&self, index: GrainIndex, timeout: Duration) -> Result<()> {
+        self.get_grain(index, timeout).map(|_| ())
+    }
+
+    /// Size in bytes of a slice of the first plane.
+    pub(crate) fn slice_size(&self) -> Result<usize> {
+        Ok(self.config.discrete()?.sliceSizes[0] as usize)
+    }
+
+    fn slot_probe(&self) -> SlotProbe {
+        SlotProbe::new(self.context.clone(), self.reader, self.id)
+    }
+
+    fn error_context(&self, operation: &'static str, index: GrainIndex) -> ErrorContext {
+        ErrorContext::new(operation).flow_id(self.id).index(index)
+    }
+
+    fn to_valid_slices<'a>(
+        &'a self,
+        index: GrainIndex,
+        grain_info: &mxl_sys::mxlGrainInfo,
+        payload_ptr: *mut u8,
+    ) -> Result<GrainData<'a>> {
+        if payload_ptr.is_null() {
+            return Err(Error::NullPointer("grain payload"));
+        }
+
+        let grain_size = grain_info.grainSize as usize;
+        let valid_size = if grain_info.validSlices >= grain_info.totalSlices {
+            grain_size
+        } else {
+            (grain_info.validSlices as usize * self.slice_size()?).min(grain_size)
+        };
 
         // SAFETY
         // We know that the lifetime is as long as the flow, so it is at least self's lifetime.
         // It may happen that the buffer is overwritten by a subsequent write, but it is safe.
-        let payload =
-            unsafe { std::slice::from_raw_parts(payload_ptr, grain_info.grainSize as usize) };
+        // `GrainData::still_valid` tells whether it has been.
+        let payload = unsafe { std::slice::from_raw_parts(payload_ptr, valid_size) };
 
         Ok(GrainData {
             payload,
-            total_size: grain_info.grainSize as usize,
+            total_size: grain_size,
+            valid_slices: grain_info.validSlices,
+            total_slices: grain_info.totalSlices,
+            flags: GrainFlags::from_bits(grain_info.flags),
+            index,
+            header_index: grain_info.index,
+            slot: self.slot_probe(),
         })
     }
 
-    /// Non-blocking version of `get_complete_grain`. If the grain is not available, returns an error.
-    /// If the grain is partial, it is returned as is and the payload length will be smaller than the total grain size.
-    pub fn get_grain_non_blocking<'a>(&'a self, index: u64) -> Result<GrainData<'a>> {
+    /// Same as `get_grain_non_blocking`, but returns the raw grain header together with the payload.
+    pub(crate) fn get_raw_grain_non_blocking(
+        &self,
+        index: GrainIndex,
+    ) -> Result<(mxl_sys::mxlGrainInfo, &[u8])> {
         let mut grain_info: mxl_sys::mxlGrainInfo = unsafe { std::mem::zeroed() };
         let mut payload_ptr: *mut u8 = std::ptr::null_mut();
         unsafe {
             Error::from_status(self.context.api.flow_reader_get_grain_non_blocking(
                 self.reader,
-                index,
+                index.value(),
                 &mut grain_info,
                 &mut payload_ptr,
-            ))?;
+            ))
+            .context(|| self.error_context("get grain", index))?;
         }
 
         if payload_ptr.is_null() {
-            return Err(Error::Other(format!(
-                "Failed to get grain payload for index {index}.",
-            )));
+            return Err(Error::NullPointer("grain payload")
+                .with_context(self.error_context("get grain", index)));
         }
 
         // SAFETY
@@ -112,10 +328,11 @@ impl GrainReader {
         let payload =
             unsafe { std::slice::from_raw_parts(payload_ptr, grain_info.grainSize as usize) };
 
-        Ok(GrainData {
-            payload,
-            total_size: grain_info.grainSize as usize,
-        })
+        Ok((grain_info, payload))
+    }
+
+    pub(crate) fn handle(&self) -> mxl_sys::mxlFlowReader {
+        self.reader
     }
 
     fn destroy_inner(&mut self) -> Result<()> {
@@ -134,6 +351,28 @@ impl GrainReader {
     }
 }
 
+impl FlowRead for GrainReader {
+    fn flow_id(&self) -> uuid::Uuid {
+        self.flow_id()
+    }
+
+    fn config_info(&self) -> &FlowConfigInfo {
+        self.config_info()
+    }
+
+    fn get_info(&self) -> Result<FlowInfo> {
+        self.get_info()
+    }
+
+    fn get_config_info(&self) -> Result<FlowConfigInfo> {
+        self.get_config_info()
+    }
+
+    fn get_runtime_info(&self) -> Result<FlowRuntimeInfo> {
+        self.get_runtime_info()
+    }
+}
+
 impl Drop for GrainReader {
     fn drop(&mut self) {
         if !self.reader.is_null()
@@ -0,0 +1,148 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Sink;
+
+use crate::{
+    Error, GrainFlags, GrainIndex, GrainWriter, Result, error::ErrorContext, pacing::Pacer,
+};
+
+/// A grain to write with a `GrainSink`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrainPayload<B = Vec<u8>> {
+    pub index: GrainIndex,
+    /// Written at the beginning of the grain, the rest of the grain is left as it is.
+    pub payload: B,
+    pub flags: GrainFlags,
+}
+
+impl<B> GrainPayload<B> {
+    pub fn new(index: GrainIndex, payload: B) -> Self {
+        Self {
+            index,
+            payload,
+            flags: GrainFlags::empty(),
+        }
+    }
+
+    pub fn with_flags(mut self, flags: GrainFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+}
+
+/// Asynchronous sink of the grains of a flow, see `GrainWriter::into_sink`.
+///
+/// Every grain is opened, copied, and committed as a whole, or canceled if it cannot be written.
+/// The sink holds a single grain: `poll_ready` is pending until the previous grain has been
+/// written. When paced, a grain is not written before its timestamp, and waiting for it does not
+/// block the executor.
+pub struct GrainSink<B = Vec<u8>> {
+    writer: GrainWriter,
+    pacer: Option<Pacer<GrainIndex>>,
+    pending: Option<GrainPayload<B>>,
+}
+
+impl<B: AsRef<[u8]>> GrainSink<B> {
+    pub(crate) fn new(writer: GrainWriter) -> Self {
+        Self {
+            writer,
+            pacer: None,
+            pending: None,
+        }
+    }
+
+    /// Paces the writes to the grain rate of the flow.
+    pub fn paced(mut self) -> Result<Self> {
+        let rate = self.writer.config_info().common().grain_rate()?;
+        self.pacer = Some(Pacer::new(self.writer.context().clone(), rate)?);
+        Ok(self)
+    }
+
+    pub fn is_paced(&self) -> bool {
+        self.pacer.is_some()
+    }
+
+    /// Stops the sink and gives the writer back. A grain that has not been flushed is dropped.
+    pub fn into_writer(self) -> GrainWriter {
+        self.writer
+    }
+
+    /// Writes the pending grain, once due when paced.
+    fn poll_write_pending(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let Some(index) = self.pending.as_ref().map(|grain| grain.index) else {
+            return Poll::Ready(Ok(()));
+        };
+        if let Some(pacer) = &self.pacer {
+            match pacer.poll_due(index, cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(error)) => {
+                    self.pending = None;
+                    return Poll::Ready(Err(error.with_context(self.error_context(index))));
+                }
+                Poll::Ready(Ok(())) => {}
+            }
+        }
+        Poll::Ready(
+            self.pending
+                .take()
+                .map_or(Ok(()), |grain| self.write(&grain)),
+        )
+    }
+
+    fn write(&mut self, grain: &GrainPayload<B>) -> Result<()> {
+        let payload = grain.payload.as_ref();
+        let error_context = self.error_context(grain.index);
+        let mut access = self.writer.open_grain(grain.index)?;
+        let Some(buffer) = access.payload_mut().get_mut(..payload.len()) else {
+            // Dropping the access cancels the grain.
+            return Err(Error::Other(format!(
+                "Payload of {} bytes does not fit in a grain of {} bytes.",
+                payload.len(),
+                access.max_size()
+            ))
+            .with_context(error_context));
+        };
+        buffer.copy_from_slice(payload);
+        access.set_flags(grain.flags);
+        let total_slices = access.total_slices();
+        access.commit(total_slices)
+    }
+
+    fn error_context(&self, index: GrainIndex) -> ErrorContext {
+        ErrorContext::new("send grain")
+            .flow_id(self.writer.flow_id())
+            .index(index)
+    }
+}
+
+impl<B: AsRef<[u8]> + Unpin> Sink<GrainPayload<B>> for GrainSink<B> {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_write_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, grain: GrainPayload<B>) -> Result<()> {
+        let this = self.get_mut();
+        if this.pending.is_some() {
+            // `poll_ready` has not been polled to completion.
+            return Err(Error::InvalidState.with_context(this.error_context(grain.index)));
+        }
+        this.pending = Some(grain);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_write_pending(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_write_pending(cx)
+    }
+}
@@ -0,0 +1,90 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{ops::Range, time::Duration};
+
+use crate::{GrainIndex, GrainReader, Result};
+
+/// Slices of a grain that became valid together.
+pub struct GrainSlice<'a> {
+    /// Indices of the slices, relative to the beginning of the grain.
+    pub slices: Range<u16>,
+
+    /// Payload of the slices. Once the grain is complete, the last item also holds the planes
+    /// following the first one, if any.
+    pub payload: &'a [u8],
+}
+
+/// Iterator over the slices of a grain as they become valid, see `GrainReader::slices`.
+///
+/// An error ends the iteration. The iteration also ends without reaching the total number of
+/// slices if the writer flags the grain as invalid.
+pub struct GrainSlices<'a> {
+    reader: &'a GrainReader,
+    index: GrainIndex,
+    timeout: Duration,
+    next_slice: u16,
+    done: bool,
+}
+
+impl<'a> GrainSlices<'a> {
+    pub(crate) fn new(reader: &'a GrainReader, index: GrainIndex, timeout: Duration) -> Self {
+        Self {
+            reader,
+            index,
+            timeout,
+            next_slice: 0,
+            done: false,
+        }
+    }
+
+    fn next_slices(&mut self) -> Result<Option<GrainSlice<'a>>> {
+        let grain = self.reader.get_grain_slice(
+            self.index,
+            self.next_slice.saturating_add(1),
+            self.timeout,
+        )?;
+        let start = self.next_slice;
+        let end = grain.valid_slices.min(grain.total_slices);
+        if end <= start {
+            // Only happens with grains flagged as invalid, nothing more is coming.
+            return Ok(None);
+        }
+
+        let slice_size = self.reader.slice_size()?;
+        let start_offset = (start as usize * slice_size).min(grain.payload.len());
+        let end_offset = if end == grain.total_slices {
+            grain.payload.len()
+        } else {
+            (end as usize * slice_size).min(grain.payload.len())
+        };
+
+        self.next_slice = end;
+        self.done = end == grain.total_slices;
+        Ok(Some(GrainSlice {
+            slices: start..end,
+            payload: &grain.payload[start_offset..end_offset],
+        }))
+    }
+}
+
+impl<'a> Iterator for GrainSlices<'a> {
+    type Item = Result<GrainSlice<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_slices() {
+            Ok(Some(slice)) => Some(Ok(slice)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(error) => {
+                self.done = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
@@ -0,0 +1,288 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    pin::Pin,
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    task::{Context, Poll, Waker},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use futures::Stream;
+
+use crate::{
+    Error, GrainData, GrainIndex, GrainReader, OwnedGrainData, Result, error::ErrorContext,
+};
+
+/// Longest blocking wait of the waiter thread, which bounds how long dropping a stream takes.
+const WAIT_SLICE: Duration = Duration::from_millis(10);
+
+/// Where a grain stream starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamStart {
+    /// The last grain committed by the writer.
+    Head,
+    /// A given grain, which may be in the past or in the future.
+    Index(GrainIndex),
+}
+
+/// Asynchronous stream of the complete grains of a flow, in index order, see
+/// `GrainReader::stream`.
+///
+/// Waiting for grains does not block the executor: a helper thread owning its own reader on the
+/// flow waits in its place and wakes the task once the grain is available. The thread stops when
+/// the stream is dropped.
+///
+/// Grains the writer flagged as invalid are yielded as soon as they are available, even if they
+/// are partial. Errors are yielded as items:
+/// - `Error::OutOfRangeTooEarly` if the next grain is still missing after the timeout, the stream
+///   keeps waiting for it;
+/// - `Error::OutOfRangeTooLate` if the next grain has already been overwritten, the stream
+///   continues from the head of the flow;
+/// - other errors end the stream.
+pub struct GrainStream<'a> {
+    reader: &'a GrainReader,
+    state: StreamState,
+}
+
+impl<'a> GrainStream<'a> {
+    pub(crate) fn new(
+        reader: &'a GrainReader,
+        start: StreamStart,
+        timeout: Duration,
+    ) -> Result<Self> {
+        Ok(Self {
+            reader,
+            state: StreamState::new(reader, start, timeout)?,
+        })
+    }
+
+    /// Index of the next grain the stream yields, `None` until it has been resolved from the head
+    /// of the flow.
+    pub fn next_index(&self) -> Option<GrainIndex> {
+        self.state.next
+    }
+}
+
+impl<'a> Stream for GrainStream<'a> {
+    type Item = Result<(GrainIndex, GrainData<'a>)>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.state.poll_grain(this.reader, cx)
+    }
+}
+
+/// Same as `GrainStream`, but owns the reader and yields copies of the grains, see
+/// `GrainReader::into_stream`.
+pub struct OwnedGrainStream {
+    reader: GrainReader,
+    state: StreamState,
+}
+
+impl OwnedGrainStream {
+    pub(crate) fn new(reader: GrainReader, start: StreamStart, timeout: Duration) -> Result<Self> {
+        let state = StreamState::new(&reader, start, timeout)?;
+        Ok(Self { reader, state })
+    }
+
+    pub fn next_index(&self) -> Option<GrainIndex> {
+        self.state.next
+    }
+
+    /// Stops the stream and gives the reader back.
+    pub fn into_reader(self) -> GrainReader {
+        self.reader
+    }
+}
+
+impl Stream for OwnedGrainStream {
+    type Item = Result<(GrainIndex, OwnedGrainData)>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.state
+            .poll_grain(&this.reader, cx)
+            .map(|item| item.map(|result| result.map(|(index, grain)| (index, grain.into()))))
+    }
+}
+
+struct StreamState {
+    /// `None` until the head of the flow has been read.
+    next: Option<GrainIndex>,
+    timeout: Duration,
+    /// Since when the stream has been waiting for the next grain.
+    waiting_since: Option<Instant>,
+    done: bool,
+    waiter: Waiter,
+}
+
+impl StreamState {
+    fn new(reader: &GrainReader, start: StreamStart, timeout: Duration) -> Result<Self> {
+        Ok(Self {
+            next: match start {
+                StreamStart::Head => None,
+                StreamStart::Index(index) => Some(index),
+            },
+            timeout,
+            waiting_since: None,
+            done: false,
+            waiter: Waiter::spawn(reader.reopen()?)?,
+        })
+    }
+
+    fn poll_grain<'r>(
+        &mut self,
+        reader: &'r GrainReader,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<(GrainIndex, GrainData<'r>)>>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        let index = match self.next {
+            Some(index) => index,
+            None => match reader.head_index() {
+                Ok(index) => *self.next.insert(index),
+                Err(error) => return self.fail(error),
+            },
+        };
+
+        match reader.get_grain_non_blocking(index) {
+            Ok(grain) if grain.is_complete() || grain.is_invalid() => {
+                self.next = Some(index + 1);
+                self.waiting_since = None;
+                Poll::Ready(Some(Ok((index, grain))))
+            }
+            Ok(_) => self.wait(reader, index, cx),
+            Err(error) if matches!(error.root(), Error::OutOfRangeTooEarly) => {
+                self.wait(reader, index, cx)
+            }
+            Err(error) if matches!(error.root(), Error::OutOfRangeTooLate) => {
+                // Resolved again from the head on the next poll.
+                self.next = None;
+                self.waiting_since = None;
+                Poll::Ready(Some(Err(error)))
+            }
+            Err(error) => self.fail(error),
+        }
+    }
+
+    fn wait<T>(
+        &mut self,
+        reader: &GrainReader,
+        index: GrainIndex,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<T>>> {
+        let now = Instant::now();
+        let waiting_since = *self.waiting_since.get_or_insert(now);
+        let deadline = waiting_since + self.timeout;
+        if now >= deadline {
+            self.waiting_since = None;
+            return Poll::Ready(Some(Err(Error::OutOfRangeTooEarly.with_context(
+                ErrorContext::new("stream grains")
+                    .flow_id(reader.flow_id())
+                    .index(index),
+            ))));
+        }
+        match self.waiter.request(index, deadline, cx.waker().clone()) {
+            Ok(()) => Poll::Pending,
+            Err(error) => self.fail(error),
+        }
+    }
+
+    fn fail<T>(&mut self, error: Error) -> Poll<Option<Result<T>>> {
+        self.done = true;
+        Poll::Ready(Some(Err(error)))
+    }
+}
+
+struct WaitRequest {
+    index: GrainIndex,
+    deadline: Instant,
+    waker: Waker,
+}
+
+/// Thread waiting for grains on behalf of a stream.
+struct Waiter {
+    requests: Option<Sender<WaitRequest>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Waiter {
+    fn spawn(reader: GrainReader) -> Result<Self> {
+        let (requests, receiver) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("mxl-grain-stream".to_string())
+            .spawn(move || wait_for_grains(reader, receiver))?;
+        Ok(Self {
+            requests: Some(requests),
+            thread: Some(thread),
+        })
+    }
+
+    fn request(&self, index: GrainIndex, deadline: Instant, waker: Waker) -> Result<()> {
+        self.requests
+            .as_ref()
+            .and_then(|requests| {
+                requests
+                    .send(WaitRequest {
+                        index,
+                        deadline,
+                        waker,
+                    })
+                    .ok()
+            })
+            .ok_or_else(|| Error::Other("The grain stream waiter thread has stopped.".to_string()))
+    }
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        // Disconnects the channel, which stops the thread within a wait slice.
+        self.requests = None;
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            tracing::error!("The grain stream waiter thread panicked.");
+        }
+    }
+}
+
+/// Body of the waiter thread. Only the latest request matters, the older ones are from polls that
+/// have been superseded.
+fn wait_for_grains(reader: GrainReader, receiver: Receiver<WaitRequest>) {
+    let mut next_request = None;
+    loop {
+        let request = match next_request.take() {
+            Some(request) => request,
+            None => match receiver.recv() {
+                Ok(request) => request,
+                Err(_) => return,
+            },
+        };
+        loop {
+            match receiver.try_recv() {
+                Ok(newer) => {
+                    next_request = Some(newer);
+                    break;
+                }
+                Err(TryRecvError::Disconnected) => return,
+                Err(TryRecvError::Empty) => {}
+            }
+            let remaining = request.deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                request.waker.wake();
+                break;
+            }
+            match reader.wait_for_grain(request.index, remaining.min(WAIT_SLICE)) {
+                Err(error) if error.is_retryable() => {}
+                // The grain is there, or the stream has an error to report.
+                _ => {
+                    request.waker.wake();
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -5,7 +5,28 @@ use std::{marker::PhantomData, sync::Arc};
 
 use tracing::error;
 
-use crate::{Error, Result, instance::InstanceContext};
+use crate::{
+    Error, GrainFlags, Result,
+    error::{ErrorContext, ResultExt},
+    instance::InstanceContext,
+};
+
+/// Batch size hints of a discrete flow, in slices. See `maxCommitBatchSizeHint` and
+/// `maxSyncBatchSizeHint` in `mxlCommonFlowConfigInfo`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SliceBatchSizes {
+    pub(crate) commit: u32,
+    pub(crate) sync: u32,
+}
+
+impl SliceBatchSizes {
+    pub(crate) fn new(commit: u32, sync: u32) -> Self {
+        Self {
+            commit: commit.max(1),
+            sync: sync.max(1),
+        }
+    }
+}
 
 /// RAII grain writing session
 ///
@@ -13,25 +34,38 @@ use crate::{Error, Result, instance::InstanceContext};
 pub struct GrainWriteAccess<'a> {
     context: Arc<InstanceContext>,
     writer: mxl_sys::mxlFlowWriter,
+    flow_id: uuid::Uuid,
     grain_info: mxl_sys::mxlGrainInfo,
     payload_ptr: *mut u8,
+    batch_sizes: SliceBatchSizes,
+    /// Slices committed so far with `commit_slices`.
+    committed_slices: u16,
+    /// Slices readers have been notified about.
+    published_slices: u16,
     /// Serves as a flag to know whether to cancel the grain on drop.
     committed_or_canceled: bool,
-    phantom: PhantomData<&'a ()>,
+    /// Exclusive borrow of the writer, which only supports a single open access.
+    phantom: PhantomData<&'a mut ()>,
 }
 
 impl<'a> GrainWriteAccess<'a> {
     pub(crate) fn new(
         context: Arc<InstanceContext>,
         writer: mxl_sys::mxlFlowWriter,
+        flow_id: uuid::Uuid,
         grain_info: mxl_sys::mxlGrainInfo,
         payload_ptr: *mut u8,
+        batch_sizes: SliceBatchSizes,
     ) -> Self {
         Self {
             context,
             writer,
+            flow_id,
             grain_info,
             payload_ptr,
+            batch_sizes,
+            committed_slices: 0,
+            published_slices: 0,
             committed_or_canceled: false,
             phantom: Default::default(),
         }
@@ -51,14 +85,37 @@ impl<'a> GrainWriteAccess<'a> {
         self.grain_info.totalSlices
     }
 
+    pub fn flags(&self) -> GrainFlags {
+        GrainFlags::from_bits(self.grain_info.flags)
+    }
+
+    /// Sets the flags committed with the grain.
+    pub fn set_flags(&mut self, flags: GrainFlags) {
+        self.grain_info.flags = flags.bits();
+    }
+
+    /// Direct access to the grain header that will be committed.
+    pub(crate) fn grain_info_mut(&mut self) -> &mut mxl_sys::mxlGrainInfo {
+        &mut self.grain_info
+    }
+
     pub fn commit(mut self, valid_slices: u16) -> Result<()> {
         self.committed_or_canceled = true;
 
         if valid_slices > self.grain_info.totalSlices {
-            return Err(Error::Other(format!(
-                "Valid slices {} cannot exceed total slices {}.",
-                valid_slices, self.grain_info.totalSlices
-            )));
+            return Err(Error::SlicesOutOfRange {
+                slices: valid_slices,
+                total: self.grain_info.totalSlices,
+            }
+            .with_context(self.error_context("commit grain")));
+        }
+        // Readers may already have seen the slices committed with `commit_slices`.
+        if valid_slices < self.committed_slices {
+            return Err(Error::SlicesBelowCommitted {
+                slices: valid_slices,
+                committed: self.committed_slices,
+            }
+            .with_context(self.error_context("commit grain")));
         }
         self.grain_info.validSlices = valid_slices;
 
@@ -69,6 +126,91 @@ impl<'a> GrainWriteAccess<'a> {
                     .flow_writer_commit_grain(self.writer, &self.grain_info),
             )
         }
+        .context(|| self.error_context("commit grain"))
+    }
+
+    /// Number of slices committed so far with `commit_slices`.
+    pub fn committed_slices(&self) -> u16 {
+        self.committed_slices
+    }
+
+    /// Marks the slices `0..upto` as valid while keeping the grain open, so that the rest of the
+    /// grain can be written afterwards.
+    ///
+    /// Readers are not necessarily notified on every call. Following the batch size hints of the
+    /// flow, the slices are published as soon as waiting for another commit batch would exceed
+    /// the sync batch size. The default hints of discrete flows are both the number of slices of
+    /// the grain, and with equal hints every call publishes. Committing all the slices completes
+    /// the grain, like `finish`.
+    pub fn commit_slices(&mut self, upto: u16) -> Result<()> {
+        if self.committed_or_canceled {
+            return Err(Error::InvalidState.with_context(self.error_context("commit slices")));
+        }
+        if upto > self.grain_info.totalSlices {
+            return Err(Error::SlicesOutOfRange {
+                slices: upto,
+                total: self.grain_info.totalSlices,
+            }
+            .with_context(self.error_context("commit slices")));
+        }
+        if upto < self.committed_slices {
+            return Err(Error::SlicesBelowCommitted {
+                slices: upto,
+                committed: self.committed_slices,
+            }
+            .with_context(self.error_context("commit slices")));
+        }
+        self.committed_slices = upto;
+
+        if upto == self.grain_info.totalSlices {
+            self.committed_or_canceled = true;
+            return self.publish(upto);
+        }
+        let pending = u32::from(upto - self.published_slices);
+        if pending > 0 && pending + self.batch_sizes.commit > self.batch_sizes.sync {
+            self.publish(upto)?;
+        }
+        Ok(())
+    }
+
+    /// Completes a grain written with `commit_slices`.
+    pub fn finish(mut self) -> Result<()> {
+        if self.committed_or_canceled {
+            // All the slices have already been committed.
+            return Ok(());
+        }
+        let total_slices = self.grain_info.totalSlices;
+        self.commit_slices(total_slices)
+    }
+
+    fn publish(&mut self, valid_slices: u16) -> Result<()> {
+        self.grain_info.validSlices = valid_slices;
+        unsafe {
+            Error::from_status(
+                self.context
+                    .api
+                    .flow_writer_commit_grain(self.writer, &self.grain_info),
+            )
+            .context(|| self.error_context("commit slices"))?;
+        }
+        self.published_slices = valid_slices;
+        Ok(())
+    }
+
+    fn error_context(&self, operation: &'static str) -> ErrorContext {
+        ErrorContext::new(operation)
+            .flow_id(self.flow_id)
+            .index(self.grain_info.index)
+    }
+
+    /// Commits the grain as complete but flagged as invalid. This moves the ring buffer forward
+    /// while letting the readers know that the payload must not be used.
+    pub fn commit_invalid(mut self) -> Result<()> {
+        let mut flags = self.flags();
+        flags.insert(GrainFlags::INVALID);
+        self.set_flags(flags);
+        let total_slices = self.grain_info.totalSlices;
+        self.commit(total_slices)
     }
 
     /// Please note that the behavior of canceling a grain writing is dependent on the behavior
@@ -79,6 +221,7 @@ impl<'a> GrainWriteAccess<'a> {
         self.committed_or_canceled = true;
 
         unsafe { Error::from_status(self.context.api.flow_writer_cancel_grain(self.writer)) }
+            .context(|| self.error_context("cancel grain"))
     }
 }
 
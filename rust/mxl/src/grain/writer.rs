@@ -3,14 +3,21 @@
 
 use std::sync::Arc;
 
-use super::write_access::GrainWriteAccess;
+use super::write_access::{GrainWriteAccess, SliceBatchSizes};
 
-use crate::{Error, Result, instance::InstanceContext};
+use crate::{
+    Error, FlowConfigInfo, GrainFlags, GrainIndex, GrainSink, PacedWriter, Result,
+    error::{ErrorContext, ResultExt},
+    instance::InstanceContext,
+};
 
 /// MXL Flow Writer for discrete flows (grain-based data like video frames)
 pub struct GrainWriter {
     context: Arc<InstanceContext>,
     writer: mxl_sys::mxlFlowWriter,
+    id: uuid::Uuid,
+    config: FlowConfigInfo,
+    batch_sizes: SliceBatchSizes,
 }
 
 /// The MXL readers and writers are not thread-safe, so we do not implement `Sync` for them, but
@@ -18,44 +25,152 @@ pub struct GrainWriter {
 unsafe impl Send for GrainWriter {}
 
 impl GrainWriter {
-    pub(crate) fn new(context: Arc<InstanceContext>, writer: mxl_sys::mxlFlowWriter) -> Self {
-        Self { context, writer }
+    pub(crate) fn new(
+        context: Arc<InstanceContext>,
+        writer: mxl_sys::mxlFlowWriter,
+        id: uuid::Uuid,
+        config: FlowConfigInfo,
+    ) -> Self {
+        let batch_sizes = SliceBatchSizes::new(
+            config.common().max_commit_batch_size_hint(),
+            config.common().max_sync_batch_size_hint(),
+        );
+        Self {
+            context,
+            writer,
+            id,
+            config,
+            batch_sizes,
+        }
+    }
+
+    pub fn flow_id(&self) -> uuid::Uuid {
+        self.id
+    }
+
+    /// The config of the flow, captured when the writer was created.
+    pub fn config_info(&self) -> &FlowConfigInfo {
+        &self.config
     }
 
     pub fn destroy(mut self) -> Result<()> {
         self.destroy_inner()
     }
 
-    /// The current MXL implementation states a TODO to allow multiple grains to be edited at the
-    /// same time. For this reason, there is no protection on the Rust level against trying to open
-    /// multiple grains. If the TODO ever gets removed, it may be worth considering pattern where
-    /// opening grain would consume the writer and then return it back on commit or cancel.
-    pub fn open_grain<'a>(&'a self, index: u64) -> Result<GrainWriteAccess<'a>> {
+    /// Opens the grain at `index` for writing. The MXL writers only track a single open grain, so
+    /// the access borrows the writer exclusively until it is committed, canceled or dropped, and
+    /// opening another grain in the meantime does not compile:
+    ///
+    /// ```compile_fail
+    /// # fn open_two(writer: &mut mxl::GrainWriter) -> mxl::Result<()> {
+    /// let first = writer.open_grain(mxl::GrainIndex::new(0))?;
+    /// let second = writer.open_grain(mxl::GrainIndex::new(1))?;
+    /// first.cancel()?;
+    /// second.cancel()
+    /// # }
+    /// ```
+    ///
+    /// The grain flags start cleared, the ring buffer entry may still hold the flags of an older
+    /// grain.
+    pub fn open_grain(&mut self, index: GrainIndex) -> Result<GrainWriteAccess<'_>> {
+        let mut access = self.open(index)?;
+        access.set_flags(GrainFlags::empty());
+        Ok(access)
+    }
+
+    /// Same as `open_grain` through a shared reference, for callers that cannot borrow the writer
+    /// exclusively yet.
+    ///
+    /// # Safety
+    ///
+    /// No other grain or access of the writer may be open until the returned access is committed,
+    /// canceled or dropped.
+    #[deprecated(note = "use `open_grain`, which borrows the writer exclusively")]
+    pub unsafe fn open_grain_unchecked(&self, index: GrainIndex) -> Result<GrainWriteAccess<'_>> {
+        let mut access = self.open(index)?;
+        access.set_flags(GrainFlags::empty());
+        Ok(access)
+    }
+
+    /// Same as `open_grain`, but keeps the header exactly as stored in the ring buffer. Used when
+    /// the header has been written by someone else, e.g. a remote fabrics initiator.
+    pub(crate) fn open_grain_as_stored(
+        &mut self,
+        index: GrainIndex,
+    ) -> Result<GrainWriteAccess<'_>> {
+        self.open(index)
+    }
+
+    fn open(&self, index: GrainIndex) -> Result<GrainWriteAccess<'_>> {
         let mut grain_info: mxl_sys::mxlGrainInfo = unsafe { std::mem::zeroed() };
         let mut payload_ptr: *mut u8 = std::ptr::null_mut();
         unsafe {
             Error::from_status(self.context.api.flow_writer_open_grain(
                 self.writer,
-                index,
+                index.value(),
                 &mut grain_info,
                 &mut payload_ptr,
-            ))?;
+            ))
+            .context(|| {
+                ErrorContext::new("open grain")
+                    .flow_id(self.id)
+                    .index(index)
+            })?;
         }
 
         if payload_ptr.is_null() {
-            return Err(Error::Other(format!(
-                "Failed to open grain payload for index {index}.",
-            )));
+            return Err(Error::NullPointer("grain payload").with_context(
+                ErrorContext::new("open grain")
+                    .flow_id(self.id)
+                    .index(index),
+            ));
         }
 
         Ok(GrainWriteAccess::new(
             self.context.clone(),
             self.writer,
+            self.id,
             grain_info,
             payload_ptr,
+            self.batch_sizes,
         ))
     }
 
+    /// Header of the ring buffer entry of `index`, read without opening the grain.
+    pub(crate) fn grain_info(&self, index: GrainIndex) -> Result<mxl_sys::mxlGrainInfo> {
+        let mut grain_info: mxl_sys::mxlGrainInfo = unsafe { std::mem::zeroed() };
+        let status = unsafe {
+            self.context
+                .api
+                .flow_writer_get_grain_info(self.writer, index.value(), &mut grain_info)
+        }
+        .ok_or_else(|| self.context.api.unsupported("mxlFlowWriterGetGrainInfo"))?;
+        Error::from_status(status).context(|| {
+            ErrorContext::new("get grain info")
+                .flow_id(self.id)
+                .index(index)
+        })?;
+        Ok(grain_info)
+    }
+
+    pub(crate) fn handle(&self) -> mxl_sys::mxlFlowWriter {
+        self.writer
+    }
+
+    /// Turns the writer into an asynchronous sink, see `GrainSink`.
+    pub fn into_sink<B: AsRef<[u8]>>(self) -> GrainSink<B> {
+        GrainSink::new(self)
+    }
+
+    /// Turns the writer into a writer paced to the grain rate of the flow, see `PacedWriter`.
+    pub fn into_paced(self) -> Result<PacedWriter<GrainWriter>> {
+        PacedWriter::for_grains(self)
+    }
+
+    pub(crate) fn context(&self) -> &Arc<InstanceContext> {
+        &self.context
+    }
+
     fn destroy_inner(&mut self) -> Result<()> {
         if self.writer.is_null() {
             return Err(Error::InvalidArg);
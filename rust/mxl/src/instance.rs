@@ -1,9 +1,17 @@
 // SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{ffi::CString, sync::Arc};
+use std::{ffi::CString, path::Path, sync::Arc, time::Duration};
 
-use crate::{Error, FlowConfigInfo, FlowReader, FlowWriter, Result, api::MxlApiHandle};
+use crate::{
+    EditRate, Error, FlowConfigInfo, FlowDef, FlowOptions, FlowRead, FlowReader, FlowStatus,
+    FlowWriter, GrainReader, GrainWriter, InstanceOptions, MediaIndex, OpenedFlow, Result,
+    SamplesReader, SamplesWriter, Timestamp,
+    api::MxlApiHandle,
+    error::{ErrorContext, ResultExt},
+    options::DOMAIN_OPTIONS_FILE_NAME,
+    timing,
+};
 
 /// This struct stores the context that is shared by all objects.
 /// It is separated out from `MxlInstance` so that it can be cloned
@@ -40,10 +48,24 @@ impl Drop for InstanceContext {
     }
 }
 
+fn parse_flow_id(flow_id: &str) -> Result<uuid::Uuid> {
+    uuid::Uuid::parse_str(flow_id).map_err(|_| Error::InvalidFlowId(flow_id.to_string()))
+}
+
+/// Context of an operation on the flow `flow_id`, which may not be a valid ID.
+fn flow_context(operation: &'static str, flow_id: &str) -> ErrorContext {
+    let context = ErrorContext::new(operation);
+    match uuid::Uuid::parse_str(flow_id) {
+        Ok(flow_id) => context.flow_id(flow_id),
+        Err(_) => context,
+    }
+}
+
 pub(crate) fn create_flow_reader(
     context: &Arc<InstanceContext>,
     flow_id: &str,
 ) -> Result<FlowReader> {
+    let uuid = parse_flow_id(flow_id)?;
     let flow_id = CString::new(flow_id)?;
     let options = CString::new("")?;
     let mut reader: mxl_sys::mxlFlowReader = std::ptr::null_mut();
@@ -53,12 +75,13 @@ pub(crate) fn create_flow_reader(
             flow_id.as_ptr(),
             options.as_ptr(),
             &mut reader,
-        ))?;
+        ))
+        .context(|| ErrorContext::new("create flow reader").flow_id(uuid))?;
     }
     if reader.is_null() {
-        return Err(Error::Other("Failed to create flow reader.".to_string()));
+        return Err(Error::NullPointer("flow reader"));
     }
-    Ok(FlowReader::new(context.clone(), reader))
+    FlowReader::new(context.clone(), reader, uuid)
 }
 
 #[derive(Clone)]
@@ -75,20 +98,71 @@ impl MxlInstance {
             )
         };
         if instance.is_null() {
-            Err(Error::Other("Failed to create MXL instance.".to_string()))
+            Err(Error::NullPointer("instance"))
         } else {
             let context = Arc::new(InstanceContext { api, instance });
             Ok(Self { context })
         }
     }
 
+    /// Merges `options` into the options file of `domain`, creating it if needed. The other
+    /// options in the file are kept.
+    ///
+    /// The file is shared by all the processes and flows of the domain, and the MXL library only
+    /// reads it when an instance is created. The options therefore apply to the instances created
+    /// afterwards in any process, not to the existing ones. The file is replaced atomically, but
+    /// concurrent updates from several processes may still lose one of them.
+    pub fn update_domain_options(
+        domain: impl AsRef<Path>,
+        options: &InstanceOptions,
+    ) -> Result<()> {
+        let context = || ErrorContext::new("update domain options");
+        options.validate().context(context)?;
+        let path = domain.as_ref().join(DOMAIN_OPTIONS_FILE_NAME);
+        let existing = match std::fs::read_to_string(&path) {
+            Ok(existing) => existing,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(Error::from(error).with_context(context())),
+        };
+        let merged = options.merge_into_json(&existing).context(context)?;
+        let temporary = path.with_extension(format!("json.{}", uuid::Uuid::new_v4()));
+        std::fs::write(&temporary, merged)
+            .and_then(|()| std::fs::rename(&temporary, &path))
+            .map_err(|error| {
+                let _ = std::fs::remove_file(&temporary);
+                Error::from(error)
+            })
+            .context(context)
+    }
+
+    pub(crate) fn context(&self) -> &Arc<InstanceContext> {
+        &self.context
+    }
+
     pub fn create_flow_reader(&self, flow_id: &str) -> Result<FlowReader> {
         create_flow_reader(&self.context, flow_id)
     }
 
     pub fn create_flow_writer(&self, flow_id: &str) -> Result<FlowWriter> {
-        let uuid = uuid::Uuid::parse_str(flow_id)
-            .map_err(|_| Error::Other("Invalid flow ID format.".to_string()))?;
+        let (uuid, writer) = self.open_flow_writer(flow_id)?;
+        FlowWriter::new(self.context.clone(), writer, uuid)
+    }
+
+    /// Same as `create_flow_writer`, for a flow whose config is already known, as returned by
+    /// `create_flow` or `create_flow_from_def`. `create_flow_writer` has to open a reader to read
+    /// the config, this does not.
+    pub fn create_flow_writer_with_config(&self, config: &FlowConfigInfo) -> Result<FlowWriter> {
+        let (uuid, writer) = self.open_flow_writer(&config.common().id().to_string())?;
+        Ok(FlowWriter::with_config(
+            self.context.clone(),
+            writer,
+            uuid,
+            config.clone(),
+        ))
+    }
+
+    fn open_flow_writer(&self, flow_id: &str) -> Result<(uuid::Uuid, mxl_sys::mxlFlowWriter)> {
+        let uuid = parse_flow_id(flow_id)?;
         let flow_id = CString::new(flow_id)?;
         let options = CString::new("")?;
         let mut writer: mxl_sys::mxlFlowWriter = std::ptr::null_mut();
@@ -98,12 +172,39 @@ impl MxlInstance {
                 flow_id.as_ptr(),
                 options.as_ptr(),
                 &mut writer,
-            ))?;
+            ))
+            .context(|| ErrorContext::new("create flow writer").flow_id(uuid))?;
         }
         if writer.is_null() {
-            return Err(Error::Other("Failed to create flow writer.".to_string()));
+            return Err(Error::NullPointer("flow writer"));
         }
-        Ok(FlowWriter::new(self.context.clone(), writer, uuid))
+        Ok((uuid, writer))
+    }
+
+    /// Opens a reader of the flow, a grain reader for video and data flows and a samples reader
+    /// for audio flows. Fails with `Error::FlowFormatMismatch` at the root for other flows.
+    pub fn open_flow(&self, flow_id: &str) -> Result<OpenedFlow> {
+        self.create_flow_reader(flow_id)?.to_typed_reader()
+    }
+
+    /// Fails with `Error::FlowFormatMismatch` at the root unless the flow is a video or data flow.
+    pub fn create_grain_reader(&self, flow_id: &str) -> Result<GrainReader> {
+        self.create_flow_reader(flow_id)?.to_grain_reader()
+    }
+
+    /// Fails with `Error::FlowFormatMismatch` at the root unless the flow is an audio flow.
+    pub fn create_samples_reader(&self, flow_id: &str) -> Result<SamplesReader> {
+        self.create_flow_reader(flow_id)?.to_samples_reader()
+    }
+
+    /// Fails with `Error::FlowFormatMismatch` at the root unless the flow is a video or data flow.
+    pub fn create_grain_writer(&self, flow_id: &str) -> Result<GrainWriter> {
+        self.create_flow_writer(flow_id)?.to_grain_writer()
+    }
+
+    /// Fails with `Error::FlowFormatMismatch` at the root unless the flow is an audio flow.
+    pub fn create_samples_writer(&self, flow_id: &str) -> Result<SamplesWriter> {
+        self.create_flow_writer(flow_id)?.to_samples_writer()
     }
 
     /// For now, we provide direct access to the MXL API for creating and
@@ -120,27 +221,124 @@ impl MxlInstance {
                 flow_def.as_ptr(),
                 options.as_ptr(),
                 info.as_mut_ptr(),
-            ))?;
+            ))
+            .context(|| ErrorContext::new("create flow"))?;
         }
 
         let info = unsafe { info.assume_init() };
         Ok(FlowConfigInfo { value: info })
     }
 
+    /// Validates `flow_def` and `options` and creates the flow, see `create_flow`.
+    ///
+    /// The buffer length of continuous flows is only known once they are created, since it depends
+    /// on the history duration of the domain. If the commit batch size turns out to be too large
+    /// for it, the flow is destroyed again and the validation error is returned, even if
+    /// destroying the flow fails.
+    pub fn create_flow_from_def(
+        &self,
+        flow_def: &FlowDef,
+        options: &FlowOptions,
+    ) -> Result<FlowConfigInfo> {
+        let context = || ErrorContext::new("create flow").flow_id(flow_def.id);
+        flow_def.validate().context(context)?;
+        options.validate().context(context)?;
+        let config_info = self.create_flow(
+            flow_def.to_json().as_str(),
+            Some(options.to_json().as_str()),
+        )?;
+
+        if let Ok(continuous) = config_info.continuous()
+            && let Err(error) =
+                options.validate_for_buffer_length(u64::from(continuous.bufferLength))
+        {
+            // The validation error matters more to the caller than a failed cleanup.
+            if let Err(destroy_error) = self.destroy_flow(flow_def.id.to_string().as_str()) {
+                tracing::error!(
+                    "Failed to destroy flow {} after rejecting its options: {:?}",
+                    flow_def.id,
+                    destroy_error
+                );
+            }
+            return Err(error.with_context(context()));
+        }
+        Ok(config_info)
+    }
+
     /// See `create_flow` for more info.
     pub fn destroy_flow(&self, flow_id: &str) -> Result<()> {
+        let context = || flow_context("destroy flow", flow_id);
         let flow_id = CString::new(flow_id)?;
         unsafe {
             Error::from_status(
                 self.context
                     .api
                     .destroy_flow(self.context.instance, flow_id.as_ptr()),
-            )?;
+            )
+            .context(context)?;
         }
         Ok(())
     }
 
+    /// Whether the flow currently has a writer.
+    pub fn is_flow_active(&self, flow_id: &str) -> Result<bool> {
+        let context = || flow_context("check flow activity", flow_id);
+        let flow_id = CString::new(flow_id)?;
+        let mut is_active = false;
+        let status = unsafe {
+            self.context
+                .api
+                .is_flow_active(self.context.instance, flow_id.as_ptr(), &mut is_active)
+        }
+        .ok_or_else(|| self.context.api.unsupported("mxlIsFlowActive"))?;
+        Error::from_status(status).context(context)?;
+        Ok(is_active)
+    }
+
+    /// Combines `is_flow_active` with the last write time of the flow. A flow with a writer that
+    /// has not written for longer than `stall_threshold` is reported as stalled. A threshold of a
+    /// few grain or batch durations is usually a good pick.
+    ///
+    /// A reader of the flow is only opened if the flow has a writer. Supervisors polling a flow
+    /// they already read should go for `get_flow_status_with_reader` instead.
+    pub fn get_flow_status(&self, flow_id: &str, stall_threshold: Duration) -> Result<FlowStatus> {
+        if !self.is_flow_active(flow_id)? {
+            return Ok(FlowStatus::WriterGone);
+        }
+        let reader = self.create_flow_reader(flow_id)?;
+        self.active_flow_status(&reader, stall_threshold)
+    }
+
+    /// Same as `get_flow_status`, reading the last write time through an existing reader of the
+    /// flow.
+    pub fn get_flow_status_with_reader(
+        &self,
+        reader: &impl FlowRead,
+        stall_threshold: Duration,
+    ) -> Result<FlowStatus> {
+        if !self.is_flow_active(reader.flow_id().to_string().as_str())? {
+            return Ok(FlowStatus::WriterGone);
+        }
+        self.active_flow_status(reader, stall_threshold)
+    }
+
+    /// Status of a flow known to have a writer.
+    fn active_flow_status(
+        &self,
+        reader: &impl FlowRead,
+        stall_threshold: Duration,
+    ) -> Result<FlowStatus> {
+        let last_write_time = reader.get_runtime_info()?.last_write_time();
+        Ok(FlowStatus::new(
+            true,
+            last_write_time,
+            self.get_time(),
+            stall_threshold,
+        ))
+    }
+
     pub fn get_flow_def(&self, flow_id: &str) -> Result<String> {
+        let context = || flow_context("get flow definition", flow_id);
         let flow_id = CString::new(flow_id)?;
         const INITIAL_BUFFER_SIZE: usize = 4096;
         let mut buffer: Vec<u8> = vec![0; INITIAL_BUFFER_SIZE];
@@ -163,10 +361,11 @@ impl MxlInstance {
                     flow_id.as_ptr(),
                     buffer.as_mut_ptr() as *mut std::os::raw::c_char,
                     &mut buffer_size,
-                ))?;
+                ))
+                .context(context)?;
             }
         } else {
-            Error::from_status(status)?;
+            Error::from_status(status).context(context)?;
         }
 
         if buffer_size > 0 && buffer[buffer_size - 1] == 0 {
@@ -175,60 +374,59 @@ impl MxlInstance {
         buffer.truncate(buffer_size);
 
         String::from_utf8(buffer)
-            .map_err(|_| Error::Other("Invalid UTF-8 in flow definition".to_string()))
+            .map_err(Error::from)
+            .context(context)
     }
 
-    pub fn get_current_index(&self, rational: &mxl_sys::mxlRational) -> u64 {
-        unsafe { self.context.api.get_current_index(rational) }
+    /// Index of the grain or sample at the current TAI time, `None` if the clock is unavailable.
+    /// The kind of index follows the rate, see `CommonFlowConfigInfo::grain_rate` and
+    /// `CommonFlowConfigInfo::sample_rate`.
+    pub fn get_current_index<I: MediaIndex>(&self, rate: &EditRate<I>) -> Option<I> {
+        let now = self.get_time();
+        if now == Timestamp::EPOCH {
+            return None;
+        }
+        timing::timestamp_to_index(now, rate).ok()
     }
 
-    pub fn get_duration_until_index(
+    /// Time left until the beginning of the grain or sample at `index`, zero if it is already
+    /// due.
+    pub fn get_duration_until_index<I: MediaIndex>(
         &self,
-        index: u64,
-        rate: &mxl_sys::mxlRational,
-    ) -> Result<std::time::Duration> {
-        let duration_ns = unsafe { self.context.api.get_ns_until_index(index, rate) };
-        if duration_ns == u64::MAX {
-            Err(Error::Other(format!(
-                "Failed to get duration until index, invalid rate {}/{}.",
-                rate.numerator, rate.denominator
-            )))
-        } else {
-            Ok(std::time::Duration::from_nanos(duration_ns))
-        }
+        index: I,
+        rate: &EditRate<I>,
+    ) -> Result<Duration> {
+        timing::duration_until_index(index, rate, self.get_time())
+            .context(|| ErrorContext::new("get duration until index").index(index))
     }
 
-    /// TODO: Make timestamp a strong type.
-    pub fn timestamp_to_index(&self, timestamp: u64, rate: &mxl_sys::mxlRational) -> Result<u64> {
-        let index = unsafe { self.context.api.timestamp_to_index(rate, timestamp) };
-        if index == u64::MAX {
-            Err(Error::Other(format!(
-                "Failed to convert timestamp to index, invalid rate {}/{}.",
-                rate.numerator, rate.denominator
-            )))
-        } else {
-            Ok(index)
-        }
+    /// Index of the grain or sample at `timestamp`, rounded to the nearest one.
+    pub fn timestamp_to_index<I: MediaIndex>(
+        &self,
+        timestamp: Timestamp,
+        rate: &EditRate<I>,
+    ) -> Result<I> {
+        timing::timestamp_to_index(timestamp, rate)
+            .context(|| ErrorContext::new("convert timestamp to index"))
     }
 
-    pub fn index_to_timestamp(&self, index: u64, rate: &mxl_sys::mxlRational) -> Result<u64> {
-        let timestamp = unsafe { self.context.api.index_to_timestamp(rate, index) };
-        if timestamp == u64::MAX {
-            Err(Error::Other(format!(
-                "Failed to convert index to timestamp, invalid rate {}/{}.",
-                rate.numerator, rate.denominator
-            )))
-        } else {
-            Ok(timestamp)
-        }
+    /// Timestamp of the beginning of the grain or sample at `index`.
+    pub fn index_to_timestamp<I: MediaIndex>(
+        &self,
+        index: I,
+        rate: &EditRate<I>,
+    ) -> Result<Timestamp> {
+        timing::index_to_timestamp(index, rate)
+            .context(|| ErrorContext::new("convert index to timestamp").index(index))
     }
 
-    pub fn sleep_for(&self, duration: std::time::Duration) {
+    pub fn sleep_for(&self, duration: Duration) {
         unsafe { self.context.api.sleep_for_ns(duration.as_nanos() as u64) }
     }
 
-    pub fn get_time(&self) -> u64 {
-        unsafe { self.context.api.get_time() }
+    /// Current TAI time.
+    pub fn get_time(&self) -> Timestamp {
+        Timestamp::from_nanos(unsafe { self.context.api.get_time() })
     }
 
     /// This function forces the destruction of the MXL instance.
@@ -236,8 +434,7 @@ impl MxlInstance {
     /// The caller must ensure that no other objects are using the MXL instance when this function
     /// is called.
     pub fn destroy(self) -> Result<()> {
-        let context = Arc::into_inner(self.context)
-            .ok_or_else(|| Error::Other("Instance is still in use.".to_string()))?;
+        let context = Arc::into_inner(self.context).ok_or(Error::InstanceInUse)?;
         context.destroy()
     }
 }
@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Minimal JSON serialization of the documents passed to the MXL library. Values are serialized
+//! to strings bottom up and assembled into arrays and objects.
+
+use std::fmt::Write;
+
+use crate::Rational;
+
+pub(crate) fn json_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(result, "\\u{:04x}", u32::from(c));
+            }
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// `values` must already be serialized.
+pub(crate) fn json_array(values: impl Iterator<Item = String>) -> String {
+    format!("[{}]", values.collect::<Vec<_>>().join(","))
+}
+
+/// The values of `fields` must already be serialized.
+pub(crate) fn json_object(fields: Vec<(&str, String)>) -> String {
+    let fields: Vec<String> = fields
+        .into_iter()
+        .map(|(name, value)| format!("{}:{}", json_string(name), value))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+pub(crate) fn json_rational(rate: &Rational) -> String {
+    json_object(vec![
+        ("numerator", rate.numerator().to_string()),
+        ("denominator", rate.denominator().to_string()),
+    ])
+}
+
+/// Splits a JSON object into its members, with the names decoded and the values left serialized.
+/// Returns `None` if `text` is not a JSON object. The values are only checked for balanced
+/// brackets and strings.
+pub(crate) fn json_object_members(text: &str) -> Option<Vec<(String, String)>> {
+    let inner = text.trim().strip_prefix('{')?.strip_suffix('}')?;
+    let mut members = Vec::new();
+    let mut rest = inner.trim_start();
+    if rest.is_empty() {
+        return Some(members);
+    }
+    loop {
+        let (name, after_name) = parse_string(rest)?;
+        let after_colon = after_name.trim_start().strip_prefix(':')?;
+        let length = value_length(after_colon)?;
+        let value = after_colon[..length].trim();
+        if value.is_empty() {
+            return None;
+        }
+        members.push((name, value.to_string()));
+        match after_colon[length..].strip_prefix(',') {
+            Some(next) => rest = next.trim_start(),
+            None if after_colon[length..].trim().is_empty() => return Some(members),
+            None => return None,
+        }
+    }
+}
+
+/// Decodes the string at the beginning of `text`, returns it with the text following it.
+fn parse_string(text: &str) -> Option<(String, &str)> {
+    let mut chars = text.strip_prefix('"')?.char_indices();
+    let mut result = String::new();
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '"' => return Some((result, &text[offset + 2..])),
+            '\\' => {
+                let escaped = match chars.next()?.1 {
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'u' => {
+                        let digits: String =
+                            (0..4).filter_map(|_| chars.next()).map(|c| c.1).collect();
+                        char::from_u32(u32::from_str_radix(&digits, 16).ok()?)?
+                    }
+                    c => c,
+                };
+                result.push(escaped);
+            }
+            c => result.push(c),
+        }
+    }
+    None
+}
+
+/// Length of the serialized value at the beginning of `text`, up to the comma ending it or the
+/// end of `text`.
+fn value_length(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (offset, byte) in text.bytes().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth = depth.checked_sub(1)?,
+            b',' if depth == 0 => return Some(offset),
+            _ => {}
+        }
+    }
+    (depth == 0 && !in_string).then_some(text.len())
+}
@@ -1,22 +1,61 @@
 // SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
 // SPDX-License-Identifier: Apache-2.0
 
+mod aligned;
 mod api;
 mod error;
 mod flow;
 mod grain;
 mod instance;
+mod json;
+mod options;
+mod paced;
+mod pacing;
+mod rational;
 mod samples;
+mod time;
 
 pub mod config;
+pub mod fabrics;
+pub mod timing;
 
-pub use api::{MxlApi, load_api};
-pub use error::{Error, Result};
-pub use flow::{reader::FlowReader, writer::FlowWriter, *};
+pub use aligned::{AlignedBundle, AlignedData, AlignedMember, AlignedReader};
+pub use api::{MxlApi, MxlFabricsApi, MxlLibrary, MxlVersion, load_api, load_fabrics_api};
+pub use error::{Error, ErrorContext, Result};
+pub use flow::{
+    def::{
+        AudioFlowDef, DataFlowDef, FlowDef, FlowMedia, GROUP_HINT_TAG, InterlaceMode,
+        VideoComponent, VideoFlowDef, VideoMediaType,
+    },
+    reader::{FlowReader, OpenedFlow},
+    writer::FlowWriter,
+    *,
+};
 pub use grain::{
-    data::*, reader::GrainReader, write_access::GrainWriteAccess, writer::GrainWriter,
+    data::*,
+    flags::GrainFlags,
+    iter::{CatchUp, GrainEvent, GrainIter},
+    reader::GrainReader,
+    sink::{GrainPayload, GrainSink},
+    slices::{GrainSlice, GrainSlices},
+    stream::{GrainStream, OwnedGrainStream, StreamStart},
+    write_access::GrainWriteAccess,
+    writer::GrainWriter,
 };
 pub use instance::MxlInstance;
+pub use options::{
+    DEFAULT_HISTORY_DURATION, FlowOptions, HISTORY_DURATION_OPTION, InstanceOptions,
+};
+pub use paced::{PacedWriter, PacingStats};
+pub use rational::Rational;
 pub use samples::{
-    data::*, reader::SamplesReader, write_access::SamplesWriteAccess, writer::SamplesWriter,
+    channel::{ChannelSamples, ChannelSamplesMut, deinterleave, interleave},
+    cursor::{CursorEvent, SamplesCursor},
+    data::*,
+    pcm::{Dither, PcmConverter, PcmFormat},
+    reader::SamplesReader,
+    sink::{SamplesBlock, SamplesSink},
+    write_access::SamplesWriteAccess,
+    writer::SamplesWriter,
 };
+pub use time::{EditRate, GrainIndex, GrainRate, MediaIndex, SampleIndex, SampleRate, Timestamp};
@@ -0,0 +1,176 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed options of instances and flows, serialized to the JSON understood by the MXL library.
+
+use std::time::Duration;
+
+use crate::{
+    Error, Result,
+    json::{json_object, json_object_members},
+};
+
+/// Option holding the depth of the flow ring buffers, in nanoseconds.
+pub const HISTORY_DURATION_OPTION: &str = "urn:x-mxl:option:history_duration/v1.0";
+
+/// History duration used by the MXL library when none is configured.
+pub const DEFAULT_HISTORY_DURATION: Duration = Duration::from_millis(100);
+
+/// Name of the domain options file, at the root of the domain.
+pub(crate) const DOMAIN_OPTIONS_FILE_NAME: &str = "options.json";
+
+/// Options of an MXL domain.
+///
+/// The MXL library reads the history duration from the `options.json` file at the root of the
+/// domain when an instance is created, and not from the options of the instance itself. The
+/// duration therefore applies to all the flows created afterwards in the domain, see
+/// `MxlInstance::update_domain_options`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstanceOptions {
+    pub history_duration: Option<Duration>,
+}
+
+impl InstanceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Depth of the ring buffers of the flows. Grain counts and sample buffer lengths are derived
+    /// from it and the rate of the flow.
+    pub fn history_duration(mut self, history_duration: Duration) -> Self {
+        self.history_duration = Some(history_duration);
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(history_duration) = self.history_duration {
+            if history_duration.is_zero() {
+                return Err(invalid("the history duration must be positive"));
+            }
+            if history_duration.as_nanos() > u128::from(u64::MAX) {
+                return Err(invalid(
+                    "the history duration must fit in 64 bit nanoseconds",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        json_object(self.fields())
+    }
+
+    /// Merges the options into `json`, the content of a domain options file, keeping the other
+    /// options it holds. Fails with `Error::InvalidOptions` if `json` is not a JSON object.
+    pub(crate) fn merge_into_json(&self, json: &str) -> Result<String> {
+        let mut members = if json.trim().is_empty() {
+            Vec::new()
+        } else {
+            json_object_members(json)
+                .ok_or_else(|| invalid("the domain options file does not hold a JSON object"))?
+        };
+        for (name, value) in self.fields() {
+            match members.iter_mut().find(|(existing, _)| existing == name) {
+                Some((_, existing)) => *existing = value,
+                None => members.push((name.to_string(), value)),
+            }
+        }
+        Ok(json_object(
+            members
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.clone()))
+                .collect(),
+        ))
+    }
+
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = Vec::new();
+        if let Some(history_duration) = self.history_duration {
+            fields.push((
+                HISTORY_DURATION_OPTION,
+                history_duration.as_nanos().to_string(),
+            ));
+        }
+        fields
+    }
+}
+
+/// Options of a flow, passed to `mxlCreateFlow`.
+///
+/// The batch size hints are in slices for discrete flows and in samples for continuous flows.
+/// Unset hints default to a whole grain for discrete flows and 10 ms of samples for continuous
+/// flows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlowOptions {
+    pub max_commit_batch_size_hint: Option<u32>,
+    pub max_sync_batch_size_hint: Option<u32>,
+}
+
+impl FlowOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Largest batch in which the writer commits new data.
+    pub fn commit_batch_size(mut self, commit_batch_size: u32) -> Self {
+        self.max_commit_batch_size_hint = Some(commit_batch_size);
+        self
+    }
+
+    /// Largest batch after which readers are notified of new data. Must be a multiple of the
+    /// commit batch size.
+    pub fn sync_batch_size(mut self, sync_batch_size: u32) -> Self {
+        self.max_sync_batch_size_hint = Some(sync_batch_size);
+        self
+    }
+
+    /// Checks the options against the rules of the MXL flow options parser.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_commit_batch_size_hint == Some(0) {
+            return Err(invalid("the commit batch size must be at least 1"));
+        }
+        if let Some(sync) = self.max_sync_batch_size_hint {
+            if sync == 0 {
+                return Err(invalid("the sync batch size must be at least 1"));
+            }
+            let commit = self.max_commit_batch_size_hint.unwrap_or(1);
+            if sync % commit != 0 {
+                return Err(Error::InvalidOptions(format!(
+                    "the sync batch size {sync} must be a multiple of the commit batch size \
+                     {commit}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Same as `validate`, with the additional rule of continuous flows: the commit batch size
+    /// must be less than half of the buffer length, in samples.
+    pub fn validate_for_buffer_length(&self, buffer_length: u64) -> Result<()> {
+        self.validate()?;
+        if let Some(commit) = self.max_commit_batch_size_hint
+            && u64::from(commit) * 2 >= buffer_length
+        {
+            return Err(Error::InvalidOptions(format!(
+                "the commit batch size {commit} must be less than half of the buffer length \
+                 {buffer_length}"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut fields = Vec::new();
+        if let Some(commit) = self.max_commit_batch_size_hint {
+            fields.push(("maxCommitBatchSizeHint", commit.to_string()));
+        }
+        if let Some(sync) = self.max_sync_batch_size_hint {
+            fields.push(("maxSyncBatchSizeHint", sync.to_string()));
+        }
+        json_object(fields)
+    }
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidOptions(reason.to_string())
+}
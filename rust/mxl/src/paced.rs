@@ -0,0 +1,292 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    ops::{ControlFlow, Range},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    EditRate, Error, GrainIndex, GrainWriteAccess, GrainWriter, MediaIndex, Result, SampleIndex,
+    SamplesWriteAccess, SamplesWriter, Timestamp, instance::InstanceContext, timing,
+};
+
+/// How well a `PacedWriter` keeps up with the clock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PacingStats {
+    /// Grains or sample batches written by the producer.
+    pub written: u64,
+    /// Grains or sample batches the writer was too late for, written as invalid grains or
+    /// silence.
+    pub filled: u64,
+    /// Grains or sample batches the writer was too late for and that were not filled, since the
+    /// ring buffer could not hold them along with the later ones.
+    pub dropped: u64,
+    /// Number of times the writer fell more than a grain or sample batch behind the clock.
+    pub overruns: u64,
+    /// How late the producer was called for the last grain or sample batch.
+    pub last_lateness: Duration,
+    pub max_lateness: Duration,
+    pub total_lateness: Duration,
+}
+
+impl PacingStats {
+    /// Mean lateness of the calls to the producer.
+    pub fn mean_lateness(&self) -> Duration {
+        match u32::try_from(self.written) {
+            Ok(0) => Duration::ZERO,
+            Ok(written) => self.total_lateness / written,
+            Err(_) => Duration::from_nanos(
+                u64::try_from(self.total_lateness.as_nanos() / u128::from(self.written))
+                    .unwrap_or(u64::MAX),
+            ),
+        }
+    }
+
+    fn record(&mut self, lateness: Duration) {
+        self.written += 1;
+        self.last_lateness = lateness;
+        self.max_lateness = self.max_lateness.max(lateness);
+        self.total_lateness = self.total_lateness.saturating_add(lateness);
+    }
+}
+
+/// Writer calling a producer at every grain or sample batch boundary of the flow clock, see
+/// `GrainWriter::into_paced` and `SamplesWriter::into_paced`.
+///
+/// The grain or sample batch at `index` is written once the clock reaches `index + offset`. A
+/// positive offset writes into the past, a negative one into the future, as `--video-offset` and
+/// `--audio-offset` of `mxl-gst-videotestsrc`.
+///
+/// When the writer falls behind the clock, e.g. because the producer took too long, the grains it
+/// missed are committed as invalid and the sample batches it missed are filled with silence, so
+/// that readers do not wait for them. Only the last ones the ring buffer can hold are filled, up to
+/// `grainCount` grains or half of the channel buffers, the others are counted as dropped. The
+/// producer is called again for the grain or batch that is due.
+pub struct PacedWriter<W> {
+    writer: W,
+    context: Arc<InstanceContext>,
+    /// Samples per batch, 1 for grains.
+    batch_size: u64,
+    offset: i64,
+    /// `None` until resolved from the clock on the first write.
+    next: Option<u64>,
+    stats: PacingStats,
+}
+
+impl<W> PacedWriter<W> {
+    fn new(writer: W, context: Arc<InstanceContext>, batch_size: u64) -> Self {
+        Self {
+            writer,
+            context,
+            batch_size,
+            offset: 0,
+            next: None,
+            stats: PacingStats::default(),
+        }
+    }
+
+    /// Sets the write offset, in grains or samples.
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn stats(&self) -> &PacingStats {
+        &self.stats
+    }
+
+    /// Gives the writer back.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    /// When the grain or sample batch at `index` is due: the timestamp of `index + offset` at
+    /// `rate`.
+    fn due<I: MediaIndex>(&self, index: u64, rate: &EditRate<I>) -> Result<Timestamp> {
+        let clock_index = u64::try_from(i128::from(index) + i128::from(self.offset))
+            .map_err(|_| Error::TimestampOutOfRange)?;
+        timing::index_to_timestamp(I::from(clock_index), rate)
+    }
+
+    /// The last of the `missed` grains or sample batches that fit in `capacity` grains or
+    /// samples. The ones before are counted as dropped, as filling them would only overwrite them.
+    fn cap_fill(&mut self, missed: Range<u64>, capacity: u64) -> Range<u64> {
+        let excess = missed
+            .end
+            .saturating_sub(missed.start)
+            .saturating_sub(capacity);
+        let dropped = excess.div_ceil(self.batch_size);
+        self.stats.dropped += dropped;
+        missed
+            .start
+            .saturating_add(dropped.saturating_mul(self.batch_size))
+            .min(missed.end)..missed.end
+    }
+
+    fn now(&self) -> Timestamp {
+        Timestamp::from_nanos(unsafe { self.context.api.get_time() })
+    }
+
+    /// Waits until the next grain or sample batch is due, and returns its index along with the
+    /// indexes that have been missed and how late it is.
+    fn wait_for_next<I: MediaIndex>(
+        &mut self,
+        rate: &EditRate<I>,
+    ) -> Result<(u64, Range<u64>, Duration)> {
+        let next = match self.next {
+            Some(next) => next,
+            None => {
+                let clock_index = timing::timestamp_to_index(self.now(), rate)?;
+                let first = u64::try_from(i128::from(clock_index.into()) - i128::from(self.offset))
+                    .map_err(|_| Error::TimestampOutOfRange)?;
+                *self.next.insert(first)
+            }
+        };
+        let wait = self.due(next, rate)?.saturating_duration_since(self.now());
+        if !wait.is_zero() {
+            unsafe { self.context.api.sleep_for_ns(wait.as_nanos() as u64) }
+        }
+
+        // Everything before the last boundary the clock has passed is missed.
+        let now = self.now();
+        let clock_index = timing::timestamp_to_index(now, rate)?;
+        let last = i128::from(clock_index.into()) - i128::from(self.offset);
+        let mut due = next;
+        if let Ok(last) = u64::try_from(last)
+            && last > next
+        {
+            due = next + (last - next) / self.batch_size * self.batch_size;
+            // The clock index is rounded to the nearest one, so it may not have been reached yet.
+            if due > next && self.due(due, rate)? > now {
+                due -= self.batch_size;
+            }
+        }
+        if due > next {
+            self.stats.overruns += 1;
+        }
+        Ok((
+            due,
+            next..due,
+            now.saturating_duration_since(self.due(due, rate)?),
+        ))
+    }
+}
+
+impl PacedWriter<GrainWriter> {
+    pub(crate) fn for_grains(writer: GrainWriter) -> Result<Self> {
+        // Fails early for flows without a valid grain rate.
+        writer.config_info().common().grain_rate()?;
+        let context = writer.context().clone();
+        Ok(Self::new(writer, context, 1))
+    }
+
+    /// Waits until the next grain is due and calls `produce` to fill it. The grain is committed
+    /// whole if `produce` continues, and canceled if it breaks or fails.
+    pub fn write_next<F>(&mut self, produce: F) -> Result<ControlFlow<()>>
+    where
+        F: FnOnce(GrainIndex, &mut GrainWriteAccess<'_>) -> Result<ControlFlow<()>>,
+    {
+        let rate = self.writer.config_info().common().grain_rate()?;
+        let grain_count = u64::from(self.writer.config_info().discrete()?.grainCount);
+        let (index, missed, lateness) = self.wait_for_next(&rate)?;
+        for missed in self.cap_fill(missed, grain_count) {
+            self.writer
+                .open_grain(GrainIndex::new(missed))?
+                .commit_invalid()?;
+            self.stats.filled += 1;
+        }
+        self.next = Some(index);
+
+        let mut access = self.writer.open_grain(GrainIndex::new(index))?;
+        let flow = produce(GrainIndex::new(index), &mut access)?;
+        if flow.is_break() {
+            access.cancel()?;
+            return Ok(flow);
+        }
+        let total_slices = access.total_slices();
+        access.commit(total_slices)?;
+        self.stats.record(lateness);
+        // Saturating is fine, the next grain would be out of the range of the timestamps anyway.
+        self.next = Some(index.saturating_add(1));
+        Ok(flow)
+    }
+
+    /// Calls `write_next` until the producer breaks or fails.
+    pub fn run<F>(&mut self, mut produce: F) -> Result<()>
+    where
+        F: FnMut(GrainIndex, &mut GrainWriteAccess<'_>) -> Result<ControlFlow<()>>,
+    {
+        while self.write_next(&mut produce)?.is_continue() {}
+        Ok(())
+    }
+}
+
+impl PacedWriter<SamplesWriter> {
+    pub(crate) fn for_samples(writer: SamplesWriter, batch_size: u64) -> Result<Self> {
+        if batch_size == 0 {
+            return Err(Error::InvalidOptions(
+                "the sample batch size must be at least one sample".to_string(),
+            ));
+        }
+        // Fails early for flows without a valid sample rate.
+        writer.config_info().common().sample_rate()?;
+        let context = writer.context().clone();
+        Ok(Self::new(writer, context, batch_size))
+    }
+
+    pub fn batch_size(&self) -> u64 {
+        self.batch_size
+    }
+
+    /// Waits until the next sample batch is due and calls `produce` to fill it. The batch ends at
+    /// the index given to `produce`, as in `open_samples`. The batch is committed if `produce`
+    /// continues, and canceled if it breaks or fails.
+    pub fn write_next<F>(&mut self, produce: F) -> Result<ControlFlow<()>>
+    where
+        F: FnOnce(SampleIndex, &mut SamplesWriteAccess<'_>) -> Result<ControlFlow<()>>,
+    {
+        let batch_size = usize::try_from(self.batch_size).map_err(|_| {
+            Error::InvalidOptions(format!("invalid sample batch size {}", self.batch_size))
+        })?;
+        let rate = self.writer.config_info().common().sample_rate()?;
+        let readable_length = u64::from(self.writer.config_info().continuous()?.bufferLength) / 2;
+        let (index, missed, lateness) = self.wait_for_next(&rate)?;
+        for missed in self.cap_fill(missed, readable_length).step_by(batch_size) {
+            let mut access = self
+                .writer
+                .open_samples(SampleIndex::new(missed), batch_size)?;
+            for channel in 0..access.channels() {
+                let (first, second) = access.channel_data_mut(channel)?;
+                first.fill(0);
+                second.fill(0);
+            }
+            access.commit()?;
+            self.stats.filled += 1;
+        }
+        self.next = Some(index);
+
+        let mut access = self
+            .writer
+            .open_samples(SampleIndex::new(index), batch_size)?;
+        let flow = produce(SampleIndex::new(index), &mut access)?;
+        if flow.is_break() {
+            access.cancel()?;
+            return Ok(flow);
+        }
+        access.commit()?;
+        self.stats.record(lateness);
+        self.next = Some(index.saturating_add(self.batch_size));
+        Ok(flow)
+    }
+
+    /// Calls `write_next` until the producer breaks or fails.
+    pub fn run<F>(&mut self, mut produce: F) -> Result<()>
+    where
+        F: FnMut(SampleIndex, &mut SamplesWriteAccess<'_>) -> Result<ControlFlow<()>>,
+    {
+        while self.write_next(&mut produce)?.is_continue() {}
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    sync::{
+        Arc,
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    },
+    task::{Context, Poll, Waker},
+    thread::JoinHandle,
+    time::Instant,
+};
+
+use crate::{EditRate, Error, MediaIndex, Result, Timestamp, instance::InstanceContext, timing};
+
+/// Holds writes back until the grain or sample they are about is due, without blocking the
+/// executor: a helper thread wakes the task once the time has come.
+pub(crate) struct Pacer<I> {
+    context: Arc<InstanceContext>,
+    rate: EditRate<I>,
+    timer: Timer,
+}
+
+impl<I: MediaIndex> Pacer<I> {
+    pub(crate) fn new(context: Arc<InstanceContext>, rate: EditRate<I>) -> Result<Self> {
+        Ok(Self {
+            context,
+            rate,
+            timer: Timer::spawn()?,
+        })
+    }
+
+    /// Ready once the current TAI time has reached the timestamp of `index`.
+    pub(crate) fn poll_due(&self, index: I, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let now = Timestamp::from_nanos(unsafe { self.context.api.get_time() });
+        let remaining = match timing::duration_until_index(index, &self.rate, now) {
+            Ok(remaining) => remaining,
+            Err(error) => return Poll::Ready(Err(error)),
+        };
+        if remaining.is_zero() {
+            return Poll::Ready(Ok(()));
+        }
+        let Some(deadline) = Instant::now().checked_add(remaining) else {
+            return Poll::Ready(Err(Error::TimestampOutOfRange));
+        };
+        match self.timer.request(deadline, cx.waker().clone()) {
+            Ok(()) => Poll::Pending,
+            Err(error) => Poll::Ready(Err(error)),
+        }
+    }
+}
+
+struct WakeRequest {
+    deadline: Instant,
+    waker: Waker,
+}
+
+/// Thread waking tasks at a deadline.
+struct Timer {
+    requests: Option<Sender<WakeRequest>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Timer {
+    fn spawn() -> Result<Self> {
+        let (requests, receiver) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("mxl-pacer".to_string())
+            .spawn(move || wake_at_deadlines(receiver))?;
+        Ok(Self {
+            requests: Some(requests),
+            thread: Some(thread),
+        })
+    }
+
+    fn request(&self, deadline: Instant, waker: Waker) -> Result<()> {
+        self.requests
+            .as_ref()
+            .and_then(|requests| requests.send(WakeRequest { deadline, waker }).ok())
+            .ok_or_else(|| Error::Other("The pacing timer thread has stopped.".to_string()))
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        // Disconnects the channel, which stops the thread right away.
+        self.requests = None;
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            tracing::error!("The pacing timer thread panicked.");
+        }
+    }
+}
+
+/// Body of the timer thread. Only the latest request matters, the older ones are from polls that
+/// have been superseded.
+fn wake_at_deadlines(receiver: Receiver<WakeRequest>) {
+    let Ok(mut request) = receiver.recv() else {
+        return;
+    };
+    loop {
+        let remaining = request.deadline.saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(remaining) {
+            Ok(newer) => request = newer,
+            Err(RecvTimeoutError::Timeout) => {
+                request.waker.wake();
+                match receiver.recv() {
+                    Ok(next) => request = next,
+                    Err(_) => return,
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
@@ -0,0 +1,196 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{cmp::Ordering, fmt::Display, str::FromStr, time::Duration};
+
+use crate::{Error, Result};
+
+/// A rational number, used for grain and sample rates.
+///
+/// The value is always reduced and its denominator positive, so that equal values have equal
+/// representations: `50/2 == 25/1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Rational {
+    pub const FPS_23_98: Rational = Rational::from_reduced(24000, 1001);
+    pub const FPS_24: Rational = Rational::from_reduced(24, 1);
+    pub const FPS_25: Rational = Rational::from_reduced(25, 1);
+    pub const FPS_29_97: Rational = Rational::from_reduced(30000, 1001);
+    pub const FPS_30: Rational = Rational::from_reduced(30, 1);
+    pub const FPS_50: Rational = Rational::from_reduced(50, 1);
+    pub const FPS_59_94: Rational = Rational::from_reduced(60000, 1001);
+    pub const FPS_60: Rational = Rational::from_reduced(60, 1);
+    pub const HZ_48000: Rational = Rational::from_reduced(48000, 1);
+    pub const HZ_96000: Rational = Rational::from_reduced(96000, 1);
+
+    /// Fails with `Error::InvalidRate` if `denominator` is zero.
+    pub fn new(numerator: i64, denominator: i64) -> Result<Self> {
+        Self::reduce(i128::from(numerator), i128::from(denominator)).ok_or(Error::InvalidRate {
+            numerator,
+            denominator,
+        })
+    }
+
+    pub const fn from_integer(value: i64) -> Self {
+        Self::from_reduced(value, 1)
+    }
+
+    /// The caller guarantees that the fraction is reduced and the denominator positive.
+    const fn from_reduced(numerator: i64, denominator: i64) -> Self {
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// Reduces the fraction, `None` if the denominator is zero or the result does not fit.
+    fn reduce(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = gcd(numerator.unsigned_abs(), denominator.unsigned_abs()) as i128;
+        let sign = denominator.signum();
+        Some(Self {
+            numerator: i64::try_from(sign * numerator / divisor).ok()?,
+            denominator: i64::try_from(sign * denominator / divisor).ok()?,
+        })
+    }
+
+    pub fn numerator(&self) -> i64 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i64 {
+        self.denominator
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.numerator > 0
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// Fails on a zero value.
+    pub fn recip(&self) -> Result<Self> {
+        Self::new(self.denominator, self.numerator)
+    }
+
+    pub fn checked_add(&self, other: Rational) -> Option<Self> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.1 + b.0 * a.1, a.1 * b.1)
+    }
+
+    pub fn checked_sub(&self, other: Rational) -> Option<Self> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.1 - b.0 * a.1, a.1 * b.1)
+    }
+
+    pub fn checked_mul(&self, other: Rational) -> Option<Self> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.0, a.1 * b.1)
+    }
+
+    /// `None` when dividing by zero or if the result does not fit.
+    pub fn checked_div(&self, other: Rational) -> Option<Self> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.1, a.1 * b.0)
+    }
+
+    /// Duration of a grain or sample at this rate, rounded down to the nanosecond.
+    pub fn frame_duration(&self) -> Result<Duration> {
+        let rate = self.validate_rate()?;
+        let nanos = i128::from(rate.denominator) * 1_000_000_000 / i128::from(rate.numerator);
+        Ok(Duration::from_nanos(nanos as u64))
+    }
+
+    /// Checks that the value can be used as a grain or sample rate, i.e. is positive.
+    pub(crate) fn validate_rate(self) -> Result<Self> {
+        if self.is_positive() {
+            Ok(self)
+        } else {
+            Err(self.invalid_rate())
+        }
+    }
+
+    /// The error reported when the value is refused as a rate.
+    pub(crate) fn invalid_rate(&self) -> Error {
+        Error::InvalidRate {
+            numerator: self.numerator,
+            denominator: self.denominator,
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> mxl_sys::mxlRational {
+        mxl_sys::mxlRational {
+            numerator: self.numerator,
+            denominator: self.denominator,
+        }
+    }
+
+    fn wide(&self) -> (i128, i128) {
+        (i128::from(self.numerator), i128::from(self.denominator))
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    // gcd(0, 0) only happens for a zero numerator with a zero denominator, which is refused.
+    a.max(1)
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (self.wide(), other.wide());
+        (a.0 * b.1).cmp(&(b.0 * a.1))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+impl FromStr for Rational {
+    type Err = Error;
+
+    /// Parses `"30000/1001"`, or an integer such as `"25"`.
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || Error::InvalidRational(value.to_string());
+        let (numerator, denominator) = match value.split_once('/') {
+            Some((numerator, denominator)) => (numerator, denominator),
+            None => (value, "1"),
+        };
+        let numerator = numerator.trim().parse().map_err(|_| invalid())?;
+        let denominator = denominator.trim().parse().map_err(|_| invalid())?;
+        Self::new(numerator, denominator)
+    }
+}
+
+impl TryFrom<mxl_sys::mxlRational> for Rational {
+    type Error = Error;
+
+    fn try_from(value: mxl_sys::mxlRational) -> Result<Self> {
+        Self::new(value.numerator, value.denominator)
+    }
+}
+
+impl From<Rational> for mxl_sys::mxlRational {
+    fn from(value: Rational) -> Self {
+        value.to_raw()
+    }
+}
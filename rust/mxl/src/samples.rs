@@ -1,7 +1,11 @@
 // SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod channel;
+pub mod cursor;
 pub mod data;
+pub mod pcm;
 pub mod reader;
+pub mod sink;
 pub mod write_access;
 pub mod writer;
@@ -0,0 +1,217 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! `audio/float32` samples of a channel, see `docs/architecture.md`.
+//!
+//! The samples of a channel may be split into two fragments when they wrap around the end of the
+//! ring buffer. The views below index across both fragments as if they were a single slice. The
+//! samples are stored as native endian IEEE 754 floats, and read and written by value, so the
+//! buffers do not need to be aligned.
+
+use std::iter;
+
+use crate::{Error, Result};
+
+const SAMPLE_SIZE: usize = size_of::<f32>();
+
+fn check_fragments(first: &[u8], second: &[u8]) -> Result<()> {
+    if !first.len().is_multiple_of(SAMPLE_SIZE) || !second.len().is_multiple_of(SAMPLE_SIZE) {
+        return Err(Error::Other(format!(
+            "Fragments of {} and {} bytes do not hold whole float32 samples.",
+            first.len(),
+            second.len()
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_count(expected: usize, actual: usize) -> Result<()> {
+    if expected != actual {
+        return Err(Error::SampleCountMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+fn read_sample(bytes: &[u8]) -> f32 {
+    // The chunks always hold a whole sample.
+    <[u8; SAMPLE_SIZE]>::try_from(bytes).map_or(0.0, f32::from_ne_bytes)
+}
+
+/// Read-only view of the float32 samples of a channel.
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelSamples<'a> {
+    first: &'a [u8],
+    second: &'a [u8],
+}
+
+impl<'a> ChannelSamples<'a> {
+    /// View over the raw fragments of a channel, as returned by `SamplesData::channel_data`. Fails
+    /// if a fragment does not hold a whole number of samples.
+    pub fn new(first: &'a [u8], second: &'a [u8]) -> Result<Self> {
+        check_fragments(first, second)?;
+        Ok(Self { first, second })
+    }
+
+    /// Number of samples.
+    pub fn len(&self) -> usize {
+        (self.first.len() + self.second.len()) / SAMPLE_SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<f32> {
+        let offset = index.checked_mul(SAMPLE_SIZE)?;
+        let (fragment, offset) = match offset.checked_sub(self.first.len()) {
+            None => (self.first, offset),
+            Some(offset) => (self.second, offset),
+        };
+        fragment
+            .get(offset..offset.checked_add(SAMPLE_SIZE)?)
+            .map(read_sample)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = f32> + 'a {
+        self.first
+            .chunks_exact(SAMPLE_SIZE)
+            .chain(self.second.chunks_exact(SAMPLE_SIZE))
+            .map(read_sample)
+    }
+
+    /// Copies the samples to `samples`, which must have the same length.
+    pub fn copy_to(&self, samples: &mut [f32]) -> Result<()> {
+        check_count(self.len(), samples.len())?;
+        for (sample, value) in samples.iter_mut().zip(self.iter()) {
+            *sample = value;
+        }
+        Ok(())
+    }
+
+    pub fn to_vec(&self) -> Vec<f32> {
+        self.iter().collect()
+    }
+
+    /// The raw fragments, in order.
+    pub(crate) fn fragments(&self) -> [&'a [u8]; 2] {
+        [self.first, self.second]
+    }
+}
+
+/// Mutable view of the float32 samples of a channel.
+#[derive(Debug)]
+pub struct ChannelSamplesMut<'a> {
+    first: &'a mut [u8],
+    second: &'a mut [u8],
+}
+
+impl<'a> ChannelSamplesMut<'a> {
+    /// View over the raw fragments of a channel, as returned by
+    /// `SamplesWriteAccess::channel_data_mut`. Fails if a fragment does not hold a whole number
+    /// of samples.
+    pub fn new(first: &'a mut [u8], second: &'a mut [u8]) -> Result<Self> {
+        check_fragments(first, second)?;
+        Ok(Self { first, second })
+    }
+
+    /// Number of samples.
+    pub fn len(&self) -> usize {
+        (self.first.len() + self.second.len()) / SAMPLE_SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn as_samples(&self) -> ChannelSamples<'_> {
+        ChannelSamples {
+            first: self.first,
+            second: self.second,
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<f32> {
+        self.as_samples().get(index)
+    }
+
+    /// Fails with `Error::InvalidArg` if `index` is out of range.
+    pub fn set(&mut self, index: usize, value: f32) -> Result<()> {
+        let first_length = self.first.len();
+        let offset = index.checked_mul(SAMPLE_SIZE).ok_or(Error::InvalidArg)?;
+        let (fragment, offset) = match offset.checked_sub(first_length) {
+            None => (&mut *self.first, offset),
+            Some(offset) => (&mut *self.second, offset),
+        };
+        let end = offset.checked_add(SAMPLE_SIZE).ok_or(Error::InvalidArg)?;
+        fragment
+            .get_mut(offset..end)
+            .ok_or(Error::InvalidArg)?
+            .copy_from_slice(&value.to_ne_bytes());
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = f32> + '_ {
+        self.first
+            .chunks_exact(SAMPLE_SIZE)
+            .chain(self.second.chunks_exact(SAMPLE_SIZE))
+            .map(read_sample)
+    }
+
+    /// Copies `samples`, which must have the same length.
+    pub fn copy_from(&mut self, samples: &[f32]) -> Result<()> {
+        check_count(self.len(), samples.len())?;
+        self.write_all(samples.iter().copied());
+        Ok(())
+    }
+
+    pub fn fill(&mut self, value: f32) {
+        self.write_all(iter::repeat(value));
+    }
+
+    /// The raw fragments, in order.
+    pub(crate) fn fragments_mut(&mut self) -> [&mut [u8]; 2] {
+        [&mut *self.first, &mut *self.second]
+    }
+
+    /// Writes the samples in order, as long as `values` yields some.
+    pub(crate) fn write_all(&mut self, values: impl Iterator<Item = f32>) {
+        let chunks = self
+            .first
+            .chunks_exact_mut(SAMPLE_SIZE)
+            .chain(self.second.chunks_exact_mut(SAMPLE_SIZE));
+        for (chunk, value) in chunks.zip(values) {
+            chunk.copy_from_slice(&value.to_ne_bytes());
+        }
+    }
+}
+
+/// Interleaves `channels` into `interleaved`, which must hold all their samples: the first sample
+/// of every channel, then the second one, and so on.
+pub fn interleave(channels: &[ChannelSamples<'_>], interleaved: &mut [f32]) -> Result<()> {
+    let length = channels.first().map_or(0, ChannelSamples::len);
+    for channel in channels {
+        check_count(length, channel.len())?;
+    }
+    check_count(length * channels.len(), interleaved.len())?;
+    for (index, channel) in channels.iter().enumerate() {
+        let targets = interleaved.iter_mut().skip(index).step_by(channels.len());
+        for (target, value) in targets.zip(channel.iter()) {
+            *target = value;
+        }
+    }
+    Ok(())
+}
+
+/// Splits `interleaved` into `channels`, the reverse of `interleave`.
+pub fn deinterleave(interleaved: &[f32], channels: &mut [ChannelSamplesMut<'_>]) -> Result<()> {
+    let length = channels.first().map_or(0, ChannelSamplesMut::len);
+    for channel in channels.iter() {
+        check_count(length, channel.len())?;
+    }
+    check_count(length * channels.len(), interleaved.len())?;
+    let count = channels.len();
+    for (index, channel) in channels.iter_mut().enumerate() {
+        channel.write_all(interleaved.iter().skip(index).step_by(count).copied());
+    }
+    Ok(())
+}
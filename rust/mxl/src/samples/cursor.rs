@@ -0,0 +1,175 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use crate::{
+    Error, Result, SampleIndex, SampleRate, SamplesData, SamplesReader, Timestamp,
+    error::{ErrorContext, ResultExt},
+    timing::{self, SampleRing},
+};
+
+/// Item read by a `SamplesCursor`.
+pub enum CursorEvent<'a> {
+    /// The samples from `start` on, as many as `data` holds.
+    Samples {
+        start: SampleIndex,
+        data: SamplesData<'a>,
+    },
+    /// The samples from `missed` up to `resumed` excluded had been overwritten before they could
+    /// be read. They are lost, the cursor continues at `resumed` with the latest samples.
+    Overrun {
+        missed: SampleIndex,
+        resumed: SampleIndex,
+    },
+}
+
+impl CursorEvent<'_> {
+    /// Number of samples lost by an overrun, zero for samples.
+    pub fn lost_samples(&self) -> u64 {
+        match self {
+            CursorEvent::Samples { .. } => 0,
+            CursorEvent::Overrun { missed, resumed } => resumed.saturating_distance_since(*missed),
+        }
+    }
+}
+
+/// Reads the samples of a flow forward from a start index, see `SamplesReader::cursor`.
+///
+/// Unlike `SamplesReader::get_samples`, which takes the samples ending at an index, the cursor
+/// keeps the index of the next sample to read and moves past the samples it returns. Requests of
+/// any size are split into chunks no larger than half of the channel buffers, the most the MXL
+/// library can return at once.
+pub struct SamplesCursor<'a> {
+    reader: &'a SamplesReader,
+    /// Index of the next sample to read.
+    position: SampleIndex,
+    timeout: Duration,
+    ring: SampleRing,
+    rate: SampleRate,
+    chunk_size: usize,
+}
+
+impl<'a> SamplesCursor<'a> {
+    pub(crate) fn new(
+        reader: &'a SamplesReader,
+        start: SampleIndex,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let config = reader.config_info();
+        let ring = SampleRing::new(u64::from(config.continuous()?.bufferLength))?;
+        let rate = config.common().sample_rate()?;
+        let hint = match config.common().max_commit_batch_size_hint() {
+            // About 10 ms without a hint from the writer.
+            0 => rate.numerator() / (100 * rate.denominator()),
+            hint => i64::from(hint),
+        };
+        let max_chunk_size = ring.buffer_length() / 2;
+        let chunk_size = u64::try_from(hint)
+            .unwrap_or(1)
+            .clamp(1, max_chunk_size.max(1));
+        Ok(Self {
+            reader,
+            position: start,
+            timeout,
+            ring,
+            rate,
+            chunk_size: usize::try_from(chunk_size).map_err(|_| Error::InvalidArg)?,
+        })
+    }
+
+    /// Sets the largest number of samples read at once, the commit batch size hint of the flow by
+    /// default. Fails with `Error::InvalidArg` unless it is positive and no larger than half of
+    /// the channel buffers.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Result<Self> {
+        if chunk_size == 0 || chunk_size as u64 > self.ring.buffer_length() / 2 {
+            return Err(Error::InvalidArg.with_context(self.error_context("set chunk size")));
+        }
+        self.chunk_size = chunk_size;
+        Ok(self)
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Index of the next sample to read.
+    pub fn position(&self) -> SampleIndex {
+        self.position
+    }
+
+    /// TAI time of the next sample to read.
+    pub fn position_timestamp(&self) -> Result<Timestamp> {
+        timing::index_to_timestamp(self.position, &self.rate)
+    }
+
+    pub fn seek(&mut self, index: SampleIndex) {
+        self.position = index;
+    }
+
+    /// Moves to the sample at `timestamp`, rounded to the nearest one, and returns its index.
+    pub fn seek_to_timestamp(&mut self, timestamp: Timestamp) -> Result<SampleIndex> {
+        self.position = timing::timestamp_to_index(timestamp, &self.rate)?;
+        Ok(self.position)
+    }
+
+    /// Reads the next samples, at most `max_count` and at most the chunk size, waiting for them
+    /// up to the timeout of the cursor. Fails with `Error::Timeout` at the root if they are still
+    /// missing, in which case the cursor does not move.
+    pub fn next_chunk(&mut self, max_count: usize) -> Result<CursorEvent<'a>> {
+        let count = max_count.min(self.chunk_size);
+        if count == 0 {
+            return Err(Error::InvalidArg.with_context(self.error_context("read samples")));
+        }
+        let start = self.position;
+        let end = start
+            .checked_add(count as u64)
+            .ok_or(Error::TimestampOutOfRange)
+            .context(|| self.error_context("read samples"))?;
+        match self.reader.get_samples(end, count, self.timeout) {
+            Ok(data) => {
+                self.position = end;
+                Ok(CursorEvent::Samples { start, data })
+            }
+            Err(error) if matches!(error.root(), Error::OutOfRangeTooLate) => {
+                let head = self.reader.head_index()?;
+                let resumed = (head - count as u64).max(self.ring.oldest(head));
+                self.position = resumed.max(start);
+                Ok(CursorEvent::Overrun {
+                    missed: start,
+                    resumed: self.position,
+                })
+            }
+            // `mxlFlowReaderGetSamples` reports missing samples as too early, even after waiting.
+            Err(error) if matches!(error.root(), Error::OutOfRangeTooEarly) => {
+                Err(Error::Timeout.with_context(self.error_context("read samples")))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Reads the next `count` samples chunk by chunk and passes them to `consume`, along with the
+    /// overruns. The samples lost by an overrun count towards `count`.
+    pub fn read<F>(&mut self, count: usize, mut consume: F) -> Result<()>
+    where
+        F: FnMut(CursorEvent<'a>) -> Result<()>,
+    {
+        let end = self
+            .position
+            .checked_add(count as u64)
+            .ok_or(Error::TimestampOutOfRange)
+            .context(|| self.error_context("read samples"))?;
+        while self.position < end {
+            let remaining = end.saturating_distance_since(self.position);
+            let event = self.next_chunk(usize::try_from(remaining).unwrap_or(usize::MAX))?;
+            consume(event)?;
+        }
+        Ok(())
+    }
+
+    fn error_context(&self, operation: &'static str) -> ErrorContext {
+        ErrorContext::new(operation)
+            .flow_id(self.reader.flow_id())
+            .index(self.position)
+    }
+}
@@ -3,7 +3,7 @@
 
 use std::marker::PhantomData;
 
-use crate::Error;
+use crate::{ChannelSamples, Error, Result, samples::channel::interleave};
 
 pub struct SamplesData<'a> {
     buffer_slice: mxl_sys::mxlWrappedMultiBufferSlice,
@@ -40,6 +40,21 @@ impl<'a> SamplesData<'a> {
         }
     }
 
+    /// Float32 samples of the given channel, across both fragments.
+    pub fn channel_samples(&self, channel: usize) -> Result<ChannelSamples<'_>> {
+        let (first, second) = self.channel_data(channel)?;
+        ChannelSamples::new(first, second)
+    }
+
+    /// Interleaves the float32 samples of all the channels into `interleaved`, which must hold
+    /// them all.
+    pub fn interleave_to(&self, interleaved: &mut [f32]) -> Result<()> {
+        let channels = (0..self.num_of_channels())
+            .map(|channel| self.channel_samples(channel))
+            .collect::<Result<Vec<_>>>()?;
+        interleave(&channels, interleaved)
+    }
+
     pub fn to_owned(&self) -> OwnedSamplesData {
         self.into()
     }
@@ -56,6 +71,14 @@ pub struct OwnedSamplesData {
     pub payload: Vec<Vec<u8>>,
 }
 
+impl OwnedSamplesData {
+    /// Float32 samples of the given channel.
+    pub fn channel_samples(&self, channel: usize) -> Result<ChannelSamples<'_>> {
+        let payload = self.payload.get(channel).ok_or(Error::InvalidArg)?;
+        ChannelSamples::new(payload, &[])
+    }
+}
+
 impl<'a> From<&SamplesData<'a>> for OwnedSamplesData {
     fn from(value: &SamplesData<'a>) -> Self {
         let mut payload = Vec::with_capacity(value.buffer_slice.count);
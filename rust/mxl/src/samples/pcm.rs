@@ -0,0 +1,405 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversions between the `audio/float32` samples of MXL and integer PCM.
+//!
+//! The samples are converted one block of fixed size at a time, the dither noise of a block being
+//! generated before its samples are rounded. Planar PCM and single channels are contiguous, so
+//! their blocks are converted as a whole, with the sample format resolved once per block rather
+//! than once per sample.
+
+use super::channel::check_count;
+
+use crate::{ChannelSamples, ChannelSamplesMut, Error, Result, SamplesData, SamplesWriteAccess};
+
+/// Samples converted at once.
+const BLOCK_SIZE: usize = 64;
+
+const SAMPLE_SIZE: usize = size_of::<f32>();
+
+/// Integer PCM sample formats, all signed little endian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PcmFormat {
+    S16Le,
+    /// Packed in 3 bytes.
+    S24Le,
+    S32Le,
+}
+
+impl PcmFormat {
+    pub const fn bytes_per_sample(&self) -> usize {
+        match self {
+            PcmFormat::S16Le => 2,
+            PcmFormat::S24Le => 3,
+            PcmFormat::S32Le => 4,
+        }
+    }
+
+    pub const fn bits(&self) -> u32 {
+        self.bytes_per_sample() as u32 * 8
+    }
+
+    /// Integer value of the float full scale 1.0.
+    fn full_scale(&self) -> f64 {
+        f64::from(1u32 << (self.bits() - 1))
+    }
+
+    fn write(&self, value: i32, bytes: &mut [u8]) {
+        let value = value.to_le_bytes();
+        bytes.copy_from_slice(&value[..bytes.len()]);
+    }
+
+    fn read(&self, bytes: &[u8]) -> i32 {
+        // Sign extends from the top byte.
+        let mut value = [0; 4];
+        value[4 - bytes.len()..].copy_from_slice(bytes);
+        i32::from_le_bytes(value) >> (32 - self.bits())
+    }
+
+    /// Writes contiguous samples, `bytes` holding exactly as many samples as `values`.
+    fn write_block(&self, values: &[i32], bytes: &mut [u8]) {
+        match self {
+            PcmFormat::S16Le => write_le::<2>(values, bytes),
+            PcmFormat::S24Le => write_le::<3>(values, bytes),
+            PcmFormat::S32Le => write_le::<4>(values, bytes),
+        }
+    }
+
+    /// Reads contiguous samples, `bytes` holding exactly as many samples as `values`.
+    fn read_block(&self, bytes: &[u8], values: &mut [i32]) {
+        match self {
+            PcmFormat::S16Le => read_le::<2>(bytes, values),
+            PcmFormat::S24Le => read_le::<3>(bytes, values),
+            PcmFormat::S32Le => read_le::<4>(bytes, values),
+        }
+    }
+}
+
+fn write_le<const N: usize>(values: &[i32], bytes: &mut [u8]) {
+    for (value, bytes) in values.iter().zip(bytes.chunks_exact_mut(N)) {
+        bytes.copy_from_slice(&value.to_le_bytes()[..N]);
+    }
+}
+
+fn read_le<const N: usize>(bytes: &[u8], values: &mut [i32]) {
+    for (bytes, value) in bytes.chunks_exact(N).zip(values.iter_mut()) {
+        let mut extended = [0; 4];
+        extended[4 - N..].copy_from_slice(bytes);
+        *value = i32::from_le_bytes(extended) >> (32 - 8 * N);
+    }
+}
+
+/// Dither added when reducing float samples to integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dither {
+    /// Rounds to the nearest integer.
+    #[default]
+    None,
+    /// Triangular probability density function dither of one least significant bit peak.
+    Tpdf,
+}
+
+/// Converts between MXL samples and integer PCM, interleaved or planar.
+///
+/// Float samples outside of the full-scale range \[−1.0 ; +1.0\] are clipped, the conversions to
+/// integers return how many samples have been clipped. The dither noise comes from a pseudorandom
+/// generator with a fixed seed, so the conversions are reproducible.
+#[derive(Debug, Clone)]
+pub struct PcmConverter {
+    format: PcmFormat,
+    dither: Dither,
+    random: u64,
+}
+
+impl PcmConverter {
+    pub fn new(format: PcmFormat) -> Self {
+        Self {
+            format,
+            dither: Dither::None,
+            random: 0x9e37_79b9_7f4a_7c15,
+        }
+    }
+
+    pub fn with_dither(mut self, dither: Dither) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Seeds the dither noise generator.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        // The generator must not be seeded with zero.
+        self.random = seed | 1;
+        self
+    }
+
+    pub fn format(&self) -> PcmFormat {
+        self.format
+    }
+
+    pub fn dither(&self) -> Dither {
+        self.dither
+    }
+
+    /// Converts all the channels of `samples` to interleaved PCM. `pcm` must hold exactly the
+    /// samples of all the channels. Returns the number of clipped samples.
+    pub fn encode_interleaved(
+        &mut self,
+        samples: &SamplesData<'_>,
+        pcm: &mut [u8],
+    ) -> Result<usize> {
+        let channels = channel_samples(samples)?;
+        let width = self.format.bytes_per_sample();
+        let stride = width * channels.len();
+        let length = channels.first().map_or(0, ChannelSamples::len);
+        self.check_length(length * channels.len(), pcm.len())?;
+        let mut clipped = 0;
+        for (index, channel) in channels.iter().enumerate() {
+            check_count(length, channel.len())?;
+            clipped += self.encode_strided(channel, pcm, index * width, stride);
+        }
+        Ok(clipped)
+    }
+
+    /// Converts every channel of `samples` to its own PCM buffer in `planes`, which must hold
+    /// exactly the samples of the channel. Returns the number of clipped samples.
+    pub fn encode_planar(
+        &mut self,
+        samples: &SamplesData<'_>,
+        planes: &mut [&mut [u8]],
+    ) -> Result<usize> {
+        let channels = channel_samples(samples)?;
+        check_channels(channels.len(), planes.len())?;
+        let mut clipped = 0;
+        for (channel, plane) in channels.iter().zip(planes.iter_mut()) {
+            self.check_length(channel.len(), plane.len())?;
+            clipped += self.encode_contiguous(channel, plane);
+        }
+        Ok(clipped)
+    }
+
+    /// Converts the samples of a single channel to PCM. Returns the number of clipped samples.
+    pub fn encode_channel(
+        &mut self,
+        samples: &ChannelSamples<'_>,
+        pcm: &mut [u8],
+    ) -> Result<usize> {
+        self.check_length(samples.len(), pcm.len())?;
+        Ok(self.encode_contiguous(samples, pcm))
+    }
+
+    /// Converts interleaved PCM to all the channels of `access`. `pcm` must hold exactly the
+    /// samples of all the channels.
+    pub fn decode_interleaved(
+        &self,
+        pcm: &[u8],
+        access: &mut SamplesWriteAccess<'_>,
+    ) -> Result<()> {
+        let count = access.channels();
+        let width = self.format.bytes_per_sample();
+        for channel in 0..count {
+            let mut samples = access.channel_samples_mut(channel)?;
+            self.check_length(samples.len() * count, pcm.len())?;
+            self.decode_strided(pcm, channel * width, width * count, &mut samples);
+        }
+        Ok(())
+    }
+
+    /// Converts planar PCM, one buffer per channel, to the channels of `access`.
+    pub fn decode_planar(
+        &self,
+        planes: &[&[u8]],
+        access: &mut SamplesWriteAccess<'_>,
+    ) -> Result<()> {
+        check_channels(access.channels(), planes.len())?;
+        for (channel, plane) in planes.iter().enumerate() {
+            self.decode_channel(plane, &mut access.channel_samples_mut(channel)?)?;
+        }
+        Ok(())
+    }
+
+    /// Converts PCM to the samples of a single channel.
+    pub fn decode_channel(&self, pcm: &[u8], samples: &mut ChannelSamplesMut<'_>) -> Result<()> {
+        self.check_length(samples.len(), pcm.len())?;
+        self.decode_contiguous(pcm, samples);
+        Ok(())
+    }
+
+    /// Checks that `bytes` of PCM hold exactly `samples` samples.
+    fn check_length(&self, samples: usize, bytes: usize) -> Result<()> {
+        let width = self.format.bytes_per_sample();
+        if !bytes.is_multiple_of(width) {
+            return Err(Error::Other(format!(
+                "{bytes} bytes do not hold whole {:?} samples.",
+                self.format
+            )));
+        }
+        check_count(samples, bytes / width)
+    }
+
+    /// Writes the samples every `stride` bytes of `pcm`, from `offset` on.
+    fn encode_strided(
+        &mut self,
+        samples: &ChannelSamples<'_>,
+        pcm: &mut [u8],
+        offset: usize,
+        stride: usize,
+    ) -> usize {
+        let width = self.format.bytes_per_sample();
+        let full_scale = self.format.full_scale();
+        let mut targets = pcm
+            .get_mut(offset..)
+            .unwrap_or_default()
+            .chunks_mut(stride)
+            .filter_map(|chunk| chunk.get_mut(..width));
+        let mut values = samples.iter();
+        let mut clipped = 0;
+
+        let mut block = [0.0f64; BLOCK_SIZE];
+        let mut output = [0i32; BLOCK_SIZE];
+        loop {
+            let mut length = 0;
+            for (value, sample) in block.iter_mut().zip(values.by_ref()) {
+                *value = f64::from(sample) * full_scale;
+                length += 1;
+            }
+            if length == 0 {
+                return clipped;
+            }
+            clipped += self.quantize(&block[..length], &mut output);
+            for (value, target) in output[..length].iter().zip(targets.by_ref()) {
+                self.format.write(*value, target);
+            }
+        }
+    }
+
+    /// Writes the samples to `pcm`, which holds exactly as many contiguous samples.
+    fn encode_contiguous(&mut self, samples: &ChannelSamples<'_>, pcm: &mut [u8]) -> usize {
+        let width = self.format.bytes_per_sample();
+        let full_scale = self.format.full_scale();
+        let [first, second] = samples.fragments();
+        // The lengths have been checked by the caller.
+        let Some((first_pcm, second_pcm)) =
+            pcm.split_at_mut_checked(first.len() / SAMPLE_SIZE * width)
+        else {
+            return 0;
+        };
+        let mut clipped = 0;
+
+        let mut block = [0.0f64; BLOCK_SIZE];
+        let mut output = [0i32; BLOCK_SIZE];
+        for (fragment, pcm) in [(first, first_pcm), (second, second_pcm)] {
+            let sources = fragment.chunks(BLOCK_SIZE * SAMPLE_SIZE);
+            for (source, target) in sources.zip(pcm.chunks_mut(BLOCK_SIZE * width)) {
+                let length = source.len() / SAMPLE_SIZE;
+                for (value, bytes) in block.iter_mut().zip(source.chunks_exact(SAMPLE_SIZE)) {
+                    *value = f64::from(read_float(bytes)) * full_scale;
+                }
+                clipped += self.quantize(&block[..length], &mut output);
+                self.format.write_block(&output[..length], target);
+            }
+        }
+        clipped
+    }
+
+    /// Rounds a block of samples scaled to the integer range to `output`, adding the dither
+    /// noise. Returns the number of samples outside of the full-scale range, which are clipped.
+    fn quantize(&mut self, block: &[f64], output: &mut [i32; BLOCK_SIZE]) -> usize {
+        let full_scale = self.format.full_scale();
+        let (min, max) = (-full_scale, full_scale - 1.0);
+        let mut noise = [0.0f64; BLOCK_SIZE];
+        let noise = &mut noise[..block.len().min(BLOCK_SIZE)];
+        self.fill_noise(noise);
+        let mut clipped = 0;
+        for ((value, noise), output) in block.iter().zip(noise.iter()).zip(output.iter_mut()) {
+            // +1.0 is rounded down to the largest integer without counting as clipped.
+            clipped += usize::from(value.abs() > full_scale);
+            *output = (value + noise).round().clamp(min, max) as i32;
+        }
+        clipped
+    }
+
+    /// Reads the samples every `stride` bytes of `pcm`, from `offset` on.
+    fn decode_strided(
+        &self,
+        pcm: &[u8],
+        offset: usize,
+        stride: usize,
+        samples: &mut ChannelSamplesMut<'_>,
+    ) {
+        let width = self.format.bytes_per_sample();
+        let scale = 1.0 / self.format.full_scale();
+        let values = pcm
+            .get(offset..)
+            .unwrap_or_default()
+            .chunks(stride)
+            .filter_map(|chunk| chunk.get(..width))
+            .map(|bytes| (f64::from(self.format.read(bytes)) * scale) as f32);
+        samples.write_all(values);
+    }
+
+    /// Reads the samples from `pcm`, which holds exactly as many contiguous samples.
+    fn decode_contiguous(&self, pcm: &[u8], samples: &mut ChannelSamplesMut<'_>) {
+        let width = self.format.bytes_per_sample();
+        let scale = 1.0 / self.format.full_scale();
+        let [first, second] = samples.fragments_mut();
+        // The lengths have been checked by the caller.
+        let Some((first_pcm, second_pcm)) = pcm.split_at_checked(first.len() / SAMPLE_SIZE * width)
+        else {
+            return;
+        };
+
+        let mut values = [0i32; BLOCK_SIZE];
+        for (fragment, pcm) in [(first, first_pcm), (second, second_pcm)] {
+            let targets = fragment.chunks_mut(BLOCK_SIZE * SAMPLE_SIZE);
+            for (source, target) in pcm.chunks(BLOCK_SIZE * width).zip(targets) {
+                self.format.read_block(source, &mut values);
+                for (value, bytes) in values.iter().zip(target.chunks_exact_mut(SAMPLE_SIZE)) {
+                    let sample = (f64::from(*value) * scale) as f32;
+                    bytes.copy_from_slice(&sample.to_ne_bytes());
+                }
+            }
+        }
+    }
+
+    fn fill_noise(&mut self, noise: &mut [f64]) {
+        match self.dither {
+            Dither::None => noise.fill(0.0),
+            Dither::Tpdf => {
+                for value in noise {
+                    // The sum of two uniform values in [-0.5, 0.5) has a triangular density.
+                    *value = self.next_uniform() + self.next_uniform();
+                }
+            }
+        }
+    }
+
+    /// Uniform value in [-0.5, 0.5), from a xorshift64* generator.
+    fn next_uniform(&mut self) -> f64 {
+        self.random ^= self.random >> 12;
+        self.random ^= self.random << 25;
+        self.random ^= self.random >> 27;
+        let value = self.random.wrapping_mul(0x2545_f491_4f6c_dd1d);
+        // The 53 top bits make a double in [0, 1).
+        (value >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    }
+}
+
+fn read_float(bytes: &[u8]) -> f32 {
+    // The chunks always hold a whole sample.
+    <[u8; SAMPLE_SIZE]>::try_from(bytes).map_or(0.0, f32::from_ne_bytes)
+}
+
+fn channel_samples<'a>(samples: &'a SamplesData<'_>) -> Result<Vec<ChannelSamples<'a>>> {
+    (0..samples.num_of_channels())
+        .map(|channel| samples.channel_samples(channel))
+        .collect()
+}
+
+fn check_channels(expected: usize, actual: usize) -> Result<()> {
+    if expected != actual {
+        return Err(Error::Other(format!(
+            "{actual} channels provided, but the flow has {expected}."
+        )));
+    }
+    Ok(())
+}
@@ -4,9 +4,10 @@
 use std::{sync::Arc, time::Duration};
 
 use crate::{
-    Error, Result, SamplesData,
+    Error, FlowRead, Result, SampleIndex, SamplesCursor, SamplesData,
+    error::{ErrorContext, ResultExt},
     flow::{
-        FlowConfigInfo, FlowInfo,
+        FlowConfigInfo, FlowInfo, FlowRuntimeInfo,
         reader::{get_config_info, get_flow_info, get_runtime_info},
     },
     instance::InstanceContext,
@@ -15,6 +16,8 @@ use crate::{
 pub struct SamplesReader {
     context: Arc<InstanceContext>,
     reader: mxl_sys::mxlFlowReader,
+    id: uuid::Uuid,
+    config: FlowConfigInfo,
 }
 
 /// The MXL readers and writers are not thread-safe, so we do not implement `Sync` for them, but
@@ -22,31 +25,58 @@ pub struct SamplesReader {
 unsafe impl Send for SamplesReader {}
 
 impl SamplesReader {
-    pub(crate) fn new(context: Arc<InstanceContext>, reader: mxl_sys::mxlFlowReader) -> Self {
-        Self { context, reader }
+    pub(crate) fn new(
+        context: Arc<InstanceContext>,
+        reader: mxl_sys::mxlFlowReader,
+        id: uuid::Uuid,
+        config: FlowConfigInfo,
+    ) -> Self {
+        Self {
+            context,
+            reader,
+            id,
+            config,
+        }
+    }
+
+    pub fn flow_id(&self) -> uuid::Uuid {
+        self.id
+    }
+
+    /// The config of the flow, captured when the reader was created.
+    pub fn config_info(&self) -> &FlowConfigInfo {
+        &self.config
     }
 
     pub fn destroy(mut self) -> Result<()> {
         self.destroy_inner()
     }
 
-    /// The whole FlowInfo is quite a chunk of data. Go for `get_config_info` or `get_runtime_info`
+    /// The whole FlowInfo is quite a chunk of data. Go for `config_info` or `get_runtime_info`
     /// if they contain what you need.
     pub fn get_info(&self) -> Result<FlowInfo> {
         get_flow_info(&self.context, self.reader)
+            .context(|| ErrorContext::new("get flow info").flow_id(self.id))
     }
 
     pub fn get_config_info(&self) -> Result<FlowConfigInfo> {
         get_config_info(&self.context, self.reader)
+            .context(|| ErrorContext::new("get flow config").flow_id(self.id))
+    }
+
+    pub fn get_runtime_info(&self) -> Result<FlowRuntimeInfo> {
+        get_runtime_info(&self.context, self.reader, &self.config)
+            .context(|| ErrorContext::new("get flow runtime info").flow_id(self.id))
     }
 
-    pub fn get_runtime_info(&self) -> Result<mxl_sys::mxlFlowRuntimeInfo> {
-        get_runtime_info(&self.context, self.reader)
+    /// Index following the last sample committed to the flow.
+    pub fn head_index(&self) -> Result<SampleIndex> {
+        self.get_runtime_info()?.sample_head_index()
     }
 
     pub fn get_samples(
         &self,
-        index: u64,
+        index: SampleIndex,
         count: usize,
         timeout: Duration,
     ) -> Result<SamplesData<'_>> {
@@ -55,28 +85,45 @@ impl SamplesReader {
         unsafe {
             Error::from_status(self.context.api.flow_reader_get_samples(
                 self.reader,
-                index,
+                index.value(),
                 count,
                 timeout_ns,
                 &mut buffer_slice,
-            ))?;
+            ))
+            .context(|| self.error_context(index))?;
         }
         Ok(SamplesData::new(buffer_slice))
     }
 
-    pub fn get_samples_non_blocking(&self, index: u64, count: usize) -> Result<SamplesData<'_>> {
+    pub fn get_samples_non_blocking(
+        &self,
+        index: SampleIndex,
+        count: usize,
+    ) -> Result<SamplesData<'_>> {
         let mut buffer_slice: mxl_sys::mxlWrappedMultiBufferSlice = unsafe { std::mem::zeroed() };
         unsafe {
             Error::from_status(self.context.api.flow_reader_get_samples_non_blocking(
                 self.reader,
-                index,
+                index.value(),
                 count,
                 &mut buffer_slice,
-            ))?;
+            ))
+            .context(|| self.error_context(index))?;
         }
         Ok(SamplesData::new(buffer_slice))
     }
 
+    /// Cursor reading the samples forward from `start`, waiting up to `timeout` for every chunk.
+    pub fn cursor(&self, start: SampleIndex, timeout: Duration) -> Result<SamplesCursor<'_>> {
+        SamplesCursor::new(self, start, timeout)
+    }
+
+    fn error_context(&self, index: SampleIndex) -> ErrorContext {
+        ErrorContext::new("get samples")
+            .flow_id(self.id)
+            .index(index)
+    }
+
     fn destroy_inner(&mut self) -> Result<()> {
         if self.reader.is_null() {
             return Err(Error::InvalidArg);
@@ -93,6 +140,28 @@ impl SamplesReader {
     }
 }
 
+impl FlowRead for SamplesReader {
+    fn flow_id(&self) -> uuid::Uuid {
+        self.flow_id()
+    }
+
+    fn config_info(&self) -> &FlowConfigInfo {
+        self.config_info()
+    }
+
+    fn get_info(&self) -> Result<FlowInfo> {
+        self.get_info()
+    }
+
+    fn get_config_info(&self) -> Result<FlowConfigInfo> {
+        self.get_config_info()
+    }
+
+    fn get_runtime_info(&self) -> Result<FlowRuntimeInfo> {
+        self.get_runtime_info()
+    }
+}
+
 impl Drop for SamplesReader {
     fn drop(&mut self) {
         if !self.reader.is_null()
@@ -0,0 +1,154 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Sink;
+
+use crate::{Error, Result, SampleIndex, SamplesWriter, error::ErrorContext, pacing::Pacer};
+
+/// A block of samples to write with a `SamplesSink`: the `count` samples of every channel ending
+/// at `index`, as `open_samples` takes them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SamplesBlock<B = Vec<u8>> {
+    pub index: SampleIndex,
+    pub count: usize,
+    /// The raw samples of every channel of the flow, `count` samples each.
+    pub channels: Vec<B>,
+}
+
+impl<B> SamplesBlock<B> {
+    pub fn new(index: SampleIndex, count: usize, channels: Vec<B>) -> Self {
+        Self {
+            index,
+            count,
+            channels,
+        }
+    }
+}
+
+/// Asynchronous sink of the samples of a flow, see `SamplesWriter::into_sink`.
+///
+/// Every block is opened, copied, and committed as a whole, or canceled if it cannot be written.
+/// The sink holds a single block: `poll_ready` is pending until the previous block has been
+/// written. When paced, a block is not written before the timestamp of its index, and waiting for
+/// it does not block the executor.
+pub struct SamplesSink<B = Vec<u8>> {
+    writer: SamplesWriter,
+    pacer: Option<Pacer<SampleIndex>>,
+    pending: Option<SamplesBlock<B>>,
+}
+
+impl<B: AsRef<[u8]>> SamplesSink<B> {
+    pub(crate) fn new(writer: SamplesWriter) -> Self {
+        Self {
+            writer,
+            pacer: None,
+            pending: None,
+        }
+    }
+
+    /// Paces the writes to the sample rate of the flow.
+    pub fn paced(mut self) -> Result<Self> {
+        let rate = self.writer.config_info().common().sample_rate()?;
+        self.pacer = Some(Pacer::new(self.writer.context().clone(), rate)?);
+        Ok(self)
+    }
+
+    pub fn is_paced(&self) -> bool {
+        self.pacer.is_some()
+    }
+
+    /// Stops the sink and gives the writer back. A block that has not been flushed is dropped.
+    pub fn into_writer(self) -> SamplesWriter {
+        self.writer
+    }
+
+    /// Writes the pending block, once due when paced.
+    fn poll_write_pending(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let Some(index) = self.pending.as_ref().map(|block| block.index) else {
+            return Poll::Ready(Ok(()));
+        };
+        if let Some(pacer) = &self.pacer {
+            match pacer.poll_due(index, cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(error)) => {
+                    self.pending = None;
+                    return Poll::Ready(Err(error.with_context(self.error_context(index))));
+                }
+                Poll::Ready(Ok(())) => {}
+            }
+        }
+        Poll::Ready(
+            self.pending
+                .take()
+                .map_or(Ok(()), |block| self.write(&block)),
+        )
+    }
+
+    fn write(&mut self, block: &SamplesBlock<B>) -> Result<()> {
+        let error_context = self.error_context(block.index);
+        let mut access = self.writer.open_samples(block.index, block.count)?;
+        // Dropping the access on error cancels the samples.
+        if block.channels.len() != access.channels() {
+            return Err(Error::Other(format!(
+                "{} channels sent, but the flow has {}.",
+                block.channels.len(),
+                access.channels()
+            ))
+            .with_context(error_context));
+        }
+        for (channel, samples) in block.channels.iter().enumerate() {
+            let samples = samples.as_ref();
+            let (first, second) = access.channel_data_mut(channel)?;
+            if samples.len() != first.len() + second.len() {
+                return Err(Error::Other(format!(
+                    "{} bytes sent for channel {channel}, but {} samples take {} bytes.",
+                    samples.len(),
+                    block.count,
+                    first.len() + second.len()
+                ))
+                .with_context(error_context));
+            }
+            let (samples_1, samples_2) = samples.split_at(first.len());
+            first.copy_from_slice(samples_1);
+            second.copy_from_slice(samples_2);
+        }
+        access.commit()
+    }
+
+    fn error_context(&self, index: SampleIndex) -> ErrorContext {
+        ErrorContext::new("send samples")
+            .flow_id(self.writer.flow_id())
+            .index(index)
+    }
+}
+
+impl<B: AsRef<[u8]> + Unpin> Sink<SamplesBlock<B>> for SamplesSink<B> {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_write_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, block: SamplesBlock<B>) -> Result<()> {
+        let this = self.get_mut();
+        if this.pending.is_some() {
+            // `poll_ready` has not been polled to completion.
+            return Err(Error::InvalidState.with_context(this.error_context(block.index)));
+        }
+        this.pending = Some(block);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_write_pending(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_write_pending(cx)
+    }
+}
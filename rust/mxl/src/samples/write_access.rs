@@ -5,7 +5,11 @@ use std::{marker::PhantomData, sync::Arc};
 
 use tracing::error;
 
-use crate::{Error, instance::InstanceContext};
+use crate::{
+    ChannelSamplesMut, Error,
+    error::{ErrorContext, ResultExt},
+    instance::InstanceContext,
+};
 
 /// RAII samples writing session
 ///
@@ -16,21 +20,26 @@ use crate::{Error, instance::InstanceContext};
 pub struct SamplesWriteAccess<'a> {
     context: Arc<InstanceContext>,
     writer: mxl_sys::mxlFlowWriter,
+    /// Context of the errors of `commit` and `cancel`.
+    error_context: ErrorContext,
     buffer_slice: mxl_sys::mxlMutableWrappedMultiBufferSlice,
     /// Serves as a flag to know whether to cancel the samples on drop.
     committed_or_canceled: bool,
-    phantom: PhantomData<&'a ()>,
+    /// Exclusive borrow of the writer, which only supports a single open access.
+    phantom: PhantomData<&'a mut ()>,
 }
 
 impl<'a> SamplesWriteAccess<'a> {
     pub(crate) fn new(
         context: Arc<InstanceContext>,
         writer: mxl_sys::mxlFlowWriter,
+        error_context: ErrorContext,
         buffer_slice: mxl_sys::mxlMutableWrappedMultiBufferSlice,
     ) -> Self {
         Self {
             context,
             writer,
+            error_context,
             buffer_slice,
             committed_or_canceled: false,
             phantom: PhantomData,
@@ -41,6 +50,7 @@ impl<'a> SamplesWriteAccess<'a> {
         self.committed_or_canceled = true;
 
         unsafe { Error::from_status(self.context.api.flow_writer_commit_samples(self.writer)) }
+            .context(|| self.error_context.clone())
     }
 
     /// Please note that the behavior of canceling samples writing is dependent on the behavior
@@ -51,6 +61,10 @@ impl<'a> SamplesWriteAccess<'a> {
         self.committed_or_canceled = true;
 
         unsafe { Error::from_status(self.context.api.flow_writer_cancel_samples(self.writer)) }
+            .context(|| ErrorContext {
+                operation: "cancel samples",
+                ..self.error_context.clone()
+            })
     }
 
     pub fn channels(&self) -> usize {
@@ -58,12 +72,8 @@ impl<'a> SamplesWriteAccess<'a> {
     }
 
     /// Provides direct access to buffer of the given channel. The access is split into two slices
-    /// to cover cases when the ring is not continuous.
-    ///
-    /// Currently, we provide just raw bytes access. Probably we should provide some sample-based
-    /// access and some index-based access (where we hide the complexity of 2 slices) as well?
-    ///
-    /// Samples are f32?
+    /// to cover cases when the ring is not continuous. See `channel_samples_mut` for float32
+    /// access hiding the two slices.
     pub fn channel_data_mut(&mut self, channel: usize) -> crate::Result<(&mut [u8], &mut [u8])> {
         if channel >= self.buffer_slice.count {
             return Err(Error::InvalidArg);
@@ -81,6 +91,33 @@ impl<'a> SamplesWriteAccess<'a> {
             ))
         }
     }
+
+    /// Float32 samples of the given channel, across both fragments.
+    pub fn channel_samples_mut(&mut self, channel: usize) -> crate::Result<ChannelSamplesMut<'_>> {
+        let (first, second) = self.channel_data_mut(channel)?;
+        ChannelSamplesMut::new(first, second)
+    }
+
+    /// Splits the interleaved float32 samples of `interleaved` into all the channels. It must hold
+    /// exactly the samples of all the channels.
+    pub fn deinterleave_from(&mut self, interleaved: &[f32]) -> crate::Result<()> {
+        let count = self.channels();
+        let length = match count {
+            0 => 0,
+            _ => self.channel_samples_mut(0)?.len(),
+        };
+        if interleaved.len() != length * count {
+            return Err(Error::SampleCountMismatch {
+                expected: length * count,
+                actual: interleaved.len(),
+            });
+        }
+        for channel in 0..count {
+            self.channel_samples_mut(channel)?
+                .write_all(interleaved.iter().skip(channel).step_by(count).copied());
+        }
+        Ok(())
+    }
 }
 
 impl<'a> Drop for SamplesWriteAccess<'a> {
@@ -3,12 +3,18 @@
 
 use std::sync::Arc;
 
-use crate::{Error, Result, SamplesWriteAccess, instance::InstanceContext};
+use crate::{
+    Error, FlowConfigInfo, PacedWriter, Result, SampleIndex, SamplesSink, SamplesWriteAccess,
+    error::{ErrorContext, ResultExt},
+    instance::InstanceContext,
+};
 
 /// MXL Flow Writer for continuous flows (samples-based data like audio)
 pub struct SamplesWriter {
     context: Arc<InstanceContext>,
     writer: mxl_sys::mxlFlowWriter,
+    id: uuid::Uuid,
+    config: FlowConfigInfo,
 }
 
 /// The MXL readers and writers are not thread-safe, so we do not implement `Sync` for them, but
@@ -16,32 +22,110 @@ pub struct SamplesWriter {
 unsafe impl Send for SamplesWriter {}
 
 impl SamplesWriter {
-    pub(crate) fn new(context: Arc<InstanceContext>, writer: mxl_sys::mxlFlowWriter) -> Self {
-        Self { context, writer }
+    pub(crate) fn new(
+        context: Arc<InstanceContext>,
+        writer: mxl_sys::mxlFlowWriter,
+        id: uuid::Uuid,
+        config: FlowConfigInfo,
+    ) -> Self {
+        Self {
+            context,
+            writer,
+            id,
+            config,
+        }
+    }
+
+    pub fn flow_id(&self) -> uuid::Uuid {
+        self.id
+    }
+
+    /// The config of the flow, captured when the writer was created.
+    pub fn config_info(&self) -> &FlowConfigInfo {
+        &self.config
     }
 
     pub fn destroy(mut self) -> Result<()> {
         self.destroy_inner()
     }
 
-    pub fn open_samples<'a>(&'a self, index: u64, count: usize) -> Result<SamplesWriteAccess<'a>> {
+    /// Opens the `count` samples up to `index` excluded for writing. The MXL writers only track a
+    /// single open batch of samples, so the access borrows the writer exclusively until it is
+    /// committed, canceled or dropped, and opening other samples in the meantime does not compile:
+    ///
+    /// ```compile_fail
+    /// # fn open_two(writer: &mut mxl::SamplesWriter) -> mxl::Result<()> {
+    /// let first = writer.open_samples(mxl::SampleIndex::new(64), 64)?;
+    /// let second = writer.open_samples(mxl::SampleIndex::new(128), 64)?;
+    /// first.cancel()?;
+    /// second.cancel()
+    /// # }
+    /// ```
+    pub fn open_samples(
+        &mut self,
+        index: SampleIndex,
+        count: usize,
+    ) -> Result<SamplesWriteAccess<'_>> {
+        self.open(index, count)
+    }
+
+    /// Same as `open_samples` through a shared reference, for callers that cannot borrow the
+    /// writer exclusively yet.
+    ///
+    /// # Safety
+    ///
+    /// No other samples or access of the writer may be open until the returned access is
+    /// committed, canceled or dropped.
+    #[deprecated(note = "use `open_samples`, which borrows the writer exclusively")]
+    pub unsafe fn open_samples_unchecked(
+        &self,
+        index: SampleIndex,
+        count: usize,
+    ) -> Result<SamplesWriteAccess<'_>> {
+        self.open(index, count)
+    }
+
+    fn open(&self, index: SampleIndex, count: usize) -> Result<SamplesWriteAccess<'_>> {
         let mut buffer_slice: mxl_sys::mxlMutableWrappedMultiBufferSlice =
             unsafe { std::mem::zeroed() };
         unsafe {
             Error::from_status(self.context.api.flow_writer_open_samples(
                 self.writer,
-                index,
+                index.value(),
                 count,
                 &mut buffer_slice,
-            ))?;
+            ))
+            .context(|| {
+                ErrorContext::new("open samples")
+                    .flow_id(self.id)
+                    .index(index)
+            })?;
         }
         Ok(SamplesWriteAccess::new(
             self.context.clone(),
             self.writer,
+            ErrorContext::new("commit samples")
+                .flow_id(self.id)
+                .index(index),
             buffer_slice,
         ))
     }
 
+    /// Turns the writer into an asynchronous sink, see `SamplesSink`.
+    pub fn into_sink<B: AsRef<[u8]>>(self) -> SamplesSink<B> {
+        SamplesSink::new(self)
+    }
+
+    /// Turns the writer into a writer paced to the sample rate of the flow, writing batches of
+    /// `batch_size` samples, see `PacedWriter`.
+    pub fn into_paced(self, batch_size: u64) -> Result<PacedWriter<SamplesWriter>> {
+        PacedWriter::for_samples(self, batch_size)
+    }
+
+    pub(crate) fn context(&self) -> &Arc<InstanceContext> {
+        &self.context
+    }
+
     fn destroy_inner(&mut self) -> Result<()> {
         if self.writer.is_null() {
             return Err(Error::InvalidArg);
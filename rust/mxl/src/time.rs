@@ -0,0 +1,347 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Timestamps and indexes of grains and samples.
+//!
+//! MXL times are TAI nanoseconds since the SMPTE ST 2059 epoch (1970-01-01 00:00:00 TAI). Grain
+//! and sample indexes count grains and samples at the rate of the flow since that same epoch, see
+//! `docs/timing.md`.
+
+use std::{
+    fmt::Display,
+    marker::PhantomData,
+    ops::{Add, AddAssign, Sub, SubAssign},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{Error, Rational, Result};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+/// TAI - UTC in seconds, effective from the given UTC time in seconds since the Unix epoch.
+///
+/// No leap second has been announced after the one of 2016-12-31, the table must be extended if
+/// the IERS ever announces another one.
+const LEAP_SECONDS: [(u64, u64); 28] = [
+    (63_072_000, 10),    // 1972-01-01
+    (78_796_800, 11),    // 1972-07-01
+    (94_694_400, 12),    // 1973-01-01
+    (126_230_400, 13),   // 1974-01-01
+    (157_766_400, 14),   // 1975-01-01
+    (189_302_400, 15),   // 1976-01-01
+    (220_924_800, 16),   // 1977-01-01
+    (252_460_800, 17),   // 1978-01-01
+    (283_996_800, 18),   // 1979-01-01
+    (315_532_800, 19),   // 1980-01-01
+    (362_793_600, 20),   // 1981-07-01
+    (394_329_600, 21),   // 1982-07-01
+    (425_865_600, 22),   // 1983-07-01
+    (489_024_000, 23),   // 1985-07-01
+    (567_993_600, 24),   // 1988-01-01
+    (631_152_000, 25),   // 1990-01-01
+    (662_688_000, 26),   // 1991-01-01
+    (709_948_800, 27),   // 1992-07-01
+    (741_484_800, 28),   // 1993-07-01
+    (773_020_800, 29),   // 1994-07-01
+    (820_454_400, 30),   // 1996-01-01
+    (867_715_200, 31),   // 1997-07-01
+    (915_148_800, 32),   // 1999-01-01
+    (1_136_073_600, 33), // 2006-01-01
+    (1_230_768_000, 34), // 2009-01-01
+    (1_341_100_800, 35), // 2012-07-01
+    (1_435_708_800, 36), // 2015-07-01
+    (1_483_228_800, 37), // 2017-01-01
+];
+
+/// TAI - UTC before 1972, when UTC did not count whole leap seconds yet. The offset of 1972 is
+/// used, as most PTP implementations do.
+const INITIAL_TAI_UTC_OFFSET: u64 = LEAP_SECONDS[0].1;
+
+/// A point in time as used by MXL: TAI nanoseconds since the SMPTE ST 2059 epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    /// 1970-01-01 00:00:00 TAI.
+    pub const EPOCH: Timestamp = Timestamp(0);
+
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Self(nanos)
+    }
+
+    pub const fn as_nanos(&self) -> u64 {
+        self.0
+    }
+
+    /// Converts a UTC system time. Fails with `Error::TimestampOutOfRange` for times before the
+    /// Unix epoch or too far in the future to be counted in 64 bit nanoseconds.
+    pub fn from_system_time(time: SystemTime) -> Result<Self> {
+        let since_unix_epoch = time
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| Error::TimestampOutOfRange)?;
+        let offset = utc_offset(since_unix_epoch.as_secs());
+        u64::try_from(since_unix_epoch.as_nanos())
+            .ok()
+            .and_then(|nanos| nanos.checked_add(offset * NANOS_PER_SECOND))
+            .map(Self)
+            .ok_or(Error::TimestampOutOfRange)
+    }
+
+    /// Converts to a UTC system time. The times of an inserted leap second map to the first second
+    /// of the following day.
+    pub fn to_system_time(&self) -> SystemTime {
+        let tai = UNIX_EPOCH + Duration::from_nanos(self.0);
+        tai - self.tai_utc_offset()
+    }
+
+    /// TAI - UTC at this point in time.
+    pub fn tai_utc_offset(&self) -> Duration {
+        Duration::from_secs(tai_offset(self.0 / NANOS_PER_SECOND))
+    }
+
+    /// Time elapsed since the epoch.
+    pub fn since_epoch(&self) -> Duration {
+        Duration::from_nanos(self.0)
+    }
+
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        let nanos = u64::try_from(duration.as_nanos()).ok()?;
+        self.0.checked_add(nanos).map(Self)
+    }
+
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        let nanos = u64::try_from(duration.as_nanos()).ok()?;
+        self.0.checked_sub(nanos).map(Self)
+    }
+
+    /// Time elapsed from `earlier` to this timestamp, zero if `earlier` is later.
+    pub fn saturating_duration_since(&self, earlier: Timestamp) -> Duration {
+        Duration::from_nanos(self.0.saturating_sub(earlier.0))
+    }
+}
+
+impl Display for Timestamp {
+    /// Seconds and nanoseconds since the epoch, as in `1700000037:000000000`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{:09}",
+            self.0 / NANOS_PER_SECOND,
+            self.0 % NANOS_PER_SECOND
+        )
+    }
+}
+
+/// Saturates at the bounds of the timestamps instead of panicking.
+impl Add<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    fn add(self, duration: Duration) -> Timestamp {
+        self.checked_add(duration).unwrap_or(Timestamp(u64::MAX))
+    }
+}
+
+impl AddAssign<Duration> for Timestamp {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+/// Saturates at the epoch instead of panicking.
+impl Sub<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    fn sub(self, duration: Duration) -> Timestamp {
+        self.checked_sub(duration).unwrap_or(Timestamp::EPOCH)
+    }
+}
+
+/// Saturates to zero if `earlier` is later, as `std::time::Instant` does.
+impl Sub<Timestamp> for Timestamp {
+    type Output = Duration;
+
+    fn sub(self, earlier: Timestamp) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+}
+
+impl TryFrom<SystemTime> for Timestamp {
+    type Error = Error;
+
+    fn try_from(value: SystemTime) -> Result<Self> {
+        Self::from_system_time(value)
+    }
+}
+
+impl From<Timestamp> for SystemTime {
+    fn from(value: Timestamp) -> Self {
+        value.to_system_time()
+    }
+}
+
+/// TAI - UTC in seconds, for a UTC time in seconds since the Unix epoch.
+fn utc_offset(utc_seconds: u64) -> u64 {
+    LEAP_SECONDS
+        .iter()
+        .rev()
+        .find(|(start, _)| utc_seconds >= *start)
+        .map_or(INITIAL_TAI_UTC_OFFSET, |(_, offset)| *offset)
+}
+
+/// TAI - UTC in seconds, for a TAI time in seconds since the epoch.
+fn tai_offset(tai_seconds: u64) -> u64 {
+    LEAP_SECONDS
+        .iter()
+        .rev()
+        .find(|(start, offset)| tai_seconds >= start + offset)
+        .map_or(INITIAL_TAI_UTC_OFFSET, |(_, offset)| *offset)
+}
+
+/// Index of a grain or of a sample, see `GrainIndex` and `SampleIndex`.
+///
+/// Lets the timing functions take the kind of index counted by the rate they are given, see
+/// `EditRate`.
+pub trait MediaIndex: Copy + From<u64> + Into<u64> {}
+
+macro_rules! media_index {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        pub struct $name(u64);
+
+        impl $name {
+            pub const fn new(value: u64) -> Self {
+                Self(value)
+            }
+
+            pub const fn value(&self) -> u64 {
+                self.0
+            }
+
+            pub fn checked_add(&self, count: u64) -> Option<Self> {
+                self.0.checked_add(count).map(Self)
+            }
+
+            pub fn checked_sub(&self, count: u64) -> Option<Self> {
+                self.0.checked_sub(count).map(Self)
+            }
+
+            /// Number of indexes from `earlier` to this one, zero if `earlier` is later.
+            pub fn saturating_distance_since(&self, earlier: $name) -> u64 {
+                self.0.saturating_sub(earlier.0)
+            }
+        }
+
+        impl MediaIndex for $name {}
+
+        impl From<u64> for $name {
+            fn from(value: u64) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for u64 {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        /// Saturates instead of panicking, see `checked_add` to detect overflows.
+        impl Add<u64> for $name {
+            type Output = $name;
+
+            fn add(self, count: u64) -> $name {
+                Self(self.0.saturating_add(count))
+            }
+        }
+
+        impl AddAssign<u64> for $name {
+            fn add_assign(&mut self, count: u64) {
+                *self = *self + count;
+            }
+        }
+
+        /// Saturates at zero instead of panicking, see `checked_sub` to detect underflows.
+        impl Sub<u64> for $name {
+            type Output = $name;
+
+            fn sub(self, count: u64) -> $name {
+                Self(self.0.saturating_sub(count))
+            }
+        }
+
+        impl SubAssign<u64> for $name {
+            fn sub_assign(&mut self, count: u64) {
+                *self = *self - count;
+            }
+        }
+    };
+}
+
+media_index!(
+    /// Index of a grain of a discrete flow: number of grains at the grain rate since the epoch.
+    GrainIndex
+);
+
+media_index!(
+    /// Index of a sample of a continuous flow: number of samples at the sample rate since the
+    /// epoch.
+    SampleIndex
+);
+
+/// A grain or sample rate, tagged with the kind of index it counts: `GrainRate` for the grains of
+/// discrete flows and `SampleRate` for the samples of continuous flows.
+///
+/// The timing functions infer the index they take and return from the rate, so that a grain rate
+/// cannot be used to compute sample indexes or the other way around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EditRate<I> {
+    rate: Rational,
+    index: PhantomData<fn() -> I>,
+}
+
+/// Rate of the grains of a discrete flow.
+pub type GrainRate = EditRate<GrainIndex>;
+
+/// Rate of the samples of a continuous flow.
+pub type SampleRate = EditRate<SampleIndex>;
+
+impl<I: MediaIndex> EditRate<I> {
+    /// Fails with `Error::InvalidRate` if `rate` is not positive.
+    pub fn new(rate: Rational) -> Result<Self> {
+        Ok(Self {
+            rate: rate.validate_rate()?,
+            index: PhantomData,
+        })
+    }
+
+    pub fn as_rational(&self) -> Rational {
+        self.rate
+    }
+
+    pub fn numerator(&self) -> i64 {
+        self.rate.numerator()
+    }
+
+    pub fn denominator(&self) -> i64 {
+        self.rate.denominator()
+    }
+}
+
+impl<I> Display for EditRate<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.rate.fmt(f)
+    }
+}
+
+impl<I> From<EditRate<I>> for Rational {
+    fn from(value: EditRate<I>) -> Self {
+        value.rate
+    }
+}
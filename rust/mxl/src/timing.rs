@@ -0,0 +1,300 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The MXL timing model of `docs/timing.md`, without the MXL library.
+//!
+//! The conversions give the same results as `mxlTimestampToIndex`, `mxlIndexToTimestamp` and
+//! `mxlGetNsUntilIndex` for all the valid rates, and the ring buffers locate grains and samples
+//! the same way as the MXL flow readers. The rates are `GrainRate` or `SampleRate`, which only hold
+//! positive rates and give the kind of index the conversions take and return. Results that do not
+//! fit in 64 bits are refused with `Error::TimestampOutOfRange`.
+
+use std::{ops::Range, time::Duration};
+
+use crate::{
+    EditRate, Error, GrainIndex, GrainRate, MediaIndex, Result, SampleIndex, SampleRate, Timestamp,
+};
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+/// Size the MXL library rounds the channel buffers of continuous flows up to, in bytes.
+const PAGE_SIZE: u64 = 4096;
+
+/// Index of the grain or sample at `timestamp`, rounded to the nearest one.
+pub fn timestamp_to_index<I: MediaIndex>(timestamp: Timestamp, rate: &EditRate<I>) -> Result<I> {
+    let (numerator, denominator) = wide_rate(rate);
+    i128::from(timestamp.as_nanos())
+        .checked_mul(numerator)
+        .and_then(|scaled| scaled.checked_add(NANOS_PER_SECOND / 2 * denominator))
+        .map(|scaled| scaled / (NANOS_PER_SECOND * denominator))
+        .and_then(|index| u64::try_from(index).ok())
+        .map(I::from)
+        .ok_or(Error::TimestampOutOfRange)
+}
+
+/// Timestamp of the beginning of the grain or sample at `index`, rounded to the nearest
+/// nanosecond.
+pub fn index_to_timestamp<I: MediaIndex>(index: I, rate: &EditRate<I>) -> Result<Timestamp> {
+    let (numerator, denominator) = wide_rate(rate);
+    let index: u64 = index.into();
+    i128::from(index)
+        .checked_mul(denominator * NANOS_PER_SECOND)
+        .and_then(|scaled| scaled.checked_add(numerator / 2))
+        .map(|scaled| scaled / numerator)
+        .and_then(|nanos| u64::try_from(nanos).ok())
+        .map(Timestamp::from_nanos)
+        .ok_or(Error::TimestampOutOfRange)
+}
+
+/// Time left at `now` until the beginning of the grain or sample at `index`, zero if it is already
+/// due.
+pub fn duration_until_index<I: MediaIndex>(
+    index: I,
+    rate: &EditRate<I>,
+    now: Timestamp,
+) -> Result<Duration> {
+    let target = index_to_timestamp(index, rate)?;
+    // `mxlGetNsUntilIndex` does not wait when the clock is unavailable.
+    if now == Timestamp::EPOCH {
+        return Ok(Duration::ZERO);
+    }
+    Ok(target.saturating_duration_since(now))
+}
+
+/// Duration of the grain or sample at `index`. Grain durations vary by a nanosecond at rates such
+/// as 30000/1001, since timestamps are rounded to the nanosecond.
+pub fn index_duration<I: MediaIndex>(index: I, rate: &EditRate<I>) -> Result<Duration> {
+    let next = Into::<u64>::into(index)
+        .checked_add(1)
+        .ok_or(Error::TimestampOutOfRange)?;
+    let start = index_to_timestamp(index, rate)?;
+    let end = index_to_timestamp(I::from(next), rate)?;
+    Ok(end.saturating_duration_since(start))
+}
+
+fn wide_rate<I: MediaIndex>(rate: &EditRate<I>) -> (i128, i128) {
+    (i128::from(rate.numerator()), i128::from(rate.denominator()))
+}
+
+/// Maps the grains or samples at one rate to the ones at another rate that begin during them,
+/// e.g. a 30000/1001 video grain to the 1601 or 1602 samples at 48 kHz it lasts.
+///
+/// The mapping is computed on the exact rates rather than on timestamps rounded to the
+/// nanosecond, so that the ranges of consecutive indexes are contiguous and never drift: the
+/// samples of 5 grains at 30000/1001 are always 8008 samples at 48 kHz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateMapping<I, J> {
+    from: EditRate<I>,
+    to: EditRate<J>,
+}
+
+impl<I: MediaIndex, J: MediaIndex> RateMapping<I, J> {
+    /// Maps indexes at `from` to indexes at `to`.
+    pub fn new(from: EditRate<I>, to: EditRate<J>) -> Self {
+        Self { from, to }
+    }
+
+    pub fn source_rate(&self) -> EditRate<I> {
+        self.from
+    }
+
+    pub fn target_rate(&self) -> EditRate<J> {
+        self.to
+    }
+
+    /// The mapping the other way around.
+    pub fn inverse(&self) -> RateMapping<J, I> {
+        RateMapping {
+            from: self.to,
+            to: self.from,
+        }
+    }
+
+    /// The grains or samples at `to` beginning during the one at `index` at `from`.
+    pub fn map(&self, index: I) -> Result<Range<J>> {
+        let index: u64 = index.into();
+        let next = index.checked_add(1).ok_or(Error::TimestampOutOfRange)?;
+        self.map_range(I::from(index)..I::from(next))
+    }
+
+    /// The grains or samples at `to` beginning during the ones in `range` at `from`.
+    pub fn map_range(&self, range: Range<I>) -> Result<Range<J>> {
+        Ok(J::from(self.first_from(range.start.into())?)
+            ..J::from(self.first_from(range.end.into())?))
+    }
+
+    /// Number of grains or samples at `to` beginning during the one at `index` at `from`.
+    pub fn count(&self, index: I) -> Result<u64> {
+        let index: u64 = index.into();
+        let next = index.checked_add(1).ok_or(Error::TimestampOutOfRange)?;
+        Ok(self.first_from(next)? - self.first_from(index)?)
+    }
+
+    /// The grain or sample at `to` in progress when the one at `index` at `from` begins.
+    pub fn containing(&self, index: I) -> Result<J> {
+        self.rescale(index.into(), false).map(J::from)
+    }
+
+    /// The first grain or sample at `to` that does not begin before the one at `index` at `from`.
+    fn first_from(&self, index: u64) -> Result<u64> {
+        self.rescale(index, true)
+    }
+
+    /// `index * to / from`, rounded up or down.
+    fn rescale(&self, index: u64, round_up: bool) -> Result<u64> {
+        let (from_numerator, from_denominator) = wide_rate(&self.from);
+        let (to_numerator, to_denominator) = wide_rate(&self.to);
+        to_denominator
+            .checked_mul(from_numerator)
+            .zip(
+                i128::from(index)
+                    .checked_mul(from_denominator)
+                    .and_then(|scaled| scaled.checked_mul(to_numerator)),
+            )
+            .and_then(|(divisor, scaled)| {
+                let rounding = if round_up { divisor - 1 } else { 0 };
+                scaled.checked_add(rounding).map(|scaled| scaled / divisor)
+            })
+            .and_then(|rescaled| u64::try_from(rescaled).ok())
+            .ok_or(Error::TimestampOutOfRange)
+    }
+}
+
+/// Number of whole grains or samples at `rate` in `history_duration`, rounded down.
+fn history_length<I: MediaIndex>(history_duration: Duration, rate: &EditRate<I>) -> Result<u64> {
+    let (numerator, denominator) = wide_rate(rate);
+    let length = history_duration.as_nanos() as i128 * numerator / (NANOS_PER_SECOND * denominator);
+    u64::try_from(length).map_err(|_| Error::TimestampOutOfRange)
+}
+
+/// The ring buffer of the grains of a discrete flow. The grain at `index` is stored in the slot
+/// `index % grain_count`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrainRing {
+    grain_count: u64,
+}
+
+impl GrainRing {
+    /// Fails with `Error::InvalidOptions` if the ring buffer holds no grain.
+    pub fn new(grain_count: u64) -> Result<Self> {
+        if grain_count == 0 {
+            return Err(Error::InvalidOptions(
+                "the ring buffer must hold at least one grain".to_string(),
+            ));
+        }
+        Ok(Self { grain_count })
+    }
+
+    /// The ring buffer the MXL library allocates for a flow at `grain_rate` when configured with
+    /// `history_duration`.
+    pub fn for_history(history_duration: Duration, grain_rate: &GrainRate) -> Result<Self> {
+        Self::new(history_length(history_duration, grain_rate)?)
+    }
+
+    pub fn grain_count(&self) -> u64 {
+        self.grain_count
+    }
+
+    /// Slot of the ring buffer holding the grain at `index`.
+    pub fn slot(&self, index: GrainIndex) -> u64 {
+        index.value() % self.grain_count
+    }
+
+    /// Oldest grain still in the ring buffer when the writer is at `head`.
+    pub fn oldest(&self, head: GrainIndex) -> GrainIndex {
+        GrainIndex::new(head.value().saturating_sub(self.grain_count - 1))
+    }
+
+    /// Slot of the grain at `index` when the writer is at `head`. Fails as the MXL flow readers do,
+    /// with `Error::OutOfRangeTooEarly` for grains after the head and `Error::OutOfRangeTooLate`
+    /// for grains that have been overwritten.
+    pub fn locate(&self, head: GrainIndex, index: GrainIndex) -> Result<u64> {
+        if index > head {
+            Err(Error::OutOfRangeTooEarly)
+        } else if index < self.oldest(head) {
+            Err(Error::OutOfRangeTooLate)
+        } else {
+            Ok(self.slot(index))
+        }
+    }
+}
+
+/// The ring buffers of the channels of a continuous flow. The sample at `index` is stored at the
+/// offset `index % buffer_length` of every channel buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleRing {
+    buffer_length: u64,
+}
+
+impl SampleRing {
+    /// Fails with `Error::InvalidOptions` if the buffers hold no sample.
+    pub fn new(buffer_length: u64) -> Result<Self> {
+        if buffer_length == 0 {
+            return Err(Error::InvalidOptions(
+                "the channel buffers must hold at least one sample".to_string(),
+            ));
+        }
+        Ok(Self { buffer_length })
+    }
+
+    /// The buffers the MXL library allocates for a flow at `sample_rate` when configured with
+    /// `history_duration`, rounded up to whole pages of samples of `sample_word_size` bytes.
+    pub fn for_history(
+        history_duration: Duration,
+        sample_rate: &SampleRate,
+        sample_word_size: usize,
+    ) -> Result<Self> {
+        let samples_per_page = u64::try_from(sample_word_size)
+            .ok()
+            .filter(|size| (1..=PAGE_SIZE).contains(size))
+            .map(|size| PAGE_SIZE / size)
+            .ok_or_else(|| {
+                Error::InvalidOptions(format!("invalid sample word size {sample_word_size}"))
+            })?;
+        let length = history_length(history_duration, sample_rate)?;
+        Self::new(length.div_ceil(samples_per_page) * samples_per_page)
+    }
+
+    pub fn buffer_length(&self) -> u64 {
+        self.buffer_length
+    }
+
+    /// Offset of the sample at `index` in the channel buffers.
+    pub fn offset(&self, index: SampleIndex) -> u64 {
+        index.value() % self.buffer_length
+    }
+
+    /// Oldest sample readers may access when the writer is at `head`. Only half of the buffer is
+    /// readable, the other half being the one the writer is filling.
+    pub fn oldest(&self, head: SampleIndex) -> SampleIndex {
+        SampleIndex::new(head.value().saturating_sub(self.buffer_length / 2))
+    }
+
+    /// Offsets in the channel buffers of the `count` samples read at `index`, the samples from
+    /// `index - count` up to `index` excluded, in two fragments when they wrap around the end of
+    /// the buffers. Fails as the MXL flow readers do, with
+    /// `Error::OutOfRangeTooEarly` for samples after the head and `Error::OutOfRangeTooLate` for
+    /// samples that are no longer readable.
+    pub fn locate(
+        &self,
+        head: SampleIndex,
+        index: SampleIndex,
+        count: u64,
+    ) -> Result<(Range<u64>, Range<u64>)> {
+        if index > head {
+            return Err(Error::OutOfRangeTooEarly);
+        }
+        let oldest = self.oldest(head);
+        if index < oldest || index.value() - oldest.value() < count {
+            return Err(Error::OutOfRangeTooLate);
+        }
+        let start = (index.value() + self.buffer_length - count) % self.buffer_length;
+        let end = self.offset(index);
+        let first_length = if start < end || count == 0 {
+            count
+        } else {
+            self.buffer_length - start
+        };
+        Ok((start..start + first_length, 0..count - first_length))
+    }
+}
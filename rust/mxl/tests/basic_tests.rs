@@ -7,101 +7,28 @@
 /// change in the future. For now, feel free to just edit the path to your library.
 use std::time::Duration;
 
-use mxl::{MxlInstance, OwnedGrainData, OwnedSamplesData, config::get_mxl_so_path};
-use tracing::info;
-
-static LOG_ONCE: std::sync::Once = std::sync::Once::new();
-
-struct TestDomainGuard {
-    dir: std::path::PathBuf,
-}
-
-impl TestDomainGuard {
-    fn new(test: &str) -> Self {
-        let dir = std::path::PathBuf::from(format!(
-            "/dev/shm/mxl_rust_unit_tests_domain_{}_{}",
-            test,
-            uuid::Uuid::new_v4()
-        ));
-        std::fs::create_dir_all(dir.as_path()).unwrap_or_else(|_| {
-            panic!(
-                "Failed to create test domain directory \"{}\".",
-                dir.display()
-            )
-        });
-        Self { dir }
-    }
-
-    fn domain(&self) -> String {
-        self.dir.to_string_lossy().to_string()
-    }
-}
-
-impl Drop for TestDomainGuard {
-    fn drop(&mut self) {
-        std::fs::remove_dir_all(self.dir.as_path()).unwrap_or_else(|_| {
-            panic!(
-                "Failed to remove test domain directory \"{}\".",
-                self.dir.display()
-            )
-        });
-    }
-}
-
-fn setup_test(test: &str) -> (MxlInstance, TestDomainGuard) {
-    // Set up the logging to use the RUST_LOG environment variable and if not present, print INFO
-    // and higher.
-    LOG_ONCE.call_once(|| {
-        tracing_subscriber::fmt()
-            .with_env_filter(
-                tracing_subscriber::EnvFilter::builder()
-                    .with_default_directive(tracing::level_filters::LevelFilter::INFO.into())
-                    .from_env_lossy(),
-            )
-            .init();
-    });
-
-    let mxl_api = mxl::load_api(get_mxl_so_path()).unwrap();
-    let domain_guard = TestDomainGuard::new(test);
-    (
-        MxlInstance::new(mxl_api, domain_guard.domain().as_str(), "").unwrap(),
-        domain_guard,
-    )
-}
-
-fn read_flow_def<P: AsRef<std::path::Path>>(path: P) -> String {
-    let flow_config_file = mxl::config::get_mxl_repo_root().join(path);
+mod common;
 
-    std::fs::read_to_string(flow_config_file.as_path())
-        .map_err(|error| {
-            mxl::Error::Other(format!(
-                "Error while reading flow definition from \"{}\": {}",
-                flow_config_file.display(),
-                error
-            ))
-        })
-        .unwrap()
-}
-
-fn prepare_flow_config_info<P: AsRef<std::path::Path>>(
-    mxl_instance: &MxlInstance,
-    path: P,
-) -> mxl::FlowConfigInfo {
-    let flow_def = read_flow_def(path);
-    mxl_instance.create_flow(flow_def.as_str(), None).unwrap()
-}
+use common::{TestDomainGuard, prepare_flow_config_info, read_flow_def, setup_test};
+use mxl::{
+    FlowDef, FlowOptions, FlowStatus, GrainFlags, GrainIndex, InstanceOptions, MxlInstance,
+    OwnedGrainData, OwnedSamplesData, config::get_mxl_so_path,
+};
+use tracing::info;
 
 #[test]
 fn basic_mxl_grain_writing_reading() {
     let (mxl_instance, _domain_guard) = setup_test("grains");
     let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
     let flow_id = flow_config_info.common().id().to_string();
-    let flow_writer = mxl_instance.create_flow_writer(flow_id.as_str()).unwrap();
-    let grain_writer = flow_writer.to_grain_writer().unwrap();
+    let flow_writer = mxl_instance
+        .create_flow_writer_with_config(&flow_config_info)
+        .unwrap();
+    let mut grain_writer = flow_writer.to_grain_writer().unwrap();
     let flow_reader = mxl_instance.create_flow_reader(flow_id.as_str()).unwrap();
     let grain_reader = flow_reader.to_grain_reader().unwrap();
     let rate = flow_config_info.common().grain_rate().unwrap();
-    let current_index = mxl_instance.get_current_index(&rate);
+    let current_index = mxl_instance.get_current_index(&rate).unwrap();
     let grain_write_access = grain_writer.open_grain(current_index).unwrap();
     let total_slices = grain_write_access.total_slices();
     grain_write_access.commit(total_slices).unwrap();
@@ -122,11 +49,11 @@ fn basic_mxl_samples_writing_reading() {
     let flow_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/audio_flow.json");
     let flow_id = flow_info.common().id().to_string();
     let flow_writer = mxl_instance.create_flow_writer(flow_id.as_str()).unwrap();
-    let samples_writer = flow_writer.to_samples_writer().unwrap();
+    let mut samples_writer = flow_writer.to_samples_writer().unwrap();
     let flow_reader = mxl_instance.create_flow_reader(flow_id.as_str()).unwrap();
     let samples_reader = flow_reader.to_samples_reader().unwrap();
     let rate = flow_info.common().sample_rate().unwrap();
-    let current_index = mxl_instance.get_current_index(&rate);
+    let current_index = mxl_instance.get_current_index(&rate).unwrap();
     let samples_write_access = samples_writer.open_samples(current_index, 42).unwrap();
     samples_write_access.commit().unwrap();
     let samples_data = samples_reader
@@ -155,3 +82,1670 @@ fn get_flow_def() {
     mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
     mxl_instance.destroy().unwrap();
 }
+
+#[test]
+fn grain_slices_reading() {
+    let (mxl_instance, _domain_guard) = setup_test("grain_slices");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let slice_size = flow_config_info.discrete().unwrap().sliceSizes[0] as usize;
+    let mut grain_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    let grain_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let current_index = mxl_instance.get_current_index(&rate).unwrap();
+
+    let grain_write_access = grain_writer.open_grain(current_index).unwrap();
+    let total_slices = grain_write_access.total_slices();
+    let half = total_slices / 2;
+    grain_write_access.commit(half).unwrap();
+
+    let grain_data = grain_reader
+        .get_grain_slice(current_index, half, Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(grain_data.valid_slices, half);
+    assert_eq!(grain_data.total_slices, total_slices);
+    assert_eq!(grain_data.payload.len(), half as usize * slice_size);
+    assert!(!grain_data.is_complete());
+    let error = grain_reader
+        .get_grain_slice_non_blocking(current_index, total_slices)
+        .err()
+        .unwrap();
+    assert!(matches!(error.root(), mxl::Error::OutOfRangeTooEarly));
+
+    let mut slices = grain_reader.slices(current_index, Duration::from_secs(5));
+    let first = slices.next().unwrap().unwrap();
+    assert_eq!(first.slices, 0..half);
+    assert_eq!(first.payload.len(), half as usize * slice_size);
+
+    grain_writer
+        .open_grain(current_index)
+        .unwrap()
+        .commit(total_slices)
+        .unwrap();
+    let second = slices.next().unwrap().unwrap();
+    assert_eq!(second.slices, half..total_slices);
+    assert!(slices.next().is_none());
+
+    let grain_data = grain_reader
+        .get_grain_slice_non_blocking(current_index, total_slices)
+        .unwrap();
+    assert!(grain_data.is_complete());
+    assert_eq!(grain_data.payload.len(), grain_data.total_size);
+
+    grain_reader.destroy().unwrap();
+    grain_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn complete_grain_committed_in_two_halves() {
+    let (mxl_instance, _domain_guard) = setup_test("grain_halves");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let mut grain_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    let grain_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let current_index = mxl_instance.get_current_index(&rate).unwrap();
+
+    let writer_thread = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        let grain_write_access = grain_writer.open_grain(current_index).unwrap();
+        let total_slices = grain_write_access.total_slices();
+        grain_write_access.commit(total_slices / 2).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        grain_writer
+            .open_grain(current_index)
+            .unwrap()
+            .commit(total_slices)
+            .unwrap();
+        grain_writer
+    });
+
+    let grain_data = grain_reader
+        .get_complete_grain(current_index, Duration::from_secs(5))
+        .unwrap();
+    assert!(grain_data.is_complete());
+    assert_eq!(grain_data.payload.len(), grain_data.total_size);
+
+    // Only half of the next grain is ever committed, the read must give up at the deadline.
+    let mut grain_writer = writer_thread.join().unwrap();
+    let grain_write_access = grain_writer.open_grain(current_index + 1).unwrap();
+    let total_slices = grain_write_access.total_slices();
+    grain_write_access.commit(total_slices / 2).unwrap();
+    let start = std::time::Instant::now();
+    let error = grain_reader
+        .get_complete_grain(current_index + 1, Duration::from_millis(100))
+        .err()
+        .unwrap();
+    assert!(matches!(error.root(), mxl::Error::Timeout));
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    grain_reader.destroy().unwrap();
+    grain_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn invalid_grain_flags() {
+    let (mxl_instance, _domain_guard) = setup_test("grain_flags");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let grain_count = flow_config_info.discrete().unwrap().grainCount as u64;
+    let mut grain_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    let grain_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let current_index = mxl_instance.get_current_index(&rate).unwrap();
+
+    grain_writer
+        .open_grain(current_index)
+        .unwrap()
+        .commit_invalid()
+        .unwrap();
+    let grain_data = grain_reader
+        .get_complete_grain(current_index, Duration::from_secs(5))
+        .unwrap();
+    assert!(grain_data.is_invalid());
+    assert_eq!(grain_data.flags(), GrainFlags::INVALID);
+
+    // The next grain stored in the same ring buffer entry must not inherit the flag.
+    let next_index = current_index + grain_count;
+    let grain_write_access = grain_writer.open_grain(next_index).unwrap();
+    assert_eq!(grain_write_access.flags(), GrainFlags::empty());
+    let total_slices = grain_write_access.total_slices();
+    grain_write_access.commit(total_slices).unwrap();
+    let grain_data = grain_reader
+        .get_complete_grain(next_index, Duration::from_secs(5))
+        .unwrap();
+    assert!(!grain_data.is_invalid());
+
+    grain_reader.destroy().unwrap();
+    grain_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn progressive_slice_commits() {
+    let (mxl_instance, _domain_guard) = setup_test("slice_commits");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let mut grain_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    let grain_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let current_index = mxl_instance.get_current_index(&rate).unwrap();
+
+    // The test flow has no batch size hints, so every commit is published.
+    let mut grain_write_access = grain_writer.open_grain(current_index).unwrap();
+    let total_slices = grain_write_access.total_slices();
+    for upto in [1, total_slices / 4, total_slices / 2] {
+        grain_write_access.commit_slices(upto).unwrap();
+        assert_eq!(grain_write_access.committed_slices(), upto);
+        let grain_data = grain_reader
+            .get_grain_slice_non_blocking(current_index, upto)
+            .unwrap();
+        assert_eq!(grain_data.valid_slices, upto);
+    }
+    let error = grain_write_access.commit_slices(1).unwrap_err();
+    assert!(matches!(
+        error.root(),
+        mxl::Error::SlicesBelowCommitted { slices: 1, .. }
+    ));
+    assert!(grain_write_access.commit_slices(total_slices + 1).is_err());
+    grain_write_access.finish().unwrap();
+
+    let grain_data = grain_reader
+        .get_complete_grain(current_index, Duration::from_secs(5))
+        .unwrap();
+    assert!(grain_data.is_complete());
+
+    // Committing the whole grain cannot take back the slices already published either.
+    let next_index = current_index + 1;
+    let mut grain_write_access = grain_writer.open_grain(next_index).unwrap();
+    grain_write_access.commit_slices(2).unwrap();
+    let error = grain_write_access.commit(1).unwrap_err();
+    assert!(matches!(
+        error.root(),
+        mxl::Error::SlicesBelowCommitted {
+            slices: 1,
+            committed: 2
+        }
+    ));
+    let grain_data = grain_reader
+        .get_grain_slice_non_blocking(next_index, 2)
+        .unwrap();
+    assert_eq!(grain_data.valid_slices, 2);
+
+    grain_reader.destroy().unwrap();
+    grain_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn flow_activity_and_status() {
+    let (mxl_instance, _domain_guard) = setup_test("flow_status");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let stall_threshold = Duration::from_secs(10);
+
+    assert!(!mxl_instance.is_flow_active(flow_id.as_str()).unwrap());
+    assert_eq!(
+        mxl_instance
+            .get_flow_status(flow_id.as_str(), stall_threshold)
+            .unwrap(),
+        FlowStatus::WriterGone
+    );
+
+    let mut grain_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    assert!(mxl_instance.is_flow_active(flow_id.as_str()).unwrap());
+    assert_eq!(
+        mxl_instance
+            .get_flow_status(flow_id.as_str(), stall_threshold)
+            .unwrap(),
+        FlowStatus::Starting
+    );
+
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let current_index = mxl_instance.get_current_index(&rate).unwrap();
+    let grain_write_access = grain_writer.open_grain(current_index).unwrap();
+    let total_slices = grain_write_access.total_slices();
+    grain_write_access.commit(total_slices).unwrap();
+    assert!(
+        mxl_instance
+            .get_flow_status(flow_id.as_str(), stall_threshold)
+            .unwrap()
+            .is_healthy()
+    );
+
+    // The same writer is reported as stalled once it has been idle for longer than the threshold.
+    let reader = mxl_instance.create_flow_reader(flow_id.as_str()).unwrap();
+    let short_threshold = Duration::from_millis(1);
+    std::thread::sleep(Duration::from_millis(20));
+    let status = mxl_instance
+        .get_flow_status_with_reader(&reader, short_threshold)
+        .unwrap();
+    assert!(
+        matches!(status, FlowStatus::Stalled { idle } if idle > short_threshold),
+        "{status:?}"
+    );
+    drop(reader);
+
+    grain_writer.destroy().unwrap();
+    assert_eq!(
+        mxl_instance
+            .get_flow_status(flow_id.as_str(), stall_threshold)
+            .unwrap(),
+        FlowStatus::WriterGone
+    );
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn missing_optional_entry_points() {
+    let mxl_api = mxl::load_api(get_mxl_so_path()).unwrap();
+    let version = mxl_api.version();
+    let mxl_api = std::sync::Arc::new(
+        std::sync::Arc::into_inner(mxl_api)
+            .unwrap()
+            .without_optional_entry_points(),
+    );
+    let domain_guard = TestDomainGuard::new("missing_entry_points");
+    let mxl_instance = MxlInstance::new(mxl_api, domain_guard.domain().as_str(), "").unwrap();
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+
+    let error = mxl_instance.is_flow_active(flow_id.as_str()).unwrap_err();
+    assert!(matches!(
+        error.root(),
+        mxl::Error::Unsupported { function: "mxlIsFlowActive", version: actual }
+            if *actual == version
+    ));
+    assert_eq!(
+        error.root().to_string(),
+        format!("mxlIsFlowActive is unsupported by loaded libmxl {version}")
+    );
+
+    // The entry points of the first SDK releases keep working.
+    let mut grain_writer = mxl_instance.create_grain_writer(flow_id.as_str()).unwrap();
+    let grain_reader = mxl_instance.create_grain_reader(flow_id.as_str()).unwrap();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let index: GrainIndex = mxl_instance.get_current_index(&rate).unwrap();
+    let access = grain_writer.open_grain(index).unwrap();
+    let total_slices = access.total_slices();
+    access.commit(total_slices).unwrap();
+    let grain = grain_reader
+        .get_complete_grain(index, Duration::from_secs(5))
+        .unwrap();
+    assert!(grain.still_valid());
+
+    let error = grain_reader
+        .get_grain_slice(index, 1, Duration::from_secs(1))
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error.root(),
+        mxl::Error::Unsupported {
+            function: "mxlFlowReaderGetGrainSlice",
+            ..
+        }
+    ));
+    let error = grain_reader
+        .get_grain_slice_non_blocking(index, 1)
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error.root(),
+        mxl::Error::Unsupported {
+            function: "mxlFlowReaderGetGrainSliceNonBlocking",
+            ..
+        }
+    ));
+
+    grain_reader.destroy().unwrap();
+    grain_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn errors_carry_context() {
+    let (mxl_instance, _domain_guard) = setup_test("error_context");
+    let missing_flow_id = uuid::Uuid::new_v4();
+    let error = mxl_instance
+        .create_flow_reader(missing_flow_id.to_string().as_str())
+        .err()
+        .unwrap();
+    assert!(matches!(error.root(), mxl::Error::FlowNotFound));
+    assert_eq!(
+        error.context().unwrap().flow_id,
+        Some(missing_flow_id),
+        "{error}"
+    );
+    assert!(!error.is_transient());
+    assert!(matches!(
+        mxl_instance.create_flow_reader("not a flow id"),
+        Err(mxl::Error::InvalidFlowId(_))
+    ));
+
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let grain_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let future_index = mxl_instance.get_current_index::<GrainIndex>(&rate).unwrap() + 1000;
+    let error = grain_reader
+        .get_grain_non_blocking(future_index)
+        .err()
+        .unwrap();
+    assert!(matches!(error.root(), mxl::Error::OutOfRangeTooEarly));
+    assert!(error.is_retryable());
+    assert!(error.is_transient());
+    let context = error.context().unwrap();
+    assert_eq!(context.flow_id, Some(grain_reader.flow_id()));
+    assert_eq!(context.index, Some(future_index.value()));
+    assert!(error.to_string().contains(&flow_id), "{error}");
+
+    assert!(matches!(
+        mxl::Error::from_status(mxl_sys::MXL_ERR_PERMISSION_DENIED),
+        Err(mxl::Error::PermissionDenied)
+    ));
+    assert!(
+        mxl::Error::from_status(mxl_sys::MXL_ERR_NOT_READY)
+            .unwrap_err()
+            .is_retryable()
+    );
+
+    grain_reader.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn create_flows_from_presets() {
+    let (mxl_instance, _domain_guard) = setup_test("flow_presets");
+
+    let flow_def = FlowDef::v210_1080i50();
+    let config_info = mxl_instance
+        .create_flow_from_def(&flow_def, &FlowOptions::default())
+        .unwrap();
+    assert_eq!(config_info.common().id(), flow_def.id);
+    // Interlaced flows are stored as fields.
+    let grain_rate = config_info.common().grain_rate().unwrap();
+    assert_eq!(grain_rate.as_rational(), mxl::Rational::FPS_50);
+    assert_eq!(config_info.discrete().unwrap().sliceSizes[0], 5120);
+    mxl_instance
+        .destroy_flow(flow_def.id.to_string().as_str())
+        .unwrap();
+
+    let flow_def = FlowDef::float32_48khz(4);
+    let config_info = mxl_instance
+        .create_flow_from_def(&flow_def, &FlowOptions::default())
+        .unwrap();
+    assert_eq!(config_info.continuous().unwrap().channelCount, 4);
+    mxl_instance
+        .destroy_flow(flow_def.id.to_string().as_str())
+        .unwrap();
+
+    let invalid = FlowDef::float32_48khz(4).group_hint("");
+    let error = mxl_instance
+        .create_flow_from_def(&invalid, &FlowOptions::default())
+        .err()
+        .unwrap();
+    assert!(matches!(error.root(), mxl::Error::InvalidFlowDef(_)));
+
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn instance_and_flow_options() {
+    let mxl_api = mxl::load_api(get_mxl_so_path()).unwrap();
+    let domain_guard = TestDomainGuard::new("options");
+    let instance_options = InstanceOptions::new().history_duration(Duration::from_millis(200));
+    MxlInstance::update_domain_options(domain_guard.domain(), &instance_options).unwrap();
+    let mxl_instance = MxlInstance::new(mxl_api, domain_guard.domain().as_str(), "").unwrap();
+
+    let flow_def = FlowDef::float32_48khz(2);
+    let flow_options = FlowOptions::new()
+        .commit_batch_size(480)
+        .sync_batch_size(960);
+    let config_info = mxl_instance
+        .create_flow_from_def(&flow_def, &flow_options)
+        .unwrap();
+    assert_eq!(config_info.common().max_commit_batch_size_hint(), 480);
+    assert_eq!(config_info.common().max_sync_batch_size_hint(), 960);
+    // 200 ms at 48 kHz, rounded up to whole pages.
+    assert!(config_info.continuous().unwrap().bufferLength >= 9600);
+    mxl_instance
+        .destroy_flow(flow_def.id.to_string().as_str())
+        .unwrap();
+
+    // The commit batch size does not fit in the buffer, the flow is not kept.
+    let flow_def = FlowDef::float32_48khz(2);
+    let error = mxl_instance
+        .create_flow_from_def(&flow_def, &FlowOptions::new().commit_batch_size(20000))
+        .err()
+        .unwrap();
+    assert!(matches!(error.root(), mxl::Error::InvalidOptions(_)));
+    assert!(
+        mxl_instance
+            .create_flow_reader(flow_def.id.to_string().as_str())
+            .is_err()
+    );
+
+    let flow_def = FlowDef::v210_1080i50();
+    let config_info = mxl_instance
+        .create_flow_from_def(&flow_def, &FlowOptions::new().commit_batch_size(135))
+        .unwrap();
+    assert_eq!(config_info.common().max_commit_batch_size_hint(), 135);
+    mxl_instance
+        .destroy_flow(flow_def.id.to_string().as_str())
+        .unwrap();
+
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn timestamps_and_indexes() {
+    let (mxl_instance, _domain_guard) = setup_test("timing");
+    let rate = mxl::GrainRate::new(mxl::Rational::FPS_50).unwrap();
+
+    let before = mxl_instance.get_time();
+    let index = mxl_instance.get_current_index(&rate).unwrap();
+    let timestamp = mxl_instance.index_to_timestamp(index, &rate).unwrap();
+    assert!(timestamp <= mxl_instance.get_time());
+    assert!(before - timestamp <= Duration::from_millis(20));
+    assert_eq!(
+        mxl_instance.timestamp_to_index(timestamp, &rate).unwrap(),
+        index
+    );
+    assert_eq!(
+        mxl_instance
+            .index_to_timestamp(index + 50, &rate)
+            .unwrap()
+            .saturating_duration_since(timestamp),
+        Duration::from_secs(1)
+    );
+
+    let sample_rate = mxl::SampleRate::new(mxl::Rational::HZ_48000).unwrap();
+    let sample_index = mxl_instance
+        .timestamp_to_index(timestamp, &sample_rate)
+        .unwrap();
+    assert_eq!(sample_index.value(), index.value() * 960);
+
+    let error = mxl::GrainRate::new(mxl::Rational::from_integer(0))
+        .err()
+        .unwrap();
+    assert!(matches!(error, mxl::Error::InvalidRate { .. }));
+}
+
+#[test]
+fn timing_matches_the_library() {
+    let mxl_api = mxl::load_api(get_mxl_so_path()).unwrap();
+    let now = unsafe { mxl_api.get_time() };
+    let rates = [
+        mxl::Rational::FPS_23_98,
+        mxl::Rational::FPS_25,
+        mxl::Rational::FPS_29_97,
+        mxl::Rational::FPS_59_94,
+        mxl::Rational::HZ_48000,
+        mxl::Rational::new(7, 3).unwrap(),
+    ];
+    for rate in rates {
+        let raw_rate = mxl_sys::mxlRational::from(rate);
+        let rate = mxl::GrainRate::new(rate).unwrap();
+        for timestamp in [0, 1, 16_683_333, 16_683_334, now, now + 999_999_999] {
+            let index =
+                mxl::timing::timestamp_to_index(mxl::Timestamp::from_nanos(timestamp), &rate)
+                    .unwrap();
+            let expected = unsafe { mxl_api.timestamp_to_index(&raw_rate, timestamp) };
+            assert_eq!(index.value(), expected, "{timestamp} at {rate}");
+
+            for index in [index, index + 1, index + 1001] {
+                let timestamp = mxl::timing::index_to_timestamp(index, &rate).unwrap();
+                let expected = unsafe { mxl_api.index_to_timestamp(&raw_rate, index.value()) };
+                assert_eq!(timestamp.as_nanos(), expected, "{index} at {rate}");
+            }
+        }
+    }
+
+    // The duration is computed against the clock, it can only get shorter.
+    let rate = mxl::GrainRate::new(mxl::Rational::FPS_50).unwrap();
+    let index =
+        mxl::timing::timestamp_to_index(mxl::Timestamp::from_nanos(now), &rate).unwrap() + 10;
+    let expected = unsafe { mxl_api.get_ns_until_index(index.value(), &rate.as_rational().into()) };
+    let duration = mxl::timing::duration_until_index(
+        index,
+        &rate,
+        mxl::Timestamp::from_nanos(unsafe { mxl_api.get_time() }),
+    )
+    .unwrap();
+    assert!(duration <= Duration::from_nanos(expected));
+    assert!(duration > Duration::from_millis(150));
+
+    // The ring buffers match the ones allocated by the library with the default history.
+    let (mxl_instance, _domain_guard) = setup_test("timing_rings");
+    let config_info = mxl_instance
+        .create_flow_from_def(&FlowDef::v210_1080i50(), &FlowOptions::new())
+        .unwrap();
+    let ring = mxl::timing::GrainRing::for_history(
+        mxl::DEFAULT_HISTORY_DURATION,
+        &config_info.common().grain_rate().unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        ring.grain_count(),
+        u64::from(config_info.discrete().unwrap().grainCount)
+    );
+    let config_info = mxl_instance
+        .create_flow_from_def(&FlowDef::float32_48khz(2), &FlowOptions::new())
+        .unwrap();
+    let ring = mxl::timing::SampleRing::for_history(
+        mxl::DEFAULT_HISTORY_DURATION,
+        &config_info.common().sample_rate().unwrap(),
+        4,
+    )
+    .unwrap();
+    assert_eq!(
+        ring.buffer_length(),
+        u64::from(config_info.continuous().unwrap().bufferLength)
+    );
+}
+
+#[test]
+fn rate_mapping_matches_the_library() {
+    use mxl::{Rational, SampleRate, timing::RateMapping};
+
+    let mxl_api = mxl::load_api(get_mxl_so_path()).unwrap();
+    let now = unsafe { mxl_api.get_time() };
+    let rates = [
+        Rational::FPS_23_98,
+        Rational::FPS_25,
+        Rational::FPS_29_97,
+        Rational::FPS_50,
+        Rational::FPS_59_94,
+        Rational::HZ_48000,
+        Rational::HZ_96000,
+    ];
+    let timestamp =
+        |index: u64, rate: &Rational| unsafe { mxl_api.index_to_timestamp(&(*rate).into(), index) };
+    // Pseudorandom offsets from now, reproducible from run to run.
+    let mut offset = 0x9e37_79b9_7f4a_7c15u64;
+    for from in rates {
+        for to in rates {
+            let mapping = RateMapping::new(
+                mxl::GrainRate::new(from).unwrap(),
+                SampleRate::new(to).unwrap(),
+            );
+            let current = unsafe { mxl_api.timestamp_to_index(&from.into(), now) };
+            for _ in 0..200 {
+                offset ^= offset << 13;
+                offset ^= offset >> 7;
+                offset ^= offset << 17;
+                let index = GrainIndex::new(current + offset % 1_000_000);
+                let range = mapping.map(index).unwrap();
+                let start = timestamp(index.value(), &from);
+                // The range holds exactly what begins during the index, by the library clock.
+                assert!(
+                    timestamp(range.start.value(), &to) >= start,
+                    "{index} {from} {to}"
+                );
+                assert!(
+                    timestamp(range.start.value() - 1, &to) < start,
+                    "{index} {from} {to}"
+                );
+                let end = timestamp(index.value() + 1, &from);
+                assert!(
+                    timestamp(range.end.value(), &to) >= end,
+                    "{index} {from} {to}"
+                );
+                assert!(
+                    timestamp(range.end.value() - 1, &to) < end,
+                    "{index} {from} {to}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn grain_stream() {
+    use futures::{StreamExt, executor::block_on};
+    use mxl::StreamStart;
+
+    let (mxl_instance, _domain_guard) = setup_test("grain_stream");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let mut grain_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    let grain_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let first_index: GrainIndex = mxl_instance.get_current_index(&rate).unwrap();
+
+    // The grains are written while the stream is waiting for them.
+    let writer_thread = std::thread::spawn(move || {
+        for index in 0..3 {
+            std::thread::sleep(Duration::from_millis(50));
+            let mut access = grain_writer.open_grain(first_index + index).unwrap();
+            access.payload_mut()[0] = index as u8;
+            let total_slices = access.total_slices();
+            access.commit(total_slices).unwrap();
+        }
+        grain_writer
+    });
+    let mut stream = grain_reader
+        .stream(StreamStart::Index(first_index), Duration::from_secs(5))
+        .unwrap();
+    for expected in 0..3 {
+        let (index, grain) = block_on(stream.next()).unwrap().unwrap();
+        assert_eq!(index, first_index + expected);
+        assert!(grain.is_complete());
+        assert_eq!(grain.payload[0], expected as u8);
+    }
+    let grain_writer = writer_thread.join().unwrap();
+
+    // Nothing more is written, the next grain is reported as too early and the stream goes on.
+    let mut stream = grain_reader
+        .stream(
+            StreamStart::Index(first_index + 3),
+            Duration::from_millis(50),
+        )
+        .unwrap();
+    let error = block_on(stream.next()).unwrap().err().unwrap();
+    assert!(matches!(error.root(), mxl::Error::OutOfRangeTooEarly));
+    assert_eq!(
+        error.context().unwrap().index,
+        Some((first_index + 3).value())
+    );
+    assert_eq!(stream.next_index(), Some(first_index + 3));
+    drop(stream);
+
+    // The grains written long ago are gone, the stream continues from the head.
+    let mut stream = grain_reader
+        .into_stream(
+            StreamStart::Index(first_index - 1000),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+    let error = block_on(stream.next()).unwrap().err().unwrap();
+    assert!(matches!(error.root(), mxl::Error::OutOfRangeTooLate));
+    let (index, grain) = block_on(stream.next()).unwrap().unwrap();
+    assert_eq!(index, first_index + 2);
+    assert_eq!(grain.payload[0], 2);
+
+    stream.into_reader().destroy().unwrap();
+    grain_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn grain_sink() {
+    use futures::{SinkExt, executor::block_on};
+    use mxl::GrainPayload;
+
+    let (mxl_instance, _domain_guard) = setup_test("grain_sink");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let mut sink = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap()
+        .into_sink()
+        .paced()
+        .unwrap();
+    let grain_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let first_index: GrainIndex = mxl_instance.get_current_index(&rate).unwrap();
+
+    // A paced grain is not written before its timestamp.
+    block_on(sink.send(GrainPayload::new(first_index + 2, vec![1, 2, 3]))).unwrap();
+    assert!(
+        mxl_instance.get_time()
+            >= mxl_instance
+                .index_to_timestamp(first_index + 2, &rate)
+                .unwrap()
+    );
+    let grain = grain_reader
+        .get_complete_grain(first_index + 2, Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(&grain.payload[..3], &[1, 2, 3]);
+    assert!(!grain.is_invalid());
+
+    block_on(
+        sink.send(GrainPayload::new(first_index + 3, vec![4]).with_flags(GrainFlags::INVALID)),
+    )
+    .unwrap();
+    let grain = grain_reader
+        .get_complete_grain(first_index + 3, Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(grain.payload[0], 4);
+    assert!(grain.is_invalid());
+
+    // A payload larger than the grain is refused and the grain canceled.
+    let oversized = vec![0; grain.payload.len() + 1];
+    let error = block_on(sink.send(GrainPayload::new(first_index + 4, oversized))).unwrap_err();
+    assert_eq!(
+        error.context().unwrap().index,
+        Some((first_index + 4).value())
+    );
+    let error = grain_reader
+        .get_grain_non_blocking(first_index + 4)
+        .err()
+        .unwrap();
+    assert!(matches!(error.root(), mxl::Error::OutOfRangeTooEarly));
+    block_on(sink.close()).unwrap();
+
+    grain_reader.destroy().unwrap();
+    sink.into_writer().destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn samples_sink() {
+    use futures::{SinkExt, executor::block_on};
+    use mxl::{SampleIndex, SamplesBlock};
+
+    let (mxl_instance, _domain_guard) = setup_test("samples_sink");
+    let flow_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/audio_flow.json");
+    let flow_id = flow_info.common().id().to_string();
+    let channels = flow_info.continuous().unwrap().channelCount as usize;
+    let mut sink = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_samples_writer()
+        .unwrap()
+        .into_sink();
+    let samples_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_samples_reader()
+        .unwrap();
+    let rate = flow_info.common().sample_rate().unwrap();
+    let index: SampleIndex = mxl_instance.get_current_index(&rate).unwrap();
+
+    let block: Vec<Vec<u8>> = (0..channels)
+        .map(|channel| vec![channel as u8; 42 * 4])
+        .collect();
+    block_on(sink.send(SamplesBlock::new(index, 42, block))).unwrap();
+    let samples = samples_reader
+        .get_samples(index, 42, Duration::from_secs(5))
+        .unwrap()
+        .to_owned();
+    for channel in 0..channels {
+        assert!(
+            samples.payload[channel]
+                .iter()
+                .all(|sample| *sample == channel as u8)
+        );
+    }
+
+    // Blocks must have the samples of every channel of the flow.
+    let error = block_on(sink.send(SamplesBlock::new(
+        index + 42,
+        42,
+        vec![vec![0; 42 * 4]; channels + 1],
+    )))
+    .unwrap_err();
+    assert_eq!(error.context().unwrap().operation, "send samples");
+
+    samples_reader.destroy().unwrap();
+    sink.into_writer().destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn grain_iter_catch_up() {
+    use mxl::{CatchUp, GrainEvent, StreamStart};
+
+    let (mxl_instance, _domain_guard) = setup_test("grain_iter_catch_up");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let mut grain_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    let grain_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let first_index: GrainIndex = mxl_instance.get_current_index(&rate).unwrap();
+    for index in 0..3 {
+        let mut access = grain_writer.open_grain(first_index + index).unwrap();
+        access.payload_mut()[0] = index as u8;
+        let total_slices = access.total_slices();
+        access.commit(total_slices).unwrap();
+    }
+
+    let grain_count = u64::from(flow_config_info.discrete().unwrap().grainCount);
+    let window = grain_reader.readable_window().unwrap();
+    assert_eq!(*window.end(), first_index + 2);
+    assert_eq!(*window.start(), first_index + 3 - grain_count);
+
+    let mut grains = grain_reader.iter(StreamStart::Index(first_index), Duration::from_secs(5));
+    for expected in 0..3 {
+        match grains.next().unwrap().unwrap() {
+            GrainEvent::Grain { index, grain } => {
+                assert_eq!(index, first_index + expected);
+                assert_eq!(grain.payload[0], expected as u8);
+            }
+            GrainEvent::CaughtUp { .. } => panic!("Unexpected catch-up."),
+        }
+    }
+    let error = grains.next().unwrap().err().unwrap();
+    assert!(matches!(error.root(), mxl::Error::Timeout));
+    assert_eq!(grains.next_index(), Some(first_index + 3));
+
+    // By default, falling behind the ring buffer ends the iteration.
+    let mut grains = grain_reader.iter(
+        StreamStart::Index(first_index - grain_count),
+        Duration::from_secs(5),
+    );
+    let error = grains.next().unwrap().err().unwrap();
+    assert!(matches!(error.root(), mxl::Error::OutOfRangeTooLate));
+    assert!(grains.next().is_none());
+
+    // Catching up is reported with the number of dropped grains.
+    let missed = first_index - grain_count;
+    let mut grains = grain_reader
+        .iter(StreamStart::Index(missed), Duration::from_secs(5))
+        .catch_up(CatchUp::SkipToHeadMinus(1));
+    let event = grains.next().unwrap().unwrap();
+    assert_eq!(event.dropped_grains(), grain_count + 1);
+    assert!(matches!(
+        event,
+        GrainEvent::CaughtUp { resumed, .. } if resumed == first_index + 1
+    ));
+    assert!(matches!(
+        grains.next().unwrap().unwrap(),
+        GrainEvent::Grain { index, .. } if index == first_index + 1
+    ));
+
+    let mut grains = grain_reader
+        .iter(StreamStart::Index(missed), Duration::from_secs(5))
+        .catch_up(CatchUp::SkipToHead);
+    assert!(matches!(
+        grains.next().unwrap().unwrap(),
+        GrainEvent::CaughtUp { resumed, .. } if resumed == first_index + 2
+    ));
+
+    grain_reader.destroy().unwrap();
+    grain_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn paced_writer() {
+    use std::ops::ControlFlow;
+
+    let (mxl_instance, _domain_guard) = setup_test("paced_writer");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let grain_duration = mxl::timing::index_duration(GrainIndex::new(0), &rate).unwrap();
+    let grain_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let mut writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap()
+        .into_paced()
+        .unwrap();
+
+    // The producer is too slow for the first grain, the grains it missed are filled as invalid.
+    let mut indexes = Vec::new();
+    writer
+        .run(|index, access| {
+            indexes.push(index);
+            access.payload_mut()[0] = indexes.len() as u8;
+            if indexes.len() == 1 {
+                std::thread::sleep(grain_duration * 7 / 2);
+            }
+            Ok(if indexes.len() == 3 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            })
+        })
+        .unwrap();
+    let stats = *writer.stats();
+    assert_eq!(stats.written, 2);
+    assert_eq!(stats.overruns, 1);
+    assert!(indexes[1].saturating_distance_since(indexes[0]) >= 3);
+    assert_eq!(stats.filled, indexes[1].value() - indexes[0].value() - 1);
+    assert!(stats.max_lateness >= stats.mean_lateness());
+
+    let first = grain_reader
+        .get_complete_grain(indexes[0], Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(first.payload[0], 1);
+    assert!(!first.is_invalid());
+    let filled = grain_reader
+        .get_complete_grain(indexes[0] + 1, Duration::from_secs(5))
+        .unwrap();
+    assert!(filled.is_invalid());
+    let second = grain_reader
+        .get_complete_grain(indexes[1], Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(second.payload[0], 2);
+    // Breaking cancels the grain.
+    assert_eq!(indexes[2], indexes[1] + 1);
+    let error = grain_reader
+        .get_grain_non_blocking(indexes[2])
+        .err()
+        .unwrap();
+    assert!(matches!(error.root(), mxl::Error::OutOfRangeTooEarly));
+
+    // A negative offset writes into the future without waiting.
+    let mut writer = writer.into_writer().into_paced().unwrap().offset(-100);
+    let now: GrainIndex = mxl_instance.get_current_index(&rate).unwrap();
+    let mut written = None;
+    let flow = writer
+        .write_next(|index, _| {
+            written = Some(index);
+            Ok(ControlFlow::Continue(()))
+        })
+        .unwrap();
+    assert!(flow.is_continue());
+    assert!(written.unwrap() >= now + 99);
+    assert!(writer.stats().last_lateness < grain_duration);
+
+    grain_reader.destroy().unwrap();
+    writer.into_writer().destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn paced_samples_writer() {
+    use std::ops::ControlFlow;
+
+    let (mxl_instance, _domain_guard) = setup_test("paced_samples_writer");
+    let flow_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/audio_flow.json");
+    let flow_id = flow_info.common().id().to_string();
+    let samples_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_samples_reader()
+        .unwrap();
+    let mut writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_samples_writer()
+        .unwrap()
+        .into_paced(64)
+        .unwrap();
+    assert_eq!(writer.batch_size(), 64);
+
+    let mut indexes = Vec::new();
+    writer
+        .run(|index, access| {
+            indexes.push(index);
+            for channel in 0..access.channels() {
+                let (first, second) = access.channel_data_mut(channel)?;
+                first.fill(1);
+                second.fill(1);
+            }
+            Ok(if indexes.len() == 2 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            })
+        })
+        .unwrap();
+    assert_eq!(indexes[1], indexes[0] + 64);
+    assert_eq!(writer.stats().written, 1);
+
+    let samples = samples_reader
+        .get_samples(indexes[0], 64, Duration::from_secs(5))
+        .unwrap()
+        .to_owned();
+    assert!(samples.payload.iter().flatten().all(|byte| *byte == 1));
+
+    samples_reader.destroy().unwrap();
+    writer.into_writer().destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn paced_writer_stall_longer_than_the_ring() {
+    use std::ops::ControlFlow;
+
+    let (mxl_instance, _domain_guard) = setup_test("paced_writer_stall");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let grain_duration = mxl::timing::index_duration(GrainIndex::new(0), &rate).unwrap();
+    let grain_count = flow_config_info.discrete().unwrap().grainCount;
+    let mut writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap()
+        .into_paced()
+        .unwrap();
+
+    // Only the grains the ring buffer holds are filled after the stall, the others are dropped.
+    let mut indexes = Vec::new();
+    writer
+        .run(|index, _| {
+            indexes.push(index);
+            if indexes.len() == 1 {
+                std::thread::sleep(grain_duration * (grain_count + 5));
+            }
+            Ok(if indexes.len() == 2 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            })
+        })
+        .unwrap();
+    let stats = *writer.stats();
+    assert_eq!(stats.overruns, 1);
+    assert_eq!(stats.filled, u64::from(grain_count));
+    assert!(stats.dropped >= 4);
+    assert_eq!(
+        stats.filled + stats.dropped,
+        indexes[1].value() - indexes[0].value() - 1
+    );
+
+    writer.into_writer().destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn float32_samples_across_the_ring_wrap() {
+    use mxl::SampleIndex;
+
+    let (mxl_instance, _domain_guard) = setup_test("float32_samples");
+    let flow_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/audio_flow.json");
+    let flow_id = flow_info.common().id().to_string();
+    let channels = flow_info.continuous().unwrap().channelCount as usize;
+    let buffer_length = u64::from(flow_info.continuous().unwrap().bufferLength);
+    let mut samples_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_samples_writer()
+        .unwrap();
+    let samples_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_samples_reader()
+        .unwrap();
+    let rate = flow_info.common().sample_rate().unwrap();
+    let current: SampleIndex = mxl_instance.get_current_index(&rate).unwrap();
+    // The batch ends 10 samples after a wrap of the ring buffer.
+    let index = SampleIndex::new((current.value() / buffer_length + 1) * buffer_length + 10);
+
+    let interleaved: Vec<f32> = (0..64 * channels).map(|i| i as f32 / 1000.0).collect();
+    let mut access = samples_writer.open_samples(index, 64).unwrap();
+    let (first, second) = access.channel_data_mut(0).unwrap();
+    assert_eq!((first.len(), second.len()), (54 * 4, 10 * 4));
+    access.deinterleave_from(&interleaved).unwrap();
+    assert!(access.deinterleave_from(&interleaved[1..]).is_err());
+    access.commit().unwrap();
+
+    let samples = samples_reader
+        .get_samples(index, 64, Duration::from_secs(5))
+        .unwrap();
+    let mut read_back = vec![0.0; interleaved.len()];
+    samples.interleave_to(&mut read_back).unwrap();
+    assert_eq!(read_back, interleaved);
+    let last_channel = samples.channel_samples(channels - 1).unwrap();
+    assert_eq!(last_channel.len(), 64);
+    assert_eq!(
+        last_channel.get(60),
+        Some(interleaved[60 * channels + channels - 1])
+    );
+    let owned = samples.to_owned();
+    assert_eq!(
+        owned.channel_samples(0).unwrap().to_vec(),
+        samples.channel_samples(0).unwrap().to_vec()
+    );
+
+    samples_reader.destroy().unwrap();
+    samples_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn pcm_conversions() {
+    use mxl::{PcmConverter, PcmFormat, SampleIndex};
+
+    let (mxl_instance, _domain_guard) = setup_test("pcm_conversions");
+    let flow_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/audio_flow.json");
+    let flow_id = flow_info.common().id().to_string();
+    let channels = flow_info.continuous().unwrap().channelCount as usize;
+    let mut samples_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_samples_writer()
+        .unwrap();
+    let samples_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_samples_reader()
+        .unwrap();
+    let rate = flow_info.common().sample_rate().unwrap();
+    let index: SampleIndex = mxl_instance.get_current_index(&rate).unwrap();
+
+    let mut converter = PcmConverter::new(PcmFormat::S24Le);
+    let interleaved: Vec<u8> = (0..100 * channels as i32)
+        .flat_map(|i| (i * 1000 - 50_000).to_le_bytes()[..3].to_vec())
+        .collect();
+    let mut access = samples_writer.open_samples(index, 100).unwrap();
+    converter
+        .decode_interleaved(&interleaved, &mut access)
+        .unwrap();
+    assert!(
+        converter
+            .decode_interleaved(&interleaved[3..], &mut access)
+            .is_err()
+    );
+    access.commit().unwrap();
+
+    let samples = samples_reader
+        .get_samples(index, 100, Duration::from_secs(5))
+        .unwrap();
+    let mut read_back = vec![0; interleaved.len()];
+    assert_eq!(
+        converter
+            .encode_interleaved(&samples, &mut read_back)
+            .unwrap(),
+        0
+    );
+    assert_eq!(read_back, interleaved);
+
+    let mut planes = vec![vec![0u8; 100 * 3]; channels];
+    let mut plane_refs: Vec<&mut [u8]> = planes.iter_mut().map(Vec::as_mut_slice).collect();
+    converter.encode_planar(&samples, &mut plane_refs).unwrap();
+    for (channel, plane) in planes.iter().enumerate() {
+        assert_eq!(&plane[..3], &interleaved[channel * 3..channel * 3 + 3]);
+    }
+    assert!(converter.encode_planar(&samples, &mut []).is_err());
+
+    samples_reader.destroy().unwrap();
+    samples_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn samples_cursor() {
+    use mxl::{CursorEvent, SampleIndex};
+
+    let (mxl_instance, _domain_guard) = setup_test("samples_cursor");
+    let flow_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/audio_flow.json");
+    let flow_id = flow_info.common().id().to_string();
+    let buffer_length = u64::from(flow_info.continuous().unwrap().bufferLength);
+    let mut samples_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_samples_writer()
+        .unwrap();
+    let samples_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_samples_reader()
+        .unwrap();
+    let rate = flow_info.common().sample_rate().unwrap();
+    let start: SampleIndex = mxl_instance.get_current_index(&rate).unwrap();
+
+    // Every sample of the first channel holds its distance from `start`.
+    for batch in 1..=4u64 {
+        let mut access = samples_writer.open_samples(start + batch * 64, 64).unwrap();
+        let mut channel = access.channel_samples_mut(0).unwrap();
+        for i in 0..64 {
+            channel
+                .set(i, ((batch - 1) * 64 + i as u64) as f32)
+                .unwrap();
+        }
+        access.commit().unwrap();
+    }
+
+    let cursor = samples_reader
+        .cursor(start, Duration::from_millis(10))
+        .unwrap();
+    assert!(cursor.with_chunk_size(0).is_err());
+    let mut cursor = samples_reader
+        .cursor(start, Duration::from_millis(10))
+        .unwrap()
+        .with_chunk_size(48)
+        .unwrap();
+    let mut read = Vec::new();
+    cursor
+        .read(200, |event| {
+            match event {
+                CursorEvent::Samples { start: index, data } => {
+                    let samples = data.channel_samples(0).unwrap();
+                    assert!(samples.len() <= 48);
+                    assert_eq!(index, start + read.len() as u64);
+                    read.extend(samples.iter());
+                }
+                CursorEvent::Overrun { .. } => panic!("unexpected overrun"),
+            }
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(read, (0..200).map(|i| i as f32).collect::<Vec<_>>());
+    assert_eq!(cursor.position(), start + 200);
+
+    // 56 samples are left, the rest has not been written yet.
+    let CursorEvent::Samples { data, .. } = cursor.next_chunk(100).unwrap() else {
+        panic!("unexpected overrun");
+    };
+    assert_eq!(data.channel_samples(0).unwrap().len(), 48);
+    let error = cursor.read(16, |_| Ok(())).unwrap_err();
+    assert!(matches!(error.root(), mxl::Error::Timeout));
+    assert_eq!(cursor.position(), start + 248);
+
+    // Seeking by timestamp lands on the same sample.
+    let timestamp = mxl_instance.index_to_timestamp(start + 10, &rate).unwrap();
+    assert_eq!(cursor.seek_to_timestamp(timestamp).unwrap(), start + 10);
+    assert_eq!(cursor.position_timestamp().unwrap(), timestamp);
+
+    // The writer moves a whole buffer ahead, the samples after the cursor are overwritten.
+    let ahead = start + 256 + 2 * buffer_length;
+    samples_writer
+        .open_samples(ahead, 64)
+        .unwrap()
+        .commit()
+        .unwrap();
+    match cursor.next_chunk(16).unwrap() {
+        CursorEvent::Overrun { missed, resumed } => {
+            assert_eq!(missed, start + 10);
+            assert_eq!(resumed, ahead - 16);
+        }
+        CursorEvent::Samples { .. } => panic!("expected an overrun"),
+    }
+    assert!(matches!(
+        cursor.next_chunk(16).unwrap(),
+        CursorEvent::Samples { .. }
+    ));
+    assert_eq!(cursor.position(), ahead);
+
+    samples_reader.destroy().unwrap();
+    samples_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn aligned_reader() {
+    use mxl::{AlignedData, AlignedReader, SampleIndex};
+
+    let (mxl_instance, _domain_guard) = setup_test("aligned_reader");
+    let video_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let audio_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/audio_flow.json");
+    let video_id = video_info.common().id().to_string();
+    let audio_id = audio_info.common().id().to_string();
+    let mut grain_writer = mxl_instance
+        .create_flow_writer(video_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    let mut samples_writer = mxl_instance
+        .create_flow_writer(audio_id.as_str())
+        .unwrap()
+        .to_samples_writer()
+        .unwrap();
+    let video_rate = video_info.common().grain_rate().unwrap();
+    let mut aligned = AlignedReader::new(video_rate)
+        .with_timeout(Duration::from_millis(10))
+        .with_grains(
+            mxl_instance
+                .create_flow_reader(video_id.as_str())
+                .unwrap()
+                .to_grain_reader()
+                .unwrap(),
+        )
+        .unwrap()
+        .with_samples(
+            mxl_instance
+                .create_flow_reader(audio_id.as_str())
+                .unwrap()
+                .to_samples_reader()
+                .unwrap(),
+        )
+        .unwrap();
+
+    // 5 grains at 30000/1001 last exactly 8008 samples at 48 kHz.
+    let current: GrainIndex = mxl_instance.get_current_index(&video_rate).unwrap();
+    let first = GrainIndex::new((current.value() / 5 + 1) * 5);
+    let sample_at = |tick: u64| SampleIndex::new((tick * 8008).div_ceil(5));
+    let mut counts = Vec::new();
+    for tick in 0..5 {
+        let index = first + tick;
+        let mut access = grain_writer.open_grain(index).unwrap();
+        access.payload_mut()[0] = tick as u8;
+        let total_slices = access.total_slices();
+        access.commit(total_slices).unwrap();
+        let end = sample_at(index.value() + 1);
+        let count = end.saturating_distance_since(sample_at(index.value())) as usize;
+        samples_writer
+            .open_samples(end, count)
+            .unwrap()
+            .commit()
+            .unwrap();
+
+        if tick == 0 {
+            assert_eq!(aligned.aligned_head().unwrap(), Some(first));
+            assert_eq!(aligned.next_index(), None);
+        }
+        let bundle = aligned.next_bundle().unwrap();
+        assert_eq!(bundle.index, index);
+        assert!(bundle.is_complete());
+        assert_eq!(bundle.members[0].flow_id.to_string(), video_id);
+        match &bundle.members[0].data {
+            AlignedData::Grains(grains) => {
+                assert_eq!(grains.len(), 1);
+                assert_eq!(grains[0].0, index);
+                assert_eq!(grains[0].1.payload[0], tick as u8);
+            }
+            _ => panic!("expected a grain"),
+        }
+        match &bundle.members[1].data {
+            AlignedData::Samples { start, data } => {
+                assert_eq!(*start, sample_at(index.value()));
+                let (first, second) = data.channel_data(0).unwrap();
+                counts.push((first.len() + second.len()) / 4);
+            }
+            _ => panic!("expected samples"),
+        }
+    }
+    assert_eq!(counts.iter().sum::<usize>(), 8008);
+    assert!(counts.iter().all(|count| *count == 1601 || *count == 1602));
+
+    // Nothing has been written for the next tick yet.
+    let bundle = aligned.next_bundle().unwrap();
+    assert_eq!(bundle.index, first + 5);
+    assert!(!bundle.is_complete());
+    assert!(matches!(bundle.members[0].data, AlignedData::Missing));
+    assert!(matches!(bundle.members[1].data, AlignedData::Missing));
+
+    // The ring buffers only hold a few grains, the first tick is gone.
+    aligned.seek(first);
+    let bundle = aligned.next_bundle().unwrap();
+    assert!(matches!(bundle.members[0].data, AlignedData::Late));
+
+    drop(aligned);
+    grain_writer.destroy().unwrap();
+    samples_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(video_id.as_str()).unwrap();
+    mxl_instance.destroy_flow(audio_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn grains_are_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<mxl::GrainReader>();
+    assert_send::<mxl::GrainData<'static>>();
+}
+
+#[test]
+fn torn_grain_reads() {
+    let (mxl_instance, _domain_guard) = setup_test("torn_grain_reads");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let mut grain_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    let grain_reader = mxl_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let grain_count = u64::from(flow_config_info.discrete().unwrap().grainCount);
+    let index: GrainIndex = mxl_instance.get_current_index(&rate).unwrap();
+    let mut access = grain_writer.open_grain(index).unwrap();
+    access.payload_mut().fill(7);
+    let total_slices = access.total_slices();
+    access.commit(total_slices).unwrap();
+
+    let grain = grain_reader
+        .get_complete_grain(index, Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(grain.index(), index);
+    assert!(grain.still_valid());
+    let mut buffer = vec![0; grain.total_size];
+    assert_eq!(grain.copy_into(&mut buffer).unwrap(), grain.total_size);
+    assert!(buffer.iter().all(|byte| *byte == 7));
+    assert!(grain.copy_into(&mut buffer[1..]).is_err());
+    let sum = grain
+        .read_consistent(|payload| payload.iter().map(|byte| u64::from(*byte)).sum::<u64>())
+        .unwrap();
+    assert_eq!(sum, 7 * grain.total_size as u64);
+    // The grain can be moved to another thread to be checked there, while the reader stays here.
+    let grain = std::thread::scope(|scope| {
+        scope
+            .spawn(move || {
+                assert!(grain.still_valid());
+                grain
+            })
+            .join()
+            .unwrap()
+    });
+
+    // Opening a grain in the same slot is enough to corrupt the payload.
+    let mut access = grain_writer.open_grain(index + grain_count).unwrap();
+    access.payload_mut().fill(8);
+    assert!(!grain.still_valid());
+    let error = grain.read_consistent(|payload| payload[0]).unwrap_err();
+    assert!(matches!(error.root(), mxl::Error::Overwritten));
+    assert!(matches!(
+        grain.to_owned_consistent().err().unwrap().root(),
+        mxl::Error::Overwritten
+    ));
+    access.cancel().unwrap();
+
+    grain_reader.destroy().unwrap();
+    grain_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+#[allow(deprecated)]
+fn shared_open_shims() {
+    let (mxl_instance, _domain_guard) = setup_test("shared_open_shims");
+    let flow_config_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let grain_writer = mxl_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let index: GrainIndex = mxl_instance.get_current_index(&rate).unwrap();
+    // SAFETY: no other grain is open.
+    let access = unsafe { grain_writer.open_grain_unchecked(index) }.unwrap();
+    let total_slices = access.total_slices();
+    access.commit(total_slices).unwrap();
+
+    let audio_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/audio_flow.json");
+    let audio_id = audio_info.common().id().to_string();
+    let samples_writer = mxl_instance
+        .create_flow_writer(audio_id.as_str())
+        .unwrap()
+        .to_samples_writer()
+        .unwrap();
+    let rate = audio_info.common().sample_rate().unwrap();
+    let index = mxl_instance.get_current_index(&rate).unwrap();
+    // SAFETY: no other samples are open.
+    let access = unsafe { samples_writer.open_samples_unchecked(index, 64) }.unwrap();
+    access.commit().unwrap();
+
+    grain_writer.destroy().unwrap();
+    samples_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(flow_id.as_str()).unwrap();
+    mxl_instance.destroy_flow(audio_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
+
+#[test]
+fn typed_flow_construction() {
+    use mxl::FlowRead;
+
+    let (mxl_instance, _domain_guard) = setup_test("typed_flow_construction");
+    let video_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/v210_flow.json");
+    let video_id = video_info.common().id().to_string();
+    let audio_info = prepare_flow_config_info(&mxl_instance, "lib/tests/data/audio_flow.json");
+    let audio_id = audio_info.common().id().to_string();
+
+    let mut grain_writer = mxl_instance.create_grain_writer(video_id.as_str()).unwrap();
+    assert_eq!(
+        grain_writer.config_info().common().grain_rate().unwrap(),
+        video_info.common().grain_rate().unwrap()
+    );
+    let samples_writer = mxl_instance
+        .create_samples_writer(audio_id.as_str())
+        .unwrap();
+    assert_eq!(
+        samples_writer
+            .config_info()
+            .continuous()
+            .unwrap()
+            .bufferLength,
+        audio_info.continuous().unwrap().bufferLength
+    );
+    let error = mxl_instance
+        .create_grain_writer(audio_id.as_str())
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error.root(),
+        mxl::Error::FlowFormatMismatch { .. }
+    ));
+
+    let rate = video_info.common().grain_rate().unwrap();
+    let index: GrainIndex = mxl_instance.get_current_index(&rate).unwrap();
+    let access = grain_writer.open_grain(index).unwrap();
+    let total_slices = access.total_slices();
+    access.commit(total_slices).unwrap();
+
+    let opened = mxl_instance.open_flow(video_id.as_str()).unwrap();
+    assert!(opened.is_discrete_flow());
+    assert_eq!(opened.flow_id().to_string(), video_id);
+    assert_eq!(
+        opened
+            .get_runtime_info()
+            .unwrap()
+            .grain_head_index()
+            .unwrap(),
+        index
+    );
+    let mxl::OpenedFlow::Discrete(grain_reader) = opened else {
+        panic!("video flow opened as a continuous flow");
+    };
+    grain_reader
+        .get_complete_grain(index, Duration::from_secs(5))
+        .unwrap();
+
+    let opened = mxl_instance.open_flow(audio_id.as_str()).unwrap();
+    assert!(!opened.is_discrete_flow());
+    // The head of an audio flow counts samples.
+    let runtime_info = opened.get_runtime_info().unwrap();
+    assert!(runtime_info.sample_head_index().is_ok());
+    assert!(matches!(
+        runtime_info.grain_head_index(),
+        Err(mxl::Error::FlowFormatMismatch { .. })
+    ));
+    assert_eq!(
+        opened.config_info().common().sample_rate().unwrap(),
+        audio_info.common().sample_rate().unwrap()
+    );
+    let samples_reader = mxl_instance
+        .create_samples_reader(audio_id.as_str())
+        .unwrap();
+    assert_eq!(
+        samples_reader.config_info().common().id().to_string(),
+        audio_id
+    );
+    let error = mxl_instance
+        .create_samples_reader(video_id.as_str())
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error.root(),
+        mxl::Error::FlowFormatMismatch { .. }
+    ));
+
+    drop(opened);
+    grain_reader.destroy().unwrap();
+    samples_reader.destroy().unwrap();
+    grain_writer.destroy().unwrap();
+    samples_writer.destroy().unwrap();
+    mxl_instance.destroy_flow(video_id.as_str()).unwrap();
+    mxl_instance.destroy_flow(audio_id.as_str()).unwrap();
+    mxl_instance.destroy().unwrap();
+}
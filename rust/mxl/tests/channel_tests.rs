@@ -0,0 +1,123 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Tests of the float32 channel views. These do not need the MXL library, the views are used on
+/// actual flows in the basic tests.
+use mxl::{ChannelSamples, ChannelSamplesMut, Error, deinterleave, interleave};
+
+fn to_bytes(samples: &[f32]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|sample| sample.to_ne_bytes())
+        .collect()
+}
+
+#[test]
+fn views_span_both_fragments() {
+    let first = to_bytes(&[0.0, 0.25]);
+    let second = to_bytes(&[0.5, 0.75, 1.0]);
+    let samples = ChannelSamples::new(&first, &second).unwrap();
+    assert_eq!(samples.len(), 5);
+    assert_eq!(samples.get(1), Some(0.25));
+    assert_eq!(samples.get(2), Some(0.5));
+    assert_eq!(samples.get(4), Some(1.0));
+    assert_eq!(samples.get(5), None);
+    assert_eq!(samples.get(usize::MAX), None);
+    assert_eq!(samples.to_vec(), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+    assert_eq!(
+        samples.iter().rev().collect::<Vec<_>>(),
+        vec![1.0, 0.75, 0.5, 0.25, 0.0]
+    );
+
+    let mut copy = [0.0; 5];
+    samples.copy_to(&mut copy).unwrap();
+    assert_eq!(copy, [0.0, 0.25, 0.5, 0.75, 1.0]);
+    assert!(matches!(
+        samples.copy_to(&mut [0.0; 4]),
+        Err(Error::SampleCountMismatch {
+            expected: 5,
+            actual: 4
+        })
+    ));
+
+    // The wrap may also fall at the very beginning or end of the samples.
+    let samples = ChannelSamples::new(&[], &second).unwrap();
+    assert_eq!(samples.get(0), Some(0.5));
+    let samples = ChannelSamples::new(&first, &[]).unwrap();
+    assert_eq!(samples.get(2), None);
+    assert!(ChannelSamples::new(&[], &[]).unwrap().is_empty());
+}
+
+#[test]
+fn partial_samples_are_refused() {
+    let bytes = [0u8; 6];
+    assert!(ChannelSamples::new(&bytes, &[]).is_err());
+    assert!(ChannelSamples::new(&bytes[..4], &bytes[..2]).is_err());
+}
+
+#[test]
+fn mutable_views_span_both_fragments() {
+    let mut first = vec![0u8; 8];
+    let mut second = vec![0u8; 4];
+    let mut samples = ChannelSamplesMut::new(&mut first, &mut second).unwrap();
+    assert_eq!(samples.len(), 3);
+    samples.set(2, -1.0).unwrap();
+    samples.set(1, 0.5).unwrap();
+    assert!(matches!(samples.set(3, 1.0), Err(Error::InvalidArg)));
+    // Indexes whose samples would end past the addressable bytes are out of range.
+    assert!(matches!(
+        ChannelSamplesMut::new(&mut [], &mut [0u8; 4])
+            .unwrap()
+            .set(usize::MAX / 4, 1.0),
+        Err(Error::InvalidArg)
+    ));
+    assert_eq!(
+        ChannelSamples::new(&[], &[0u8; 4])
+            .unwrap()
+            .get(usize::MAX / 4),
+        None
+    );
+    assert_eq!(samples.get(2), Some(-1.0));
+    assert_eq!(samples.iter().collect::<Vec<_>>(), vec![0.0, 0.5, -1.0]);
+
+    samples.copy_from(&[0.1, 0.2, 0.3]).unwrap();
+    assert_eq!(samples.as_samples().to_vec(), vec![0.1, 0.2, 0.3]);
+    assert!(samples.copy_from(&[0.1]).is_err());
+    samples.fill(0.25);
+    assert_eq!(second, to_bytes(&[0.25]));
+    assert_eq!(first, to_bytes(&[0.25, 0.25]));
+}
+
+#[test]
+fn interleaving_round_trip() {
+    let left = to_bytes(&[1.0, 2.0, 3.0]);
+    let (right_1, right_2) = (to_bytes(&[-1.0]), to_bytes(&[-2.0, -3.0]));
+    let channels = [
+        ChannelSamples::new(&left, &[]).unwrap(),
+        ChannelSamples::new(&right_1, &right_2).unwrap(),
+    ];
+    let mut interleaved = [0.0; 6];
+    interleave(&channels, &mut interleaved).unwrap();
+    assert_eq!(interleaved, [1.0, -1.0, 2.0, -2.0, 3.0, -3.0]);
+    assert!(interleave(&channels, &mut [0.0; 5]).is_err());
+
+    let (mut left, mut right) = (vec![0u8; 12], vec![0u8; 12]);
+    let (right_1, right_2) = right.split_at_mut(4);
+    let mut channels = [
+        ChannelSamplesMut::new(&mut left, &mut []).unwrap(),
+        ChannelSamplesMut::new(right_1, right_2).unwrap(),
+    ];
+    deinterleave(&interleaved, &mut channels).unwrap();
+    assert_eq!(channels[0].as_samples().to_vec(), vec![1.0, 2.0, 3.0]);
+    assert_eq!(channels[1].as_samples().to_vec(), vec![-1.0, -2.0, -3.0]);
+    assert!(deinterleave(&interleaved[..4], &mut channels).is_err());
+
+    // Channels of different lengths cannot be interleaved.
+    let short = to_bytes(&[1.0]);
+    let channels = [
+        ChannelSamples::new(&left, &[]).unwrap(),
+        ChannelSamples::new(&short, &[]).unwrap(),
+    ];
+    assert!(interleave(&channels, &mut [0.0; 4]).is_err());
+    interleave(&[], &mut []).unwrap();
+}
@@ -0,0 +1,87 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test domains and flows shared by the tests that need the MXL library.
+
+use mxl::{MxlInstance, config::get_mxl_so_path};
+
+static LOG_ONCE: std::sync::Once = std::sync::Once::new();
+
+pub struct TestDomainGuard {
+    dir: std::path::PathBuf,
+}
+
+impl TestDomainGuard {
+    pub fn new(test: &str) -> Self {
+        let dir = std::path::PathBuf::from(format!(
+            "/dev/shm/mxl_rust_unit_tests_domain_{}_{}",
+            test,
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(dir.as_path()).unwrap_or_else(|_| {
+            panic!(
+                "Failed to create test domain directory \"{}\".",
+                dir.display()
+            )
+        });
+        Self { dir }
+    }
+
+    pub fn domain(&self) -> String {
+        self.dir.to_string_lossy().to_string()
+    }
+}
+
+impl Drop for TestDomainGuard {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(self.dir.as_path()).unwrap_or_else(|_| {
+            panic!(
+                "Failed to remove test domain directory \"{}\".",
+                self.dir.display()
+            )
+        });
+    }
+}
+
+pub fn setup_test(test: &str) -> (MxlInstance, TestDomainGuard) {
+    // Set up the logging to use the RUST_LOG environment variable and if not present, print INFO
+    // and higher.
+    LOG_ONCE.call_once(|| {
+        tracing_subscriber::fmt()
+            .with_env_filter(
+                tracing_subscriber::EnvFilter::builder()
+                    .with_default_directive(tracing::level_filters::LevelFilter::INFO.into())
+                    .from_env_lossy(),
+            )
+            .init();
+    });
+
+    let mxl_api = mxl::load_api(get_mxl_so_path()).unwrap();
+    let domain_guard = TestDomainGuard::new(test);
+    (
+        MxlInstance::new(mxl_api, domain_guard.domain().as_str(), "").unwrap(),
+        domain_guard,
+    )
+}
+
+pub fn read_flow_def<P: AsRef<std::path::Path>>(path: P) -> String {
+    let flow_config_file = mxl::config::get_mxl_repo_root().join(path);
+
+    std::fs::read_to_string(flow_config_file.as_path())
+        .map_err(|error| {
+            mxl::Error::Other(format!(
+                "Error while reading flow definition from \"{}\": {}",
+                flow_config_file.display(),
+                error
+            ))
+        })
+        .unwrap()
+}
+
+pub fn prepare_flow_config_info<P: AsRef<std::path::Path>>(
+    mxl_instance: &MxlInstance,
+    path: P,
+) -> mxl::FlowConfigInfo {
+    let flow_def = read_flow_def(path);
+    mxl_instance.create_flow(flow_def.as_str(), None).unwrap()
+}
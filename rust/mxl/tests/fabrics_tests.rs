@@ -0,0 +1,102 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Tests of the grain transfers between flows with the local fabrics.
+///
+/// Like the basic tests, these require the MXL library to be present in the system.
+use std::time::{Duration, Instant};
+
+mod common;
+
+use common::{prepare_flow_config_info, setup_test};
+use mxl::{
+    OwnedGrainData,
+    fabrics::{
+        EndpointAddress, Fabrics, Initiator, InitiatorConfig, Progress, Provider, Target,
+        TargetConfig, TargetInfo, local::LocalFabrics,
+    },
+};
+
+#[test]
+fn local_fabrics_grain_transfer() {
+    let (source_instance, _source_guard) = setup_test("fabrics_source");
+    let flow_config_info =
+        prepare_flow_config_info(&source_instance, "lib/tests/data/v210_flow.json");
+    let flow_id = flow_config_info.common().id().to_string();
+    let (target_instance, _target_guard) = setup_test("fabrics_target");
+    prepare_flow_config_info(&target_instance, "lib/tests/data/v210_flow.json");
+
+    let fabrics = LocalFabrics::new();
+    let grain_writer = target_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    let (mut target, target_info) = fabrics
+        .create_target(TargetConfig::new(
+            EndpointAddress::new("127.0.0.1", "0"),
+            Provider::Tcp,
+            grain_writer,
+        ))
+        .unwrap();
+    let target_info =
+        <LocalFabrics as Fabrics>::TargetInfo::deserialize(&target_info.serialize().unwrap())
+            .unwrap();
+
+    let mut source_writer = source_instance
+        .create_flow_writer(flow_id.as_str())
+        .unwrap()
+        .to_grain_writer()
+        .unwrap();
+    let source_reader = source_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let mut initiator = fabrics
+        .create_initiator(InitiatorConfig::new(
+            EndpointAddress::default(),
+            Provider::Tcp,
+            source_reader,
+        ))
+        .unwrap();
+    initiator.add_target(&target_info).unwrap();
+
+    let rate = flow_config_info.common().grain_rate().unwrap();
+    let index = source_instance.get_current_index(&rate).unwrap();
+    let mut access = source_writer.open_grain(index).unwrap();
+    for (i, byte) in access.payload_mut().iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let sent = access.payload_mut().to_vec();
+    let total_slices = access.total_slices();
+    access.commit(total_slices).unwrap();
+
+    initiator.transfer_grain(index).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut received = None;
+    while received.is_none() && Instant::now() < deadline {
+        initiator
+            .make_progress_blocking(Duration::from_millis(10))
+            .unwrap();
+        received = target
+            .wait_for_new_grain(Duration::from_millis(10))
+            .unwrap();
+    }
+    assert_eq!(received, Some(index));
+    assert_eq!(
+        initiator.make_progress_non_blocking().unwrap(),
+        Progress::Done
+    );
+
+    let target_reader = target_instance
+        .create_flow_reader(flow_id.as_str())
+        .unwrap()
+        .to_grain_reader()
+        .unwrap();
+    let received: OwnedGrainData = target_reader
+        .get_complete_grain(index, Duration::from_secs(1))
+        .unwrap()
+        .into();
+    assert_eq!(sent, received.payload);
+}
@@ -0,0 +1,112 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Tests of the flow definition model. These do not need the MXL library.
+use mxl::{
+    DataFlowDef, FlowDef, GROUP_HINT_TAG, InterlaceMode, Rational, VideoFlowDef, VideoMediaType,
+};
+
+fn rational(numerator: i64, denominator: i64) -> Rational {
+    Rational::new(numerator, denominator).unwrap()
+}
+
+#[test]
+fn presets_are_valid() {
+    FlowDef::v210_1080i50().validate().unwrap();
+    FlowDef::v210a_2160p59_94().validate().unwrap();
+    FlowDef::float32_48khz(16).validate().unwrap();
+    FlowDef::data("VANC", DataFlowDef::smpte291(rational(30000, 1001)))
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn interlaced_grain_rate_rules() {
+    let video = |numerator, denominator, height| {
+        let video = VideoFlowDef::new(
+            VideoMediaType::V210,
+            rational(numerator, denominator),
+            1920,
+            height,
+        )
+        .interlace_mode(InterlaceMode::InterlacedBff);
+        FlowDef::video("Interlaced", video)
+    };
+    video(30000, 1001, 1080).validate().unwrap();
+    video(50, 2, 1080).validate().unwrap();
+    assert!(matches!(
+        video(30, 1, 1080).validate(),
+        Err(mxl::Error::InvalidFlowDef(_))
+    ));
+    assert!(matches!(
+        video(25, 1, 1081).validate(),
+        Err(mxl::Error::InvalidFlowDef(_))
+    ));
+
+    // The same rate is fine for progressive video.
+    let progressive = VideoFlowDef::new(VideoMediaType::V210, rational(30, 1), 1920, 1080);
+    FlowDef::video("Progressive", progressive)
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn invalid_definitions() {
+    let valid = FlowDef::float32_48khz(2);
+    assert!(valid.clone().label("").validate().is_err());
+    assert!(valid.clone().group_hint("no role").validate().is_err());
+    assert!(
+        valid
+            .clone()
+            .group_hint("Group:Role:site")
+            .validate()
+            .is_err()
+    );
+    valid
+        .clone()
+        .group_hint("Group:Role:node")
+        .validate()
+        .unwrap();
+    assert!(
+        valid
+            .clone()
+            .tag(GROUP_HINT_TAG, Vec::new())
+            .validate()
+            .is_err()
+    );
+
+    let mut too_wide = FlowDef::v210_1080i50();
+    if let mxl::FlowMedia::Video(video) = &mut too_wide.media {
+        video.frame_width = 8192;
+    }
+    assert!(too_wide.validate().is_err());
+
+    let mut no_channels = FlowDef::float32_48khz(2);
+    if let mxl::FlowMedia::Audio(audio) = &mut no_channels.media {
+        audio.channel_count = 0;
+    }
+    assert!(no_channels.validate().is_err());
+}
+
+#[test]
+fn json_serialization() {
+    let id = uuid::Uuid::new_v4();
+    let json = FlowDef::v210_1080i50()
+        .id(id)
+        .label("Camera \"1\"")
+        .group_hint("Camera 1:Video")
+        .to_json();
+    assert!(json.starts_with(&format!("{{\"id\":\"{id}\"")));
+    assert!(json.contains("\"label\":\"Camera \\\"1\\\"\""));
+    assert!(json.contains("\"urn:x-nmos:tag:grouphint/v1.0\":[\"Camera 1:Video\"]"));
+    assert!(json.contains("\"format\":\"urn:x-nmos:format:video\""));
+    assert!(json.contains("\"media_type\":\"video/v210\""));
+    assert!(json.contains("\"grain_rate\":{\"numerator\":25,\"denominator\":1}"));
+    assert!(json.contains("\"interlace_mode\":\"interlaced_tff\""));
+    assert!(json.contains("{\"name\":\"Cb\",\"width\":960,\"height\":1080,\"bit_depth\":10}"));
+
+    let json = FlowDef::float32_48khz(8).to_json();
+    assert!(json.contains("\"sample_rate\":{\"numerator\":48000,\"denominator\":1}"));
+    assert!(json.contains("\"channel_count\":8"));
+    assert!(json.contains("\"bit_depth\":32"));
+}
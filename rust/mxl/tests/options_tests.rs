@@ -0,0 +1,104 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Tests of the instance and flow options. These do not need the MXL library.
+use std::time::Duration;
+
+use mxl::{FlowOptions, InstanceOptions, MxlInstance};
+
+#[test]
+fn flow_options_serialization() {
+    assert_eq!(FlowOptions::new().to_json(), "{}");
+    assert_eq!(
+        FlowOptions::new()
+            .commit_batch_size(64)
+            .sync_batch_size(128)
+            .to_json(),
+        "{\"maxCommitBatchSizeHint\":64,\"maxSyncBatchSizeHint\":128}"
+    );
+}
+
+#[test]
+fn flow_options_validation() {
+    FlowOptions::new().validate().unwrap();
+    FlowOptions::new()
+        .commit_batch_size(64)
+        .sync_batch_size(192)
+        .validate()
+        .unwrap();
+    assert!(FlowOptions::new().commit_batch_size(0).validate().is_err());
+    assert!(FlowOptions::new().sync_batch_size(0).validate().is_err());
+    assert!(
+        FlowOptions::new()
+            .commit_batch_size(64)
+            .sync_batch_size(100)
+            .validate()
+            .is_err()
+    );
+
+    let options = FlowOptions::new().commit_batch_size(480);
+    options.validate_for_buffer_length(4800).unwrap();
+    assert!(matches!(
+        options.validate_for_buffer_length(960),
+        Err(mxl::Error::InvalidOptions(_))
+    ));
+}
+
+#[test]
+fn instance_options() {
+    assert_eq!(InstanceOptions::new().to_json(), "{}");
+    let options = InstanceOptions::new().history_duration(Duration::from_millis(500));
+    options.validate().unwrap();
+    assert_eq!(
+        options.to_json(),
+        "{\"urn:x-mxl:option:history_duration/v1.0\":500000000}"
+    );
+    assert!(
+        InstanceOptions::new()
+            .history_duration(Duration::ZERO)
+            .validate()
+            .is_err()
+    );
+
+    let domain = std::env::temp_dir().join(format!("mxl_rust_options_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&domain).unwrap();
+    MxlInstance::update_domain_options(&domain, &options).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(domain.join("options.json")).unwrap(),
+        options.to_json()
+    );
+    std::fs::remove_dir_all(&domain).unwrap();
+}
+
+#[test]
+fn domain_options_are_merged() {
+    let domain = std::env::temp_dir().join(format!("mxl_rust_options_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&domain).unwrap();
+    let path = domain.join("options.json");
+    std::fs::write(
+        &path,
+        "{ \"other\": {\"list\": [1, \"a,}\"]},\n  \"urn:x-mxl:option:history_duration/v1.0\": 1 }",
+    )
+    .unwrap();
+    let options = InstanceOptions::new().history_duration(Duration::from_millis(500));
+    MxlInstance::update_domain_options(&domain, &options).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{\"other\":{\"list\": [1, \"a,}\"]},\"urn:x-mxl:option:history_duration/v1.0\":500000000}"
+    );
+
+    // Options that are not set keep the values in the file.
+    MxlInstance::update_domain_options(&domain, &InstanceOptions::new()).unwrap();
+    assert!(
+        std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("500000000")
+    );
+
+    std::fs::write(&path, "[1, 2]").unwrap();
+    let error = MxlInstance::update_domain_options(&domain, &options).unwrap_err();
+    assert!(matches!(error.root(), mxl::Error::InvalidOptions(_)));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "[1, 2]");
+    assert_eq!(std::fs::read_dir(&domain).unwrap().count(), 1);
+    std::fs::remove_dir_all(&domain).unwrap();
+}
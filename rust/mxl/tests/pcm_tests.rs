@@ -0,0 +1,153 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Tests of the PCM conversions of single channels. These do not need the MXL library, the
+/// conversions of whole flows are tested in the basic tests.
+use mxl::{ChannelSamples, ChannelSamplesMut, Dither, Error, PcmConverter, PcmFormat};
+
+fn to_bytes(samples: &[f32]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|sample| sample.to_ne_bytes())
+        .collect()
+}
+
+fn encode(converter: &mut PcmConverter, samples: &[f32]) -> (Vec<u8>, usize) {
+    let bytes = to_bytes(samples);
+    let samples = ChannelSamples::new(&bytes, &[]).unwrap();
+    let mut pcm = vec![0; samples.len() * converter.format().bytes_per_sample()];
+    let clipped = converter.encode_channel(&samples, &mut pcm).unwrap();
+    (pcm, clipped)
+}
+
+fn decode(converter: &PcmConverter, pcm: &[u8]) -> Vec<f32> {
+    let mut bytes = vec![0; pcm.len() / converter.format().bytes_per_sample() * 4];
+    let mut samples = ChannelSamplesMut::new(&mut bytes, &mut []).unwrap();
+    converter.decode_channel(pcm, &mut samples).unwrap();
+    samples.as_samples().to_vec()
+}
+
+#[test]
+fn integer_formats() {
+    let samples = [0.0, 0.5, -0.5, -1.0, 1.0];
+
+    let mut converter = PcmConverter::new(PcmFormat::S16Le);
+    let (pcm, clipped) = encode(&mut converter, &samples);
+    let values: Vec<i16> = pcm
+        .chunks_exact(2)
+        .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect();
+    assert_eq!(values, vec![0, 16384, -16384, -32768, 32767]);
+    // +1.0 is a single step above the largest value, but still within the full-scale range.
+    assert_eq!(clipped, 0);
+
+    let mut converter = PcmConverter::new(PcmFormat::S24Le);
+    let (pcm, _) = encode(&mut converter, &samples);
+    assert_eq!(pcm.len(), 15);
+    assert_eq!(&pcm[3..6], &[0x00, 0x00, 0x40]);
+    assert_eq!(&pcm[6..9], &[0x00, 0x00, 0xc0]);
+    assert_eq!(&pcm[12..15], &[0xff, 0xff, 0x7f]);
+
+    let mut converter = PcmConverter::new(PcmFormat::S32Le);
+    let (pcm, _) = encode(&mut converter, &samples);
+    assert_eq!(&pcm[16..20], &i32::MAX.to_le_bytes());
+    assert_eq!(&pcm[12..16], &i32::MIN.to_le_bytes());
+}
+
+#[test]
+fn round_trips() {
+    let samples: Vec<f32> = (-100..100).map(|i| i as f32 / 128.0).collect();
+    for format in [PcmFormat::S16Le, PcmFormat::S24Le, PcmFormat::S32Le] {
+        let mut converter = PcmConverter::new(format);
+        let (pcm, clipped) = encode(&mut converter, &samples);
+        assert_eq!(clipped, 0);
+        // Multiples of 1/128 are exact in all the formats.
+        assert_eq!(decode(&converter, &pcm), samples, "{format:?}");
+    }
+
+    // Negative 24 bit values are sign extended.
+    let converter = PcmConverter::new(PcmFormat::S24Le);
+    assert_eq!(
+        decode(&converter, &[0xff, 0xff, 0xff, 0x00, 0x00, 0x80]),
+        vec![-1.0 / 8_388_608.0, -1.0]
+    );
+}
+
+#[test]
+fn out_of_range_samples_are_clipped() {
+    let mut converter = PcmConverter::new(PcmFormat::S16Le);
+    let (pcm, clipped) = encode(&mut converter, &[2.0, -2.0, f32::INFINITY, 0.25]);
+    assert_eq!(clipped, 3);
+    assert_eq!(
+        decode(&converter, &pcm),
+        vec![32767.0 / 32768.0, -1.0, 32767.0 / 32768.0, 0.25]
+    );
+}
+
+#[test]
+fn tpdf_dither() {
+    // Larger than a conversion block, so that several blocks are dithered.
+    let samples = vec![0.1f32; 1000];
+    let exact = 0.1 * 32768.0;
+    let mut converter = PcmConverter::new(PcmFormat::S16Le).with_dither(Dither::Tpdf);
+    let (pcm, _) = encode(&mut converter, &samples);
+    let values: Vec<f64> = pcm
+        .chunks_exact(2)
+        .map(|bytes| f64::from(i16::from_le_bytes([bytes[0], bytes[1]])))
+        .collect();
+    // The noise peaks at one step, the rounding adds at most half a step.
+    assert!(values.iter().all(|value| (value - exact).abs() <= 1.5));
+    assert!(values.iter().any(|value| *value != values[0]));
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    assert!((mean - exact).abs() < 0.1);
+
+    // The noise is reproducible.
+    let mut converter = PcmConverter::new(PcmFormat::S16Le).with_dither(Dither::Tpdf);
+    assert_eq!(encode(&mut converter, &samples).0, pcm);
+    let mut converter = converter.with_seed(42);
+    assert_ne!(encode(&mut converter, &samples).0, pcm);
+}
+
+#[test]
+fn wrapped_channels() {
+    // Split across a conversion block, as when the samples wrap around the ring buffer.
+    let samples: Vec<f32> = (0..300).map(|i| (i as f32 / 150.0 - 1.0) * 1.5).collect();
+    let bytes = to_bytes(&samples);
+    let (first, second) = bytes.split_at(97 * 4);
+    for format in [PcmFormat::S16Le, PcmFormat::S24Le, PcmFormat::S32Le] {
+        let mut converter = PcmConverter::new(format).with_dither(Dither::Tpdf);
+        let (expected, expected_clipped) = encode(&mut converter, &samples);
+
+        let mut converter = PcmConverter::new(format).with_dither(Dither::Tpdf);
+        let wrapped = ChannelSamples::new(first, second).unwrap();
+        let mut pcm = vec![0; expected.len()];
+        let clipped = converter.encode_channel(&wrapped, &mut pcm).unwrap();
+        assert_eq!(pcm, expected, "{format:?}");
+        assert_eq!(clipped, expected_clipped, "{format:?}");
+
+        let mut decoded = vec![0; bytes.len()];
+        let (first, second) = decoded.split_at_mut(97 * 4);
+        let mut wrapped = ChannelSamplesMut::new(first, second).unwrap();
+        converter.decode_channel(&pcm, &mut wrapped).unwrap();
+        assert_eq!(wrapped.as_samples().to_vec(), decode(&converter, &pcm));
+    }
+}
+
+#[test]
+fn lengths_are_checked() {
+    let bytes = to_bytes(&[0.0; 4]);
+    let samples = ChannelSamples::new(&bytes, &[]).unwrap();
+    let mut converter = PcmConverter::new(PcmFormat::S24Le);
+    assert!(matches!(
+        converter.encode_channel(&samples, &mut [0; 9]),
+        Err(Error::SampleCountMismatch {
+            expected: 4,
+            actual: 3
+        })
+    ));
+    assert!(converter.encode_channel(&samples, &mut [0; 13]).is_err());
+
+    let mut bytes = to_bytes(&[0.0; 2]);
+    let mut samples = ChannelSamplesMut::new(&mut bytes, &mut []).unwrap();
+    assert!(converter.decode_channel(&[0; 3], &mut samples).is_err());
+}
@@ -0,0 +1,75 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Tests of the `Rational` type. These do not need the MXL library.
+use std::time::Duration;
+
+use mxl::Rational;
+
+#[test]
+fn reduction_and_comparison() {
+    let rate = Rational::new(50, 2).unwrap();
+    assert_eq!(rate, Rational::FPS_25);
+    assert_eq!((rate.numerator(), rate.denominator()), (25, 1));
+    assert_eq!(Rational::new(3, -6).unwrap(), Rational::new(-1, 2).unwrap());
+    assert!(Rational::FPS_29_97 < Rational::FPS_30);
+    assert!(Rational::FPS_59_94 > Rational::FPS_50);
+    assert!(matches!(
+        Rational::new(25, 0),
+        Err(mxl::Error::InvalidRate {
+            numerator: 25,
+            denominator: 0
+        })
+    ));
+}
+
+#[test]
+fn parsing_and_display() {
+    let rate: Rational = "30000/1001".parse().unwrap();
+    assert_eq!(rate, Rational::FPS_29_97);
+    assert_eq!(rate.to_string(), "30000/1001");
+    assert_eq!("48000".parse::<Rational>().unwrap(), Rational::HZ_48000);
+    assert_eq!(" 60 / 2 ".parse::<Rational>().unwrap().to_string(), "30/1");
+    assert!(matches!(
+        "thirty".parse::<Rational>(),
+        Err(mxl::Error::InvalidRational(_))
+    ));
+    assert!(matches!(
+        "1/0".parse::<Rational>(),
+        Err(mxl::Error::InvalidRate { .. })
+    ));
+}
+
+#[test]
+fn arithmetic() {
+    let half = Rational::new(1, 2).unwrap();
+    let third = Rational::new(1, 3).unwrap();
+    assert_eq!(half.checked_add(third), Rational::new(5, 6).ok());
+    assert_eq!(half.checked_sub(third), Rational::new(1, 6).ok());
+    assert_eq!(half.checked_mul(third), Rational::new(1, 6).ok());
+    assert_eq!(half.checked_div(third), Rational::new(3, 2).ok());
+    assert_eq!(half.checked_div(Rational::from_integer(0)), None);
+    assert_eq!(
+        Rational::from_integer(i64::MAX).checked_add(Rational::from_integer(1)),
+        None
+    );
+    assert_eq!(
+        Rational::FPS_29_97.recip().unwrap(),
+        Rational::new(1001, 30000).unwrap()
+    );
+    assert!(Rational::from_integer(0).recip().is_err());
+}
+
+#[test]
+fn frame_duration() {
+    assert_eq!(
+        Rational::FPS_25.frame_duration().unwrap(),
+        Duration::from_millis(40)
+    );
+    assert_eq!(
+        Rational::FPS_29_97.frame_duration().unwrap(),
+        Duration::from_nanos(33_366_666)
+    );
+    assert!(Rational::from_integer(0).frame_duration().is_err());
+    assert!(Rational::new(-25, 1).unwrap().frame_duration().is_err());
+}
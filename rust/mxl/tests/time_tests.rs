@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Tests of the timestamp and index types. These do not need the MXL library.
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use mxl::{GrainIndex, SampleIndex, Timestamp};
+
+fn utc(seconds: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+#[test]
+fn utc_conversions_apply_leap_seconds() {
+    // 2017-01-01 00:00:00 UTC, right after the last leap second.
+    let timestamp = Timestamp::from_system_time(utc(1_483_228_800)).unwrap();
+    assert_eq!(timestamp.as_nanos(), (1_483_228_800 + 37) * 1_000_000_000);
+    assert_eq!(timestamp.tai_utc_offset(), Duration::from_secs(37));
+    assert_eq!(timestamp.to_system_time(), utc(1_483_228_800));
+
+    // 2016-12-31 23:59:59 UTC, right before it.
+    let timestamp = Timestamp::from_system_time(utc(1_483_228_799)).unwrap();
+    assert_eq!(timestamp.as_nanos(), (1_483_228_799 + 36) * 1_000_000_000);
+    assert_eq!(timestamp.to_system_time(), utc(1_483_228_799));
+
+    // 2016-12-31 23:59:60 UTC, the leap second itself, has no UTC system time of its own.
+    let leap_second = Timestamp::from_nanos((1_483_228_799 + 37) * 1_000_000_000);
+    assert_eq!(leap_second.to_system_time(), utc(1_483_228_800));
+
+    // Before 1972, the offset of 1972 is used.
+    let timestamp = Timestamp::from_system_time(UNIX_EPOCH).unwrap();
+    assert_eq!(timestamp.since_epoch(), Duration::from_secs(10));
+    assert_eq!(timestamp.to_system_time(), UNIX_EPOCH);
+
+    assert!(matches!(
+        Timestamp::from_system_time(UNIX_EPOCH - Duration::from_secs(1)),
+        Err(mxl::Error::TimestampOutOfRange)
+    ));
+}
+
+#[test]
+fn round_trip_through_system_time() {
+    let now = SystemTime::now();
+    let timestamp = Timestamp::try_from(now).unwrap();
+    assert_eq!(SystemTime::from(timestamp), now);
+    assert_eq!(timestamp.tai_utc_offset(), Duration::from_secs(37));
+}
+
+#[test]
+fn timestamp_arithmetic() {
+    let timestamp = Timestamp::from_nanos(1_500_000_000);
+    assert_eq!(timestamp.to_string(), "1:500000000");
+    assert_eq!(
+        timestamp + Duration::from_millis(500),
+        Timestamp::from_nanos(2_000_000_000)
+    );
+    assert_eq!(timestamp - Timestamp::EPOCH, Duration::from_millis(1500));
+    assert_eq!(Timestamp::EPOCH - timestamp, Duration::ZERO);
+    assert_eq!(Timestamp::EPOCH.checked_sub(Duration::from_nanos(1)), None);
+    assert_eq!(
+        Timestamp::from_nanos(u64::MAX).checked_add(Duration::from_nanos(1)),
+        None
+    );
+}
+
+#[test]
+fn index_arithmetic() {
+    let mut index = GrainIndex::new(10);
+    index += 5;
+    assert_eq!(index, GrainIndex::from(15));
+    assert_eq!(u64::from(index - 3), 12);
+    assert_eq!(index.to_string(), "15");
+    assert_eq!(index.saturating_distance_since(GrainIndex::new(20)), 0);
+    assert_eq!(GrainIndex::new(u64::MAX).checked_add(1), None);
+    assert_eq!(SampleIndex::new(0).checked_sub(1), None);
+    assert_eq!(
+        SampleIndex::new(48000).saturating_distance_since(SampleIndex::new(47000)),
+        1000
+    );
+}
+
+#[test]
+fn arithmetic_saturates() {
+    assert_eq!(
+        Timestamp::from_nanos(u64::MAX - 1) + Duration::from_secs(1),
+        Timestamp::from_nanos(u64::MAX)
+    );
+    assert_eq!(
+        Timestamp::from_nanos(1) - Duration::from_secs(1),
+        Timestamp::EPOCH
+    );
+    assert_eq!(GrainIndex::new(u64::MAX) + 1, GrainIndex::new(u64::MAX));
+    assert_eq!(SampleIndex::new(1) - 2, SampleIndex::new(0));
+}
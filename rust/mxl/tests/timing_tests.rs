@@ -0,0 +1,260 @@
+// SPDX-FileCopyrightText: 2025 2025 Contributors to the Media eXchange Layer project.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Tests of the timing model. These do not need the MXL library, the results are cross-checked
+/// against the library in the basic tests.
+use std::time::Duration;
+
+use mxl::{
+    GrainIndex, GrainRate, Rational, SampleIndex, SampleRate, Timestamp,
+    timing::{self, GrainRing, RateMapping, SampleRing},
+};
+
+#[test]
+fn conversions_round_to_nearest() {
+    let rate = GrainRate::new(Rational::FPS_29_97).unwrap();
+    // 1001/30 ms per grain: 33366666.67 ns.
+    let index = timing::timestamp_to_index(Timestamp::EPOCH, &rate).unwrap();
+    assert_eq!(index, GrainIndex::new(0));
+    assert_eq!(
+        timing::index_to_timestamp(GrainIndex::new(1), &rate).unwrap(),
+        Timestamp::from_nanos(33_366_667)
+    );
+    assert_eq!(
+        timing::index_to_timestamp(GrainIndex::new(2), &rate).unwrap(),
+        Timestamp::from_nanos(66_733_333)
+    );
+    let index = timing::timestamp_to_index(Timestamp::from_nanos(16_683_333), &rate).unwrap();
+    assert_eq!(index, GrainIndex::new(0));
+    let index = timing::timestamp_to_index(Timestamp::from_nanos(16_683_334), &rate).unwrap();
+    assert_eq!(index, GrainIndex::new(1));
+    assert_eq!(
+        timing::index_duration(GrainIndex::new(0), &rate).unwrap(),
+        Duration::from_nanos(33_366_667)
+    );
+    assert_eq!(
+        timing::index_duration(GrainIndex::new(1), &rate).unwrap(),
+        Duration::from_nanos(33_366_666)
+    );
+
+    let rate = SampleRate::new(Rational::HZ_48000).unwrap();
+    let index = timing::timestamp_to_index(Timestamp::from_nanos(1_000_000_000), &rate).unwrap();
+    assert_eq!(index, SampleIndex::new(48_000));
+}
+
+#[test]
+fn round_trips_at_common_rates() {
+    let now = Timestamp::from_system_time(std::time::SystemTime::now()).unwrap();
+    for rate in [
+        Rational::FPS_23_98,
+        Rational::FPS_25,
+        Rational::FPS_29_97,
+        Rational::FPS_50,
+        Rational::FPS_59_94,
+        Rational::HZ_48000,
+    ] {
+        let rate = GrainRate::new(rate).unwrap();
+        let index = timing::timestamp_to_index(now, &rate).unwrap();
+        for index in [index, index + 1, index + 1001] {
+            let timestamp = timing::index_to_timestamp(index, &rate).unwrap();
+            assert_eq!(timing::timestamp_to_index(timestamp, &rate).unwrap(), index);
+        }
+    }
+}
+
+#[test]
+fn duration_until_index() {
+    let rate = GrainRate::new(Rational::FPS_50).unwrap();
+    let now = Timestamp::from_nanos(1_010_000_000);
+    assert_eq!(
+        timing::duration_until_index(GrainIndex::new(51), &rate, now).unwrap(),
+        Duration::from_millis(10)
+    );
+    assert_eq!(
+        timing::duration_until_index(GrainIndex::new(50), &rate, now).unwrap(),
+        Duration::ZERO
+    );
+    assert_eq!(
+        timing::duration_until_index(GrainIndex::new(51), &rate, Timestamp::EPOCH).unwrap(),
+        Duration::ZERO
+    );
+}
+
+#[test]
+fn invalid_rates_and_overflows() {
+    for rate in [Rational::from_integer(0), Rational::new(-25, 1).unwrap()] {
+        assert!(matches!(
+            GrainRate::new(rate),
+            Err(mxl::Error::InvalidRate { .. })
+        ));
+        assert!(matches!(
+            SampleRate::new(rate),
+            Err(mxl::Error::InvalidRate { .. })
+        ));
+    }
+    let rate = GrainRate::new(Rational::FPS_50).unwrap();
+    assert_eq!(Rational::from(rate), Rational::FPS_50);
+    assert!(matches!(
+        timing::index_to_timestamp(GrainIndex::new(u64::MAX), &rate),
+        Err(mxl::Error::TimestampOutOfRange)
+    ));
+}
+
+#[test]
+fn grain_ring() {
+    // The example of docs/timing.md: 50 grains per second and 100 ms of history.
+    let rate = GrainRate::new(Rational::FPS_50).unwrap();
+    let ring = GrainRing::for_history(Duration::from_millis(100), &rate).unwrap();
+    assert_eq!(ring.grain_count(), 5);
+    assert_eq!(ring.slot(GrainIndex::new(7)), 2);
+    assert_eq!(ring.oldest(GrainIndex::new(7)), GrainIndex::new(3));
+    assert_eq!(ring.oldest(GrainIndex::new(2)), GrainIndex::new(0));
+    assert_eq!(
+        ring.locate(GrainIndex::new(7), GrainIndex::new(3)).unwrap(),
+        3
+    );
+    assert_eq!(
+        ring.locate(GrainIndex::new(7), GrainIndex::new(5)).unwrap(),
+        0
+    );
+    assert!(matches!(
+        ring.locate(GrainIndex::new(7), GrainIndex::new(2)),
+        Err(mxl::Error::OutOfRangeTooLate)
+    ));
+    assert!(matches!(
+        ring.locate(GrainIndex::new(7), GrainIndex::new(8)),
+        Err(mxl::Error::OutOfRangeTooEarly)
+    ));
+
+    assert!(matches!(
+        GrainRing::for_history(Duration::from_millis(10), &rate),
+        Err(mxl::Error::InvalidOptions(_))
+    ));
+}
+
+#[test]
+fn sample_ring() {
+    // 100 ms at 48 kHz is 4800 samples, rounded up to pages of 1024 float samples.
+    let rate = SampleRate::new(Rational::HZ_48000).unwrap();
+    let ring = SampleRing::for_history(Duration::from_millis(100), &rate, 4).unwrap();
+    assert_eq!(ring.buffer_length(), 5120);
+    assert_eq!(ring.offset(SampleIndex::new(5121)), 1);
+    assert_eq!(
+        ring.oldest(SampleIndex::new(10_000)),
+        SampleIndex::new(7440)
+    );
+
+    let head = SampleIndex::new(10_300);
+    assert_eq!(
+        ring.locate(head, SampleIndex::new(10_000), 100).unwrap(),
+        (4780..4880, 0..0)
+    );
+    // Wraps around the end of the buffers.
+    assert_eq!(
+        ring.locate(head, SampleIndex::new(10_260), 100).unwrap(),
+        (5040..5120, 0..20)
+    );
+    assert!(matches!(
+        ring.locate(head, SampleIndex::new(10_301), 1),
+        Err(mxl::Error::OutOfRangeTooEarly)
+    ));
+    assert!(matches!(
+        ring.locate(head, SampleIndex::new(7800), 100),
+        Err(mxl::Error::OutOfRangeTooLate)
+    ));
+}
+
+/// Rates of the mapping properties.
+const RATES: [Rational; 8] = [
+    Rational::FPS_23_98,
+    Rational::FPS_25,
+    Rational::FPS_29_97,
+    Rational::FPS_50,
+    Rational::FPS_59_94,
+    Rational::FPS_60,
+    Rational::HZ_48000,
+    Rational::HZ_96000,
+];
+
+/// Reproducible pseudorandom values for the property tests, from a xorshift64 generator.
+struct Cases(u64);
+
+impl Cases {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound
+    }
+
+    fn rate<I: mxl::MediaIndex>(&mut self) -> mxl::EditRate<I> {
+        mxl::EditRate::new(RATES[self.next(RATES.len() as u64) as usize]).unwrap()
+    }
+}
+
+#[test]
+fn rate_mapping_cadence() {
+    let mapping = RateMapping::new(
+        GrainRate::new(Rational::FPS_29_97).unwrap(),
+        SampleRate::new(Rational::HZ_48000).unwrap(),
+    );
+    let first = GrainIndex::new(5 * 123_456_789);
+    let counts: Vec<u64> = (0..5).map(|i| mapping.count(first + i).unwrap()).collect();
+    assert_eq!(counts, [1602, 1602, 1601, 1602, 1601]);
+    let range = mapping.map_range(first..first + 5).unwrap();
+    assert_eq!(range.start, SampleIndex::new(123_456_789 * 8008));
+    assert_eq!(range.end.saturating_distance_since(range.start), 8008);
+
+    // Samples begin during a single grain, or none for the slower rate.
+    let inverse = mapping.inverse();
+    assert_eq!(inverse.source_rate().as_rational(), Rational::HZ_48000);
+    let grains = inverse.map(range.start + 1).unwrap();
+    assert!(grains.is_empty());
+    let grains = inverse.map(range.start).unwrap();
+    assert_eq!(grains, first..first + 1);
+    assert_eq!(inverse.containing(range.start + 1602).unwrap(), first + 1);
+
+    assert!(matches!(
+        mapping.map(GrainIndex::new(u64::MAX)),
+        Err(mxl::Error::TimestampOutOfRange)
+    ));
+}
+
+#[test]
+fn rate_mapping_properties() {
+    let mut cases = Cases(0x2545_f491_4f6c_dd1d);
+    for _ in 0..10_000 {
+        let mapping = RateMapping::<GrainIndex, SampleIndex>::new(cases.rate(), cases.rate());
+        // Indexes up to decades at the video rates.
+        let index = GrainIndex::new(cases.next(1 << 36));
+        let span = cases.next(100_000);
+
+        // Consecutive indexes map to contiguous ranges, so spans never drift.
+        let range = mapping.map(index).unwrap();
+        let next = mapping.map(index + 1).unwrap();
+        assert_eq!(range.end, next.start);
+        let spanned = mapping.map_range(index..index + span).unwrap();
+        assert_eq!(spanned.start, range.start);
+        let end = mapping.map(index + span).unwrap();
+        assert_eq!(spanned.end, end.start);
+
+        // Everything mapped begins during the index it has been mapped from.
+        let inverse = mapping.inverse();
+        if !range.is_empty() {
+            for mapped in [range.start, range.end - 1] {
+                assert_eq!(inverse.containing(mapped).unwrap(), index);
+            }
+        }
+        let start = timing::index_to_timestamp(index, &mapping.source_rate()).unwrap();
+        let mapped_start = timing::index_to_timestamp(range.start, &mapping.target_rate()).unwrap();
+        assert!(mapped_start >= start);
+        if let Some(previous) = range.start.checked_sub(1) {
+            let previous = timing::index_to_timestamp(previous, &mapping.target_rate()).unwrap();
+            assert!(previous <= start);
+        }
+
+        // Nothing begins during the index when the next grain or sample begins after it.
+        let back = inverse.containing(range.start).unwrap();
+        assert_eq!(range.is_empty(), back > index);
+    }
+}